@@ -0,0 +1,468 @@
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::net::socket::{Address, DEFAULT_ADDRESS};
+use crate::simulation::metrics::DEFAULT_METRICS_INTERVAL;
+use crate::station::RobotType;
+
+/// Durée d'une simulation sans interface quand elle n'est pas précisée
+pub const DEFAULT_RUN_TICKS: u64 = 1000;
+/// Graines jouées par une expérience quand elles ne sont pas précisées
+pub const DEFAULT_EXPERIMENT_SEEDS: std::ops::Range<u64> = 0..10;
+
+pub const USAGE: &str = "\
+usage: robot_swarm [--events FILE] [--record FILE] [--replay FILE] [METRICS]
+       robot_swarm run [--seed N] [--ticks N] [--robots TYPE,...] [--events FILE] [METRICS]
+       robot_swarm experiment [--configs FILE] [--seeds SEEDS] [--ticks N] [--threads N]
+       robot_swarm station [--listen ADDR] [--seed N] [--events FILE] [METRICS]
+       robot_swarm robot [--connect ADDR] [--type TYPE] [--events FILE]
+
+Without a command, runs the interactive simulation.
+run simulates N ticks (default 1000) without interface, starting with one
+robot of each listed TYPE (default one of each)
+experiment runs every configuration of FILE (a JSON list, default built-in
+presets) on every seed for N ticks (default 1000) and compares the outcomes.
+SEEDS is a list like 1,2,10..20 (default 0..10)
+ADDR is host:port (default 127.0.0.1:7878) or unix:PATH
+TYPE is explorer, energy-collector, miner or scientist (default explorer)
+--events writes the simulation events to FILE as JSON lines
+--record saves the seed and every command to FILE; --replay re-runs it
+METRICS is --metrics FILE [--metrics-every N]: samples the simulation every
+N ticks (default 10) to FILE, as CSV if it ends in .csv, else JSON lines";
+
+/// Fichier de mesures demandé en ligne de commande
+#[derive(Debug, Clone, PartialEq)]
+pub struct MetricsOutput {
+    pub path: PathBuf,
+    /// Ticks entre deux relevés
+    pub every: u64,
+}
+
+/// Mode de lancement choisi en ligne de commande
+#[derive(Debug, Clone, PartialEq)]
+pub enum Command {
+    Interactive {
+        events: Option<PathBuf>,
+        record: Option<PathBuf>,
+        /// Partie enregistrée à rejouer au lieu d'une nouvelle partie
+        replay: Option<PathBuf>,
+        metrics: Option<MetricsOutput>,
+    },
+    Help,
+    /// Simulation sans interface, le temps d'un nombre de ticks donné
+    Run {
+        seed: Option<u64>,
+        ticks: u64,
+        robots: Vec<RobotType>,
+        events: Option<PathBuf>,
+        metrics: Option<MetricsOutput>,
+    },
+    /// Comparaison de configurations sur plusieurs graines, sans interface
+    Experiment {
+        /// Configurations à comparer, les préréglages intégrés sinon
+        configs: Option<PathBuf>,
+        seeds: Vec<u64>,
+        ticks: u64,
+        /// Fils d'exécution, autant que de cœurs disponibles sinon
+        threads: Option<usize>,
+    },
+    /// Station sans interface, à l'écoute des processus robots
+    Station {
+        listen: Address,
+        seed: Option<u64>,
+        events: Option<PathBuf>,
+        metrics: Option<MetricsOutput>,
+    },
+    /// Robot unique connecté à une station distante
+    Robot {
+        connect: Address,
+        robot_type: RobotType,
+        events: Option<PathBuf>,
+    },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CliError {
+    UnknownCommand(String),
+    UnknownFlag(String),
+    MissingValue(String),
+    InvalidValue { flag: String, value: String },
+}
+
+impl fmt::Display for CliError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CliError::UnknownCommand(command) => write!(f, "unknown command '{command}'"),
+            CliError::UnknownFlag(flag) => write!(f, "unknown option '{flag}'"),
+            CliError::MissingValue(flag) => write!(f, "option '{flag}' needs a value"),
+            CliError::InvalidValue { flag, value } => {
+                write!(f, "invalid value '{value}' for option '{flag}'")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CliError {}
+
+/// Analyse les arguments, sans le nom du programme
+pub fn parse_args<I: IntoIterator<Item = String>>(args: I) -> Result<Command, CliError> {
+    let mut args = args.into_iter().peekable();
+    // Sans sous-commande, les options s'appliquent à la simulation interactive
+    let interactive = args
+        .peek()
+        .is_none_or(|arg| arg.starts_with("--") && arg != "--help");
+    let command = if interactive {
+        "interactive".to_string()
+    } else {
+        args.next().unwrap_or_default()
+    };
+
+    match command.as_str() {
+        "-h" | "--help" | "help" => Ok(Command::Help),
+        "interactive" => {
+            let (mut events, mut record, mut replay) = (None, None, None);
+            let mut metrics = MetricsFlags::default();
+            while let Some(flag) = args.next() {
+                let value = flag_value(&flag, args.next())?;
+                match flag.as_str() {
+                    "--events" => events = Some(PathBuf::from(value)),
+                    "--record" => record = Some(PathBuf::from(value)),
+                    "--replay" => replay = Some(PathBuf::from(value)),
+                    _ => metrics.parse(flag, value)?,
+                }
+            }
+            Ok(Command::Interactive {
+                events,
+                record,
+                replay,
+                metrics: metrics.output(),
+            })
+        }
+        "run" => {
+            let mut seed = None;
+            let mut ticks = DEFAULT_RUN_TICKS;
+            let mut robots = RobotType::ALL.to_vec();
+            let mut events = None;
+            let mut metrics = MetricsFlags::default();
+            while let Some(flag) = args.next() {
+                let value = flag_value(&flag, args.next())?;
+                match flag.as_str() {
+                    "--seed" => seed = Some(parse_value(&flag, &value)?),
+                    "--ticks" => ticks = parse_value(&flag, &value)?,
+                    "--robots" => robots = parse_robot_types(&flag, &value)?,
+                    "--events" => events = Some(PathBuf::from(value)),
+                    _ => metrics.parse(flag, value)?,
+                }
+            }
+            Ok(Command::Run {
+                seed,
+                ticks,
+                robots,
+                events,
+                metrics: metrics.output(),
+            })
+        }
+        "experiment" => {
+            let mut configs = None;
+            let mut seeds = DEFAULT_EXPERIMENT_SEEDS.collect();
+            let mut ticks = DEFAULT_RUN_TICKS;
+            let mut threads = None;
+            while let Some(flag) = args.next() {
+                let value = flag_value(&flag, args.next())?;
+                match flag.as_str() {
+                    "--configs" => configs = Some(PathBuf::from(value)),
+                    "--seeds" => seeds = parse_seeds(&flag, &value)?,
+                    "--ticks" => ticks = parse_value(&flag, &value)?,
+                    "--threads" => match parse_value(&flag, &value)? {
+                        0 => return Err(CliError::InvalidValue { flag, value }),
+                        count => threads = Some(count),
+                    },
+                    _ => return Err(CliError::UnknownFlag(flag)),
+                }
+            }
+            Ok(Command::Experiment {
+                configs,
+                seeds,
+                ticks,
+                threads,
+            })
+        }
+        "station" => {
+            let mut listen = default_address();
+            let mut seed = None;
+            let mut events = None;
+            let mut metrics = MetricsFlags::default();
+            while let Some(flag) = args.next() {
+                let value = flag_value(&flag, args.next())?;
+                match flag.as_str() {
+                    "--listen" => listen = parse_value(&flag, &value)?,
+                    "--seed" => seed = Some(parse_value(&flag, &value)?),
+                    "--events" => events = Some(PathBuf::from(value)),
+                    _ => metrics.parse(flag, value)?,
+                }
+            }
+            Ok(Command::Station {
+                listen,
+                seed,
+                events,
+                metrics: metrics.output(),
+            })
+        }
+        "robot" => {
+            let mut connect = default_address();
+            let mut robot_type = RobotType::Explorer;
+            let mut events = None;
+            while let Some(flag) = args.next() {
+                let value = flag_value(&flag, args.next())?;
+                match flag.as_str() {
+                    "--connect" => connect = parse_value(&flag, &value)?,
+                    "--events" => events = Some(PathBuf::from(value)),
+                    "--type" => {
+                        robot_type = RobotType::from_name(&value).ok_or(CliError::InvalidValue {
+                            flag: flag.clone(),
+                            value: value.clone(),
+                        })?
+                    }
+                    _ => return Err(CliError::UnknownFlag(flag)),
+                }
+            }
+            Ok(Command::Robot {
+                connect,
+                robot_type,
+                events,
+            })
+        }
+        _ => Err(CliError::UnknownCommand(command)),
+    }
+}
+
+/// Options de mesures communes aux modes qui font tourner la simulation
+#[derive(Debug, Default)]
+struct MetricsFlags {
+    path: Option<PathBuf>,
+    every: Option<u64>,
+}
+
+impl MetricsFlags {
+    /// Reconnaît `--metrics` et `--metrics-every`, refuse toute autre option
+    fn parse(&mut self, flag: String, value: String) -> Result<(), CliError> {
+        match flag.as_str() {
+            "--metrics" => self.path = Some(PathBuf::from(value)),
+            "--metrics-every" => match parse_value(&flag, &value)? {
+                0 => return Err(CliError::InvalidValue { flag, value }),
+                every => self.every = Some(every),
+            },
+            _ => return Err(CliError::UnknownFlag(flag)),
+        }
+        Ok(())
+    }
+
+    fn output(self) -> Option<MetricsOutput> {
+        let every = self.every.unwrap_or(DEFAULT_METRICS_INTERVAL);
+        self.path.map(|path| MetricsOutput { path, every })
+    }
+}
+
+/// Liste de types de robots séparés par des virgules
+fn parse_robot_types(flag: &str, value: &str) -> Result<Vec<RobotType>, CliError> {
+    value
+        .split(',')
+        .filter(|name| !name.is_empty())
+        .map(|name| {
+            RobotType::from_name(name).ok_or_else(|| CliError::InvalidValue {
+                flag: flag.to_string(),
+                value: name.to_string(),
+            })
+        })
+        .collect()
+}
+
+/// Graines séparées par des virgules, chacune seule ou en plage `début..fin`
+fn parse_seeds(flag: &str, value: &str) -> Result<Vec<u64>, CliError> {
+    let invalid = || CliError::InvalidValue {
+        flag: flag.to_string(),
+        value: value.to_string(),
+    };
+    let mut seeds = Vec::new();
+    for part in value.split(',').filter(|part| !part.is_empty()) {
+        match part.split_once("..") {
+            Some((start, end)) => {
+                let start: u64 = start.parse().map_err(|_| invalid())?;
+                let end: u64 = end.parse().map_err(|_| invalid())?;
+                seeds.extend(start..end);
+            }
+            None => seeds.push(part.parse().map_err(|_| invalid())?),
+        }
+    }
+    if seeds.is_empty() {
+        return Err(invalid());
+    }
+    Ok(seeds)
+}
+
+fn default_address() -> Address {
+    DEFAULT_ADDRESS.parse().expect("default address is valid")
+}
+
+fn flag_value(flag: &str, value: Option<String>) -> Result<String, CliError> {
+    if !flag.starts_with("--") {
+        return Err(CliError::UnknownFlag(flag.to_string()));
+    }
+    value.ok_or_else(|| CliError::MissingValue(flag.to_string()))
+}
+
+fn parse_value<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, CliError> {
+    value.parse().map_err(|_| CliError::InvalidValue {
+        flag: flag.to_string(),
+        value: value.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(args: &[&str]) -> Result<Command, CliError> {
+        parse_args(args.iter().map(|arg| arg.to_string()))
+    }
+
+    #[test]
+    fn test_parse_modes() {
+        assert_eq!(
+            parse(&[]),
+            Ok(Command::Interactive {
+                events: None,
+                record: None,
+                replay: None,
+                metrics: None,
+            })
+        );
+        assert_eq!(
+            parse(&["--events", "events.jsonl", "--replay", "bug.replay"]),
+            Ok(Command::Interactive {
+                events: Some(PathBuf::from("events.jsonl")),
+                record: None,
+                replay: Some(PathBuf::from("bug.replay")),
+                metrics: None,
+            })
+        );
+        assert_eq!(parse(&["--help"]), Ok(Command::Help));
+        assert_eq!(
+            parse(&["station", "--listen", "127.0.0.1:9000", "--seed", "7"]),
+            Ok(Command::Station {
+                listen: Address::Tcp("127.0.0.1:9000".to_string()),
+                seed: Some(7),
+                events: None,
+                metrics: None,
+            })
+        );
+        assert_eq!(
+            parse(&["robot", "--type", "miner"]),
+            Ok(Command::Robot {
+                connect: default_address(),
+                robot_type: RobotType::Miner,
+                events: None,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        assert_eq!(
+            parse(&["fly"]),
+            Err(CliError::UnknownCommand("fly".to_string()))
+        );
+        assert_eq!(
+            parse(&["robot", "--connect"]),
+            Err(CliError::MissingValue("--connect".to_string()))
+        );
+        assert_eq!(
+            parse(&["robot", "--type", "tank"]),
+            Err(CliError::InvalidValue {
+                flag: "--type".to_string(),
+                value: "tank".to_string(),
+            })
+        );
+        assert_eq!(
+            parse(&["station", "--port", "1"]),
+            Err(CliError::UnknownFlag("--port".to_string()))
+        );
+        assert_eq!(
+            parse(&["experiment", "--seeds", "5..5"]),
+            Err(CliError::InvalidValue {
+                flag: "--seeds".to_string(),
+                value: "5..5".to_string(),
+            })
+        );
+        assert_eq!(
+            parse(&["run", "--metrics-every", "0"]),
+            Err(CliError::InvalidValue {
+                flag: "--metrics-every".to_string(),
+                value: "0".to_string(),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_experiment() {
+        assert_eq!(
+            parse(&["experiment", "--seeds", "1,4..7", "--ticks", "200"]),
+            Ok(Command::Experiment {
+                configs: None,
+                seeds: vec![1, 4, 5, 6],
+                ticks: 200,
+                threads: None,
+            })
+        );
+        assert_eq!(
+            parse(&["experiment", "--configs", "sweep.json", "--threads", "2"]),
+            Ok(Command::Experiment {
+                configs: Some(PathBuf::from("sweep.json")),
+                seeds: DEFAULT_EXPERIMENT_SEEDS.collect(),
+                ticks: DEFAULT_RUN_TICKS,
+                threads: Some(2),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_headless_run_with_metrics() {
+        assert_eq!(
+            parse(&[
+                "run",
+                "--seed",
+                "3",
+                "--ticks",
+                "500",
+                "--robots",
+                "explorer,miner",
+                "--metrics",
+                "run.csv",
+                "--metrics-every",
+                "25",
+            ]),
+            Ok(Command::Run {
+                seed: Some(3),
+                ticks: 500,
+                robots: vec![RobotType::Explorer, RobotType::Miner],
+                events: None,
+                metrics: Some(MetricsOutput {
+                    path: PathBuf::from("run.csv"),
+                    every: 25,
+                }),
+            })
+        );
+        assert_eq!(
+            parse(&["--metrics", "metrics.jsonl"]),
+            Ok(Command::Interactive {
+                events: None,
+                record: None,
+                replay: None,
+                metrics: Some(MetricsOutput {
+                    path: PathBuf::from("metrics.jsonl"),
+                    every: DEFAULT_METRICS_INTERVAL,
+                }),
+            })
+        );
+    }
+}
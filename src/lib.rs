@@ -1,3 +1,7 @@
+pub mod cli;
 pub mod map;
+pub mod net;
 pub mod robot;
-pub mod station;
\ No newline at end of file
+pub mod simulation;
+pub mod station;
+pub mod ui;
@@ -1,12 +1,15 @@
 use anyhow::Result;
 use crossterm::{
-    event::{self, Event, KeyCode},
+    event::{
+        self, DisableMouseCapture, EnableMouseCapture, Event, KeyCode, MouseButton,
+        MouseEventKind,
+    },
     execute,
     terminal::{disable_raw_mode, enable_raw_mode, EnterAlternateScreen, LeaveAlternateScreen},
 };
 use ratatui::{
     backend::CrosstermBackend,
-    layout::{Constraint, Direction, Layout},
+    layout::{Constraint, Direction, Layout, Rect},
     style::{Color, Style},
     text::{Line, Span},
     widgets::{Block, Borders, Paragraph},
@@ -14,116 +17,464 @@ use ratatui::{
 };
 use std::{
     io,
+    path::Path,
     sync::{Arc, Mutex},
     thread,
-    time::Duration,
+    time::{Duration, Instant},
 };
 
-mod map;
-mod robot;
-
-use map::map_widget::MapWidget;
-use robot::{HardwareModule, Position, Robot, State};
-
-mod station;
-use station::{RobotType, Station};
+use robot_swarm::cli::{parse_args, Command, MetricsOutput, USAGE};
+use robot_swarm::robot::communication::LinkStatus;
+use robot_swarm::map::map_widget::MapWidget;
+use robot_swarm::robot::Position;
+use robot_swarm::net::client::RobotClient;
+use robot_swarm::net::server::StationServer;
+use robot_swarm::net::socket::Address;
+use robot_swarm::simulation::clock::{SimClock, BASE_TICK_INTERVAL};
+use robot_swarm::simulation::command::UserCommand;
+use robot_swarm::simulation::events::JsonLinesSink;
+use robot_swarm::simulation::experiment::{comparison_table, run_experiment, ExperimentConfig};
+use robot_swarm::simulation::metrics::MetricsRecorder;
+use robot_swarm::simulation::replay::{Replay, ReplayPlayer, ReplayRecorder};
+use robot_swarm::simulation::{SimConfig, Simulation};
+use robot_swarm::station::RobotType;
+use robot_swarm::ui::{
+    build::{BuildPanel, BuildWidget},
+    events::{EventsPanel, EventsWidget},
+    inspector::{InspectorPanel, InspectorWidget},
+    orders::{OrderMenu, OrderMenuWidget},
+    research::{ResearchPanel, ResearchWidget},
+    stats::StatsWidget,
+    tile::TileInfoWidget,
+    workshop::{WorkshopPanel, WorkshopWidget},
+    SidePanel,
+};
 
 const MAP_WIDTH: u32 = 200;
 const MAP_HEIGHT: u32 = 100;
+/// Ticks entre deux lignes d'état des modes sans interface
+const STATUS_INTERVAL_TICKS: u64 = 50;
+/// Temps passé à enchaîner les ticks en vitesse maximale avant de laisser
+/// l'interface accéder à la simulation
+const MAX_SPEED_SLICE: Duration = Duration::from_millis(20);
+/// Attente entre deux vérifications de l'horloge quand la simulation est en pause
+const PAUSE_POLL_INTERVAL: Duration = Duration::from_millis(10);
 
 fn main() -> Result<()> {
+    match parse_args(std::env::args().skip(1)) {
+        Ok(Command::Interactive {
+            events,
+            record,
+            replay,
+            metrics,
+        }) => run_interactive(
+            events.as_deref(),
+            record.as_deref(),
+            replay.as_deref(),
+            metrics.as_ref(),
+        ),
+        Ok(Command::Help) => {
+            println!("{USAGE}");
+            Ok(())
+        }
+        Ok(Command::Run {
+            seed,
+            ticks,
+            robots,
+            events,
+            metrics,
+        }) => run_headless(
+            seed.unwrap_or_else(rand::random),
+            ticks,
+            &robots,
+            events.as_deref(),
+            metrics.as_ref(),
+        ),
+        Ok(Command::Experiment {
+            configs,
+            seeds,
+            ticks,
+            threads,
+        }) => run_experiments(configs.as_deref(), &seeds, ticks, threads),
+        Ok(Command::Station {
+            listen,
+            seed,
+            events,
+            metrics,
+        }) => run_station(
+            &listen,
+            seed.unwrap_or_else(rand::random),
+            events.as_deref(),
+            metrics.as_ref(),
+        ),
+        Ok(Command::Robot {
+            connect,
+            robot_type,
+            events,
+        }) => run_robot(&connect, robot_type, events.as_deref()),
+        Err(error) => {
+            eprintln!("{error}\n\n{USAGE}");
+            std::process::exit(2);
+        }
+    }
+}
+
+/// Ajoute un fichier JSON lines aux destinataires des événements
+fn log_events_to(sim: &mut Simulation, path: Option<&Path>) -> Result<()> {
+    if let Some(path) = path {
+        sim.events.add_sink(JsonLinesSink::create(path)?);
+    }
+    Ok(())
+}
+
+/// Relève l'état de la simulation dans un fichier CSV ou JSON lines
+fn export_metrics_to(sim: &mut Simulation, metrics: Option<&MetricsOutput>) -> Result<()> {
+    if let Some(metrics) = metrics {
+        sim.start_metrics(MetricsRecorder::create(&metrics.path, metrics.every)?);
+    }
+    Ok(())
+}
+
+/// Ferme le fichier de mesures ; une écriture ratée fait échouer la commande
+fn finish_metrics(sim: &mut Simulation, metrics: Option<&MetricsOutput>) -> Result<()> {
+    if let (Err(error), Some(metrics)) = (sim.finish_metrics(), metrics) {
+        anyhow::bail!(
+            "failed to write metrics to {}: {error}",
+            metrics.path.display()
+        );
+    }
+    Ok(())
+}
+
+/// Simulation sans interface ni attente entre les ticks, pour les analyses
+fn run_headless(
+    seed: u64,
+    ticks: u64,
+    robots: &[RobotType],
+    events: Option<&Path>,
+    metrics: Option<&MetricsOutput>,
+) -> Result<()> {
+    let mut sim = Simulation::with_config(&SimConfig {
+        width: MAP_WIDTH,
+        height: MAP_HEIGHT,
+        seed,
+        ..SimConfig::default()
+    });
+    log_events_to(&mut sim, events)?;
+    for robot_type in robots {
+        if let Err(error) = sim.create_robot(*robot_type) {
+            eprintln!("{} not built: {error}", robot_type.name());
+        }
+    }
+    export_metrics_to(&mut sim, metrics)?;
+    println!("Running {ticks} ticks (seed {seed})");
+
+    for _ in 0..ticks {
+        sim.step();
+
+        if sim.tick.is_multiple_of(STATUS_INTERVAL_TICKS) {
+            let (energy, minerals, science) = sim.station.get_discovered_resource_counts();
+            println!(
+                "tick {} | robots: {} | explored tiles: {} | discovered E/M/S: {energy}/{minerals}/{science}",
+                sim.tick,
+                sim.robots.len(),
+                sim.station.knowledge.explored_count(),
+            );
+        }
+    }
+
+    let resources = &sim.station.resources;
+    println!(
+        "Station stock after {} ticks: {} energy, {} minerals, {} scientific data",
+        sim.tick, resources.energy, resources.minerals, resources.scientific_data
+    );
+    finish_metrics(&mut sim, metrics)
+}
+
+/// Compare des configurations sur plusieurs graines et affiche le tableau des résultats
+fn run_experiments(
+    configs: Option<&Path>,
+    seeds: &[u64],
+    ticks: u64,
+    threads: Option<usize>,
+) -> Result<()> {
+    let configs = match configs {
+        Some(path) => ExperimentConfig::load(path)?,
+        None => ExperimentConfig::presets(),
+    };
+    let threads = threads
+        .unwrap_or_else(|| thread::available_parallelism().map_or(1, |threads| threads.get()));
+    println!(
+        "Running {} configurations x {} seeds for {ticks} ticks on {threads} threads",
+        configs.len(),
+        seeds.len()
+    );
+
+    let started = Instant::now();
+    let outcomes = run_experiment(&configs, seeds, ticks, threads);
+    println!(
+        "{} runs in {:.1}s\n",
+        outcomes.len(),
+        started.elapsed().as_secs_f64()
+    );
+    print!("{}", comparison_table(&configs, &outcomes));
+    Ok(())
+}
+
+/// Station sans interface : les robots sont des processus connectés par socket
+fn run_station(
+    listen: &Address,
+    seed: u64,
+    events: Option<&Path>,
+    metrics: Option<&MetricsOutput>,
+) -> Result<()> {
+    let mut sim = Simulation::new(MAP_WIDTH, MAP_HEIGHT, seed);
+    log_events_to(&mut sim, events)?;
+    export_metrics_to(&mut sim, metrics)?;
+    let mut server = StationServer::bind(listen)?;
+    println!("Station listening on {} (seed {seed})", server.address());
+
+    loop {
+        thread::sleep(BASE_TICK_INTERVAL);
+        sim.step();
+        server.exchange(&mut sim);
+
+        if sim.tick.is_multiple_of(STATUS_INTERVAL_TICKS) {
+            let (energy, minerals, science) = sim.station.get_discovered_resource_counts();
+            println!(
+                "tick {} | robots connected: {} | explored tiles: {} | discovered E/M/S: {energy}/{minerals}/{science}",
+                sim.tick,
+                server.connected().len(),
+                sim.station.knowledge.explored_count(),
+            );
+        }
+    }
+}
+
+/// Robot unique dans son propre processus, jusqu'à la perte de la connexion
+fn run_robot(connect: &Address, robot_type: RobotType, events: Option<&Path>) -> Result<()> {
+    let mut client = RobotClient::connect(connect, robot_type)?;
+    log_events_to(&mut client.sim, events)?;
+    println!(
+        "Robot #{} ({}) connected to {connect}",
+        client.robot().id,
+        robot_type.name()
+    );
+
+    loop {
+        thread::sleep(BASE_TICK_INTERVAL);
+        client.step()?;
+
+        if client.sim.tick.is_multiple_of(STATUS_INTERVAL_TICKS) {
+            let robot = client.robot();
+            println!(
+                "tick {} | {} at ({}, {}) | energy {:.0} | unacked reports: {}",
+                client.sim.tick,
+                robot.state.name(),
+                robot.position.x,
+                robot.position.y,
+                robot.energy,
+                client.unacked(),
+            );
+        }
+    }
+}
+
+fn run_interactive(
+    events: Option<&Path>,
+    record: Option<&Path>,
+    replay: Option<&Path>,
+    metrics: Option<&MetricsOutput>,
+) -> Result<()> {
+    let player = replay
+        .map(|path| Replay::load(path).map(ReplayPlayer::new))
+        .transpose()?;
+    let replaying = player.is_some();
+    let mut simulation = match &player {
+        Some(player) => player.simulation(),
+        None => Simulation::with_config(&SimConfig {
+            width: MAP_WIDTH,
+            height: MAP_HEIGHT,
+            seed: rand::random(),
+            ..SimConfig::default()
+        }),
+    };
+    log_events_to(&mut simulation, events)?;
+    export_metrics_to(&mut simulation, metrics)?;
+    if let Some(path) = record {
+        let recorder = ReplayRecorder::create(path, &simulation.config())?;
+        simulation.start_recording(recorder);
+    }
+
     enable_raw_mode()?;
     let mut stdout = io::stdout();
-    execute!(stdout, EnterAlternateScreen)?;
+    execute!(stdout, EnterAlternateScreen, EnableMouseCapture)?;
     let backend = CrosstermBackend::new(stdout);
     let mut terminal = Terminal::new(backend)?;
 
-    let random_seed = rand::random::<u64>();
-    let map = Arc::new(Mutex::new(map::Map::new(
-        MAP_WIDTH,
-        MAP_HEIGHT,
-        random_seed,
-    )));
-
-    let station = Arc::new(Mutex::new(Station::new(&mut map.lock().unwrap())));
+    let simulation = Arc::new(Mutex::new(simulation));
+    let player = Arc::new(Mutex::new(player));
 
-    let robots: Arc<Mutex<Vec<Robot>>> = Arc::new(Mutex::new(vec![]));
+    let clock = Arc::new(Mutex::new(SimClock::new()));
 
     let running = Arc::new(Mutex::new(true));
     {
         let running_clone = Arc::clone(&running);
-        let robots_clone = Arc::clone(&robots);
-        let map_clone = Arc::clone(&map);
-        let station_clone = Arc::clone(&station);
+        let simulation_clone = Arc::clone(&simulation);
+        let player_clone = Arc::clone(&player);
+        let clock_clone = Arc::clone(&clock);
 
         thread::spawn(move || {
             while *running_clone.lock().unwrap() {
-                thread::sleep(Duration::from_millis(100));
-                let mut robots = robots_clone.lock().unwrap();
-                let map = map_clone.lock().unwrap();
-                let mut station = station_clone.lock().unwrap();
-
-                for robot in robots.iter_mut() {
-                    match robot.state {
-                        State::Idle => {
-                            robot.explore_map(&map, &mut station);
-                        }
-                        State::Exploring { .. } => {
-                            robot.explore_map(&map, &mut station);
-                        }
-                        State::Returning { .. } => {
-                            if robot.is_at_station(&station) {
-                                let energy_needed = 100.0 - robot.energy;
+                let started = Instant::now();
+                let interval = clock_clone.lock().unwrap().tick_interval();
 
-                                let available_energy = station.resources.energy as f32;
-                                let energy_given = energy_needed.min(available_energy);
-
-                                robot.energy += energy_given;
-                                station.resources.energy -= energy_given as u32;
-
-                                station.collect_robot_resources(robot);
-
-                                if robot.energy >= 10.0 {
-                                    robot.state = State::Idle;
-                                }
-                            } else {
-                                robot.return_to_station(&station);
-                            }
-                        }
-
-                        _ => {
-                            robot.move_randomly(&map);
-                        }
+                // En vitesse maximale, enchaîne les ticks pendant une tranche de temps
+                while clock_clone.lock().unwrap().take_tick() {
+                    // Verrouille la simulation avant le rejeu, jamais l'inverse
+                    let mut sim = simulation_clone.lock().unwrap();
+                    if play_tick(&mut sim, player_clone.lock().unwrap().as_mut()) {
+                        clock_clone.lock().unwrap().record_tick(Instant::now());
+                    }
+                    if !interval.is_zero() || started.elapsed() >= MAX_SPEED_SLICE {
+                        break;
                     }
                 }
+
+                let wait = if clock_clone.lock().unwrap().is_paused() {
+                    PAUSE_POLL_INTERVAL
+                } else {
+                    interval.saturating_sub(started.elapsed())
+                };
+                thread::sleep(wait.max(Duration::from_millis(1)));
             }
         });
     }
 
+    let mut workshop = WorkshopPanel::new();
+    let mut research = ResearchPanel::new();
+    let mut build = BuildPanel::new();
+    let mut events = EventsPanel::new();
+    let mut inspector = InspectorPanel::new();
+    let mut inspected_tile: Option<Position> = None;
+    let mut order_menu: Option<OrderMenu> = None;
+    // Zones de l'écran relevées au dernier affichage, pour situer les clics
+    let mut screen = Rect::default();
+    let mut map_rect = Rect::default();
+    let mut side_panel: Option<SidePanel> = None;
+    let mut station_view = false;
+
     loop {
+        let (tps, speed, paused) = {
+            let mut clock = clock.lock().unwrap();
+            (
+                clock.ticks_per_second(Instant::now()),
+                clock.speed(),
+                clock.is_paused(),
+            )
+        };
+        let replay_status = player
+            .lock()
+            .unwrap()
+            .as_ref()
+            .map(|player| (player.replay().last_tick(), player.divergence()));
+
         terminal.draw(|f| {
             let chunks = Layout::default()
                 .direction(Direction::Vertical)
-                .constraints([Constraint::Min(3), Constraint::Length(6)].as_ref())
+                .constraints([Constraint::Min(3), Constraint::Length(7)].as_ref())
                 .split(f.size());
 
-            let map_lock = map.lock().unwrap();
-            let robots_lock = robots.lock().unwrap();
+            screen = f.size();
+            let sim = simulation.lock().unwrap();
+            let map_lock = &sim.map;
+            let robots_lock = &sim.robots;
+            let station_lock = &sim.station;
+
+            let map_area = if let Some(panel) = side_panel {
+                // Les graphiques ont besoin de plus de place que les menus
+                let width = match panel {
+                    SidePanel::Statistics => Constraint::Percentage(55),
+                    _ => Constraint::Length(48),
+                };
+                let columns = Layout::default()
+                    .direction(Direction::Horizontal)
+                    .constraints([Constraint::Min(20), width].as_ref())
+                    .split(chunks[0]);
+                match panel {
+                    SidePanel::Workshop => f.render_widget(
+                        WorkshopWidget::new(&workshop, station_lock, robots_lock),
+                        columns[1],
+                    ),
+                    SidePanel::Research => f.render_widget(
+                        ResearchWidget::new(&research, station_lock),
+                        columns[1],
+                    ),
+                    SidePanel::Build => {
+                        f.render_widget(BuildWidget::new(&build, station_lock), columns[1])
+                    }
+                    SidePanel::Events => {
+                        f.render_widget(EventsWidget::new(&events, &sim.events), columns[1])
+                    }
+                    SidePanel::Statistics => {
+                        f.render_widget(StatsWidget::new(station_lock, sim.tick), columns[1])
+                    }
+                    SidePanel::Inspector => f.render_widget(
+                        InspectorWidget::new(&inspector, station_lock, robots_lock),
+                        columns[1],
+                    ),
+                    SidePanel::Tile => {
+                        if let Some(position) = inspected_tile {
+                            f.render_widget(
+                                TileInfoWidget::new(position, map_lock, &station_lock.knowledge),
+                                columns[1],
+                            )
+                        }
+                    }
+                }
+                columns[0]
+            } else {
+                chunks[0]
+            };
 
-            let map_block = Block::default().title("Robots Swarm").borders(Borders::ALL);
-            let map_widget = MapWidget::new(&map_lock, &robots_lock);
-            f.render_widget(map_block.clone(), chunks[0]);
-            f.render_widget(map_widget, chunks[0].inner(&Default::default()));
+            let (map_title, map_widget) = if station_view {
+                (
+                    format!(
+                        "Robots Swarm - station map ({} tiles known)",
+                        station_lock.knowledge.explored_count()
+                    ),
+                    MapWidget::new(map_lock, robots_lock)
+                        .with_knowledge(&station_lock.knowledge)
+                        .with_fleet(&station_lock.fleet),
+                )
+            } else {
+                (
+                    "Robots Swarm".to_string(),
+                    MapWidget::new(map_lock, robots_lock).with_fleet(&station_lock.fleet),
+                )
+            };
+            let map_widget = map_widget.with_selection(inspector.selected_robot(robots_lock));
+            let map_block = Block::default().title(map_title).borders(Borders::ALL);
+            f.render_widget(map_block.clone(), map_area);
+            f.render_widget(map_widget, map_area.inner(&Default::default()));
+            map_rect = map_area;
 
             let (energy_bases, mineral_bases, scientific_bases) = map_lock.count_resource_bases();
             let (energy_total, mineral_total, scientific_total) =
                 map_lock.calculate_total_resources();
 
-            let station_lock = station.lock().unwrap();
-
             let (discovered_energy, discovered_minerals, discovered_science) =
                 station_lock.get_discovered_resource_counts();
 
+            let count_links = |status: fn(&LinkStatus) -> bool| {
+                robots_lock.iter().filter(|robot| status(&robot.link)).count()
+            };
+            let direct_links = count_links(|link| *link == LinkStatus::Direct);
+            let relayed_links = count_links(|link| matches!(link, LinkStatus::Relayed { .. }));
+            let lost_links = count_links(|link| !link.is_connected());
+            let queued_messages: usize = robots_lock.iter().map(|robot| robot.outbox.len()).sum();
+            let (alive, suspected, lost) = station_lock.fleet.counts();
+
             let info_text = vec![
                 Line::from(vec![
                     Span::raw("Press "),
@@ -131,9 +482,51 @@ fn main() -> Result<()> {
                     Span::raw(" to regenerate map | "),
                     Span::styled("'c'", Style::default().fg(Color::Yellow)),
                     Span::raw(" to create robot | "),
+                    Span::styled("'b'", Style::default().fg(Color::Yellow)),
+                    Span::raw(" build | "),
+                    Span::styled("'w'", Style::default().fg(Color::Yellow)),
+                    Span::raw(" workshop | "),
+                    Span::styled("'t'", Style::default().fg(Color::Yellow)),
+                    Span::raw(" research | "),
+                    Span::styled("'e'", Style::default().fg(Color::Yellow)),
+                    Span::raw(" events | "),
+                    Span::styled("'s'", Style::default().fg(Color::Yellow)),
+                    Span::raw(" stats | "),
+                    Span::styled("'k'", Style::default().fg(Color::Yellow)),
+                    Span::raw(" station map | "),
+                    Span::styled("'tab'", Style::default().fg(Color::Yellow)),
+                    Span::raw(" inspect robot | "),
                     Span::styled("'q'", Style::default().fg(Color::Yellow)),
                     Span::raw(" to quit | Seed: "),
                     Span::styled(map_lock.seed.to_string(), Style::default().fg(Color::Cyan)),
+                    match replay_status {
+                        Some((_, Some(divergence))) => Span::styled(
+                            format!(" | Replay diverged at tick {}", divergence.tick),
+                            Style::default().fg(Color::Red),
+                        ),
+                        Some((last_tick, None)) => Span::styled(
+                            format!(" | Replay {}/{last_tick}", sim.tick),
+                            Style::default().fg(Color::Green),
+                        ),
+                        None => Span::raw(""),
+                    },
+                ]),
+                Line::from(vec![
+                    Span::raw("⏱ Tick "),
+                    Span::styled(sim.tick.to_string(), Style::default().fg(Color::Cyan)),
+                    Span::raw(format!(" | {tps} TPS | Speed {speed}")),
+                    if paused {
+                        Span::styled(" | PAUSED", Style::default().fg(Color::Red))
+                    } else {
+                        Span::raw("")
+                    },
+                    Span::raw(" | "),
+                    Span::styled("'space'", Style::default().fg(Color::Yellow)),
+                    Span::raw(" pause | "),
+                    Span::styled("'n'", Style::default().fg(Color::Yellow)),
+                    Span::raw(" step | "),
+                    Span::styled("'+' '-'", Style::default().fg(Color::Yellow)),
+                    Span::raw(" speed"),
                 ]),
                 Line::from(vec![
                     Span::styled("⚡", Style::default().fg(Color::Yellow)),
@@ -160,6 +553,28 @@ fn main() -> Result<()> {
                         station_lock.resources.scientific_data.to_string(),
                         Style::default().fg(Color::Green),
                     ),
+                    Span::raw(format!(
+                        " | Bays: {}/{} | Queue: ",
+                        station_lock.docking.occupied_bays(),
+                        station_lock.docking.bays().len()
+                    )),
+                    Span::styled(
+                        station_lock.docking.queue_len().to_string(),
+                        Style::default().fg(Color::Magenta),
+                    ),
+                    Span::raw(" | Links: "),
+                    Span::styled(
+                        direct_links.to_string(),
+                        Style::default().fg(Color::Magenta),
+                    ),
+                    Span::raw("/"),
+                    Span::styled(relayed_links.to_string(), Style::default().fg(Color::Cyan)),
+                    Span::raw("/"),
+                    Span::styled(lost_links.to_string(), Style::default().fg(Color::DarkGray)),
+                    Span::raw(format!(
+                        " | Outbox: {queued_messages} | Unacked: {}",
+                        sim.network.unacked()
+                    )),
                 ]),
                 Line::from(vec![
                     Span::raw("🔍 Discovered - "),
@@ -178,35 +593,160 @@ fn main() -> Result<()> {
                         discovered_science.to_string(),
                         Style::default().fg(Color::Green),
                     ),
+                    Span::raw(format!(
+                        " | Map conflicts ({}): ",
+                        station_lock.knowledge.conflict_policy.name()
+                    )),
+                    Span::styled(
+                        station_lock.knowledge.conflicts.len().to_string(),
+                        Style::default().fg(Color::Red),
+                    ),
+                    Span::raw(" | Fleet: "),
+                    Span::styled(alive.to_string(), Style::default().fg(Color::Green)),
+                    Span::raw("/"),
+                    Span::styled(suspected.to_string(), Style::default().fg(Color::Yellow)),
+                    Span::raw("/"),
+                    Span::styled(lost.to_string(), Style::default().fg(Color::Red)),
                 ]),
             ];
 
             let info_block = Block::default().title("Commands").borders(Borders::ALL);
             let info = Paragraph::new(info_text).block(info_block);
             f.render_widget(info, chunks[1]);
+
+            if let Some(menu) = &order_menu {
+                f.render_widget(OrderMenuWidget::new(menu), screen);
+            }
         })?;
 
         if event::poll(Duration::from_millis(16))? {
-            if let Event::Key(key) = event::read()? {
-                match key.code {
-                    KeyCode::Char('q') => break,
-                    KeyCode::Char('r') => {
-                        let mut map_lock = map.lock().unwrap();
-                        let mut robots_lock = robots.lock().unwrap();
+            let event = event::read()?;
+            if let Event::Mouse(mouse) = event {
+                let (column, row) = (mouse.column, mouse.row);
+                let tile = map_position(map_rect, column, row);
+                match mouse.kind {
+                    MouseEventKind::Down(MouseButton::Left) => {
+                        if let Some(menu) = order_menu.take() {
+                            if let Some(command) = menu.command_at(screen, column, row) {
+                                inspector.report(simulation.lock().unwrap().apply(command));
+                                side_panel = Some(SidePanel::Inspector);
+                            }
+                        } else if let Some(position) = tile {
+                            let sim = simulation.lock().unwrap();
+                            match sim.robots.iter().find(|robot| robot.position == position) {
+                                Some(robot) => {
+                                    inspector.select(robot.id);
+                                    side_panel = Some(SidePanel::Inspector);
+                                }
+                                None => {
+                                    inspected_tile = Some(position);
+                                    side_panel = Some(SidePanel::Tile);
+                                }
+                            }
+                        }
+                    }
+                    // Les ordres sont des commandes : ils sont refusés pendant un rejeu
+                    MouseEventKind::Down(MouseButton::Right) if !replaying => {
+                        let sim = simulation.lock().unwrap();
+                        if let (Some(position), Some(robot)) =
+                            (tile, inspector.selected_robot(&sim.robots))
+                        {
+                            let can_harvest = sim
+                                .map
+                                .get_tile(position.x, position.y)
+                                .and_then(|tile| tile.resource())
+                                .is_some_and(|(resource_type, _)| {
+                                    robot.harvest_rate(&resource_type).is_some()
+                                });
+                            order_menu =
+                                Some(OrderMenu::new(robot.id, position, can_harvest, column, row));
+                        }
+                    }
+                    _ => {}
+                }
+            }
 
-                        *map_lock = map::Map::new(MAP_WIDTH, MAP_HEIGHT, rand::random());
-                        robots_lock.iter_mut().for_each(|robot| {
-                            robot.position = Position { x: 0, y: 0 };
-                        });
+            if let Event::Key(key) = event {
+                if let Some(menu) = order_menu.as_mut() {
+                    if key.code == KeyCode::Esc {
+                        order_menu = None;
+                    } else if let Some(command) = menu.handle_key(key.code) {
+                        order_menu = None;
+                        inspector.report(simulation.lock().unwrap().apply(command));
+                        side_panel = Some(SidePanel::Inspector);
+                    }
+                    continue;
+                }
 
-                        Station::new(&mut map_lock);
+                let panel_key = !matches!(
+                    key.code,
+                    KeyCode::Char('w' | 't' | 'b' | 'e' | 's' | 'q' | ' ' | 'n' | '+' | '=' | '-')
+                        | KeyCode::Esc
+                        | KeyCode::Tab
+                        | KeyCode::BackTab
+                );
+                if panel_key {
+                    let mut sim = simulation.lock().unwrap();
+                    // Pendant un rejeu, seules les commandes enregistrées modifient la partie.
+                    // Les panneaux sans raccourcis laissent passer les touches.
+                    let captured = match side_panel {
+                        Some(SidePanel::Workshop) if !replaying => {
+                            workshop.handle_key(key.code, &mut sim);
+                            true
+                        }
+                        Some(SidePanel::Research) if !replaying => {
+                            research.handle_key(key.code, &mut sim);
+                            true
+                        }
+                        Some(SidePanel::Build) if !replaying => {
+                            build.handle_key(key.code, &mut sim);
+                            true
+                        }
+                        Some(SidePanel::Events) => {
+                            events.handle_key(key.code, &sim.events);
+                            true
+                        }
+                        _ => false,
+                    };
+                    if captured {
+                        continue;
                     }
-                    KeyCode::Char('c') => {
-                        let mut station_lock = station.lock().unwrap();
-                        let mut robots_lock = robots.lock().unwrap();
+                }
 
-                        if let Some(new_robot) = station_lock.create_robot(RobotType::Explorer) {
-                            robots_lock.push(new_robot);
+                match key.code {
+                    KeyCode::Char('q') => break,
+                    KeyCode::Char('r') if !replaying => {
+                        let seed = rand::random();
+                        let _ = simulation
+                            .lock()
+                            .unwrap()
+                            .apply(UserCommand::Regenerate { seed });
+                    }
+                    KeyCode::Char('w') => toggle_panel(&mut side_panel, SidePanel::Workshop),
+                    KeyCode::Char('t') => toggle_panel(&mut side_panel, SidePanel::Research),
+                    KeyCode::Char('b') => toggle_panel(&mut side_panel, SidePanel::Build),
+                    KeyCode::Char('e') => toggle_panel(&mut side_panel, SidePanel::Events),
+                    KeyCode::Char('s') => toggle_panel(&mut side_panel, SidePanel::Statistics),
+                    KeyCode::Esc => side_panel = None,
+                    KeyCode::Char('k') => station_view = !station_view,
+                    KeyCode::Tab | KeyCode::BackTab => {
+                        let forward = key.code == KeyCode::Tab;
+                        inspector.cycle(&simulation.lock().unwrap().robots, forward);
+                        side_panel = Some(SidePanel::Inspector);
+                    }
+                    KeyCode::Char(' ') => clock.lock().unwrap().toggle_pause(),
+                    KeyCode::Char('n') => clock.lock().unwrap().request_step(),
+                    KeyCode::Char('+' | '=') => clock.lock().unwrap().faster(),
+                    KeyCode::Char('-') => clock.lock().unwrap().slower(),
+                    KeyCode::Char('c') if !replaying => {
+                        let result = simulation
+                            .lock()
+                            .unwrap()
+                            .apply(UserCommand::CreateRobot(RobotType::Explorer));
+                        // Un refus est expliqué dans le menu de construction
+                        if let Err(error) = result {
+                            build.report_refusal(&error);
+                            side_panel = Some(SidePanel::Build);
                         }
                     }
 
@@ -217,6 +757,58 @@ fn main() -> Result<()> {
     }
 
     disable_raw_mode()?;
-    execute!(terminal.backend_mut(), LeaveAlternateScreen)?;
-    Ok(())
+    execute!(
+        terminal.backend_mut(),
+        LeaveAlternateScreen,
+        DisableMouseCapture
+    )?;
+
+    let tick = simulation.lock().unwrap().tick;
+    if let Some(player) = player.lock().unwrap().as_ref() {
+        match player.divergence() {
+            Some(divergence) => println!("{divergence}"),
+            None => println!("Replay matched the recording up to tick {tick}"),
+        }
+    }
+    if let Some(path) = record {
+        println!("Recorded {tick} ticks to {}", path.display());
+    }
+    let mut simulation = simulation.lock().unwrap();
+    finish_metrics(&mut simulation, metrics)
+}
+
+/// Case de la carte sous un point de l'écran
+fn map_position(map_area: Rect, column: u16, row: u16) -> Option<Position> {
+    let inside = column >= map_area.x
+        && column < map_area.right()
+        && row >= map_area.y
+        && row < map_area.bottom();
+    inside.then(|| Position {
+        x: u32::from(column - map_area.x),
+        y: u32::from(row - map_area.y),
+    })
+}
+
+/// Joue un tick, en suivant l'enregistrement pendant un rejeu. Renvoie faux
+/// quand le rejeu est terminé.
+fn play_tick(sim: &mut Simulation, player: Option<&mut ReplayPlayer>) -> bool {
+    match player {
+        Some(player) if player.is_finished(sim) => false,
+        Some(player) => {
+            let _ = player.step(sim);
+            true
+        }
+        None => {
+            sim.step();
+            true
+        }
+    }
+}
+
+fn toggle_panel(side_panel: &mut Option<SidePanel>, panel: SidePanel) {
+    *side_panel = if *side_panel == Some(panel) {
+        None
+    } else {
+        Some(panel)
+    };
 }
@@ -1,23 +1,55 @@
 use ratatui::{
     buffer::Buffer,
     layout::Rect,
-    style::{Color, Style},
+    style::{Color, Modifier, Style},
     widgets::Widget,
 };
 
 use crate::{
     map::{Map, Tile},
-    robot::Robot,
+    robot::{communication::LinkStatus, Position, Robot, State},
+    station::{
+        fleet::{FleetTable, Liveness},
+        sync::MapKnowledge,
+    },
 };
 
 pub struct MapWidget<'a> {
     map: &'a Map,
     robots: &'a Vec<Robot>,
+    knowledge: Option<&'a MapKnowledge>,
+    fleet: Option<&'a FleetTable>,
+    selected: Option<&'a Robot>,
 }
 
 impl<'a> MapWidget<'a> {
     pub fn new(map: &'a Map, robots: &'a Vec<Robot>) -> Self {
-        Self { map, robots }
+        Self {
+            map,
+            robots,
+            knowledge: None,
+            fleet: None,
+            selected: None,
+        }
+    }
+
+    /// Affiche uniquement les cases connues de la station au lieu de la carte réelle
+    pub fn with_knowledge(mut self, knowledge: &'a MapKnowledge) -> Self {
+        self.knowledge = Some(knowledge);
+        self
+    }
+
+    /// Met en évidence les robots suspects ou perdus et la dernière position
+    /// connue des robots recherchés
+    pub fn with_fleet(mut self, fleet: &'a FleetTable) -> Self {
+        self.fleet = Some(fleet);
+        self
+    }
+
+    /// Met en évidence le robot sélectionné, son chemin prévu et sa cible
+    pub fn with_selection(mut self, robot: Option<&'a Robot>) -> Self {
+        self.selected = robot;
+        self
     }
 }
 
@@ -43,7 +75,12 @@ impl Widget for MapWidget<'_> {
                     continue;
                 }
 
-                if let Some(tile) = self.map.get_tile(map_x, map_y) {
+                let tile = match self.knowledge {
+                    Some(knowledge) => knowledge.get_tile(map_x, map_y).map(|known| &known.tile),
+                    None => self.map.get_tile(map_x, map_y),
+                };
+
+                if let Some(tile) = tile {
                     let cell = buf.get_mut(buf_x, buf_y);
                     match tile {
                         Tile::Obstacle => {
@@ -91,11 +128,51 @@ impl Widget for MapWidget<'_> {
                             cell.set_char('🏠')
                                 .set_style(Style::default().fg(Color::Magenta).bg(Color::Black));
                         }
+                        Tile::Unknown => {}
                     }
                 }
             }
         }
 
+        if let Some(fleet) = self.fleet {
+            for task in fleet.recoveries() {
+                let position = task.last_known_position;
+                let buf_x = render_area.x + position.x as u16;
+                let buf_y = render_area.y + position.y as u16;
+                if buf_x < render_area.right() && buf_y < render_area.bottom() {
+                    buf.get_mut(buf_x, buf_y)
+                        .set_char('?')
+                        .set_style(Style::default().fg(Color::White).bg(Color::Red));
+                }
+            }
+        }
+
+        if let Some(robot) = self.selected {
+            let (path, target): (&[Position], _) = match &robot.state {
+                State::Exploring { target, path } => (path, Some(*target)),
+                State::Returning {
+                    base_position,
+                    path,
+                } => (path, Some(*base_position)),
+                State::Collecting { target, .. } => (&[], Some(*target)),
+                State::Idle | State::Analyzing { .. } => (&[], None),
+            };
+            for position in path {
+                let buf_x = render_area.x + position.x as u16;
+                let buf_y = render_area.y + position.y as u16;
+                if buf_x < render_area.right() && buf_y < render_area.bottom() {
+                    buf.get_mut(buf_x, buf_y).set_bg(Color::Blue);
+                }
+            }
+            if let Some(target) = target {
+                let buf_x = render_area.x + target.x as u16;
+                let buf_y = render_area.y + target.y as u16;
+                if buf_x < render_area.right() && buf_y < render_area.bottom() {
+                    buf.get_mut(buf_x, buf_y).set_bg(Color::LightBlue);
+                }
+            }
+        }
+
         for robot in self.robots {
             let x = robot.position.x.clamp(0, self.map.width - 1) as u16;
             let y = robot.position.y.clamp(0, self.map.height - 1) as u16;
@@ -103,10 +180,26 @@ impl Widget for MapWidget<'_> {
             let buf_x = render_area.x + x;
             let buf_y = render_area.y + y;
 
+            // La couleur du robot indique l'état de sa liaison radio
+            let color = match robot.link {
+                LinkStatus::Direct => Color::Magenta,
+                LinkStatus::Relayed { .. } => Color::Cyan,
+                LinkStatus::NoSignal => Color::DarkGray,
+            };
+
+            let style = match self.fleet.and_then(|fleet| fleet.liveness(robot.id)) {
+                Some(Liveness::Lost) => Style::default().fg(Color::White).bg(Color::Red),
+                Some(Liveness::Suspected) => Style::default().fg(Color::Black).bg(Color::Yellow),
+                _ => Style::default().fg(color),
+            };
+            let style = if self.selected.is_some_and(|selected| selected.id == robot.id) {
+                style.add_modifier(Modifier::REVERSED)
+            } else {
+                style
+            };
+
             if buf_x < buf.area.width && buf_y < buf.area.height {
-                buf.get_mut(buf_x, buf_y)
-                    .set_char('R')
-                    .set_style(Style::default().fg(Color::Magenta));
+                buf.get_mut(buf_x, buf_y).set_char('R').set_style(style);
             }
         }
     }
@@ -1,7 +1,11 @@
 use noise::{NoiseFn, Value};
 use rand::{Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
+
+use crate::robot::resources::ResourceType;
+
 pub mod map_widget;
+pub mod pathfinding;
 
 const OBSTACLE_THRESHOLD: f64 = 0.4;
 const NOISE_SCALE: f64 = 0.2;
@@ -45,6 +49,20 @@ pub enum Tile {
     Mineral(Mineral),
     ScientificPoint(ScientificPoint),
     Station,
+    /// Case jamais observée, utilisée uniquement dans les cartes partielles
+    Unknown,
+}
+
+impl Tile {
+    /// Type et quantité du gisement de la case, s'il y en a un
+    pub fn resource(&self) -> Option<(ResourceType, u32)> {
+        match self {
+            Tile::Energy(energy) => Some((ResourceType::Energy, energy.amount)),
+            Tile::Mineral(mineral) => Some((ResourceType::Minerals, mineral.amount)),
+            Tile::ScientificPoint(point) => Some((ResourceType::ScientificData, point.value)),
+            _ => None,
+        }
+    }
 }
 
 impl Map {
@@ -131,6 +149,16 @@ impl Map {
         }
     }
 
+    /// Crée une carte entièrement inconnue, base des cartes partielles
+    pub fn unknown(width: u32, height: u32) -> Self {
+        Self {
+            width,
+            height,
+            tiles: vec![Tile::Unknown; (width * height) as usize],
+            seed: 0,
+        }
+    }
+
     pub fn get_tile(&self, x: u32, y: u32) -> Option<&Tile> {
         if x >= self.width || y >= self.height {
             return None; 
@@ -149,6 +177,17 @@ impl Map {
         matches!(self.get_tile(x, y), Some(Tile::Obstacle))
     }
 
+    pub fn is_unknown(&self, x: u32, y: u32) -> bool {
+        matches!(self.get_tile(x, y), Some(Tile::Unknown))
+    }
+
+    /// Facteur de coût de déplacement entre 1.0 (plat) et 2.0 (accidenté, près des obstacles)
+    pub fn terrain_factor(&self, x: u32, y: u32) -> f32 {
+        let noise = Value::new(self.seed as u32);
+        let value = noise.get([x as f64 * NOISE_SCALE, y as f64 * NOISE_SCALE]);
+        1.0 + (value.max(0.0) / OBSTACLE_THRESHOLD).min(1.0) as f32
+    }
+
     pub fn has_scientific_point(&self, x: u32, y: u32) -> bool {
         matches!(self.get_tile(x, y), Some(Tile::ScientificPoint(_)))
     }
@@ -196,6 +235,23 @@ impl Map {
         None
     }
 
+    /// Analyse partielle d'un point scientifique : extrait au plus `amount` données
+    pub fn analyse_scientific_point(&mut self, x: u32, y: u32, amount: u32) -> Option<u32> {
+        if let Some(tile) = self.get_tile_mut(x, y) {
+            if let Tile::ScientificPoint(point) = tile {
+                let extracted = amount.min(point.value);
+                point.value -= extracted;
+
+                if point.value == 0 {
+                    *tile = Tile::Empty;
+                }
+
+                return Some(extracted);
+            }
+        }
+        None
+    }
+
     pub fn resource_statistics(&self) -> (u32, u32, u32) {
         let mut energy_count = 0;
         let mut mineral_count = 0;
@@ -0,0 +1,255 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap};
+
+use crate::map::Map;
+use crate::robot::Position;
+
+const DIRECTIONS: [(i32, i32); 4] = [(0, 1), (1, 0), (0, -1), (-1, 0)];
+
+/// Traitement des cases inconnues d'une carte partielle lors de la recherche de chemin
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum UnknownTilePolicy {
+    /// Les cases inconnues sont supposées praticables
+    #[default]
+    Optimistic,
+    /// Seules les cases déjà observées sont traversées
+    Pessimistic,
+}
+
+impl UnknownTilePolicy {
+    fn is_passable(&self, map: &Map, position: Position) -> bool {
+        if position.x >= map.width || position.y >= map.height {
+            return false;
+        }
+        if map.is_obstacle(position.x, position.y) {
+            return false;
+        }
+        *self == UnknownTilePolicy::Optimistic || !map.is_unknown(position.x, position.y)
+    }
+}
+
+/// Recherche A* d'un chemin entre deux positions en évitant les obstacles.
+/// Le chemin retourné exclut le départ et se termine sur l'arrivée.
+pub fn find_path(map: &Map, start: Position, goal: Position) -> Option<Vec<Position>> {
+    find_path_with_policy(map, start, goal, UnknownTilePolicy::Optimistic)
+}
+
+/// Comme `find_path`, en précisant comment traiter les cases inconnues
+pub fn find_path_with_policy(
+    map: &Map,
+    start: Position,
+    goal: Position,
+    policy: UnknownTilePolicy,
+) -> Option<Vec<Position>> {
+    if start == goal {
+        return Some(Vec::new());
+    }
+    if !policy.is_passable(map, goal) {
+        return None;
+    }
+
+    let came_from = search(map, start, goal, policy);
+    came_from
+        .contains_key(&goal)
+        .then(|| reconstruct_path(&came_from, start, goal))
+}
+
+/// Chemin vers la case accessible la plus proche de l'arrivée, selon la
+/// politique donnée. Vide quand le départ est déjà cette case.
+pub fn find_path_towards(
+    map: &Map,
+    start: Position,
+    goal: Position,
+    policy: UnknownTilePolicy,
+) -> Vec<Position> {
+    let came_from = search(map, start, goal, policy);
+    let closest = came_from
+        .keys()
+        .copied()
+        .chain(std::iter::once(start))
+        .min_by_key(|position| (position.distance_to(&goal), position.x, position.y))
+        .unwrap_or(start);
+    if closest == start {
+        return Vec::new();
+    }
+    reconstruct_path(&came_from, start, closest)
+}
+
+/// Exploration A* depuis le départ, interrompue en atteignant l'arrivée.
+/// Retourne le prédécesseur de chaque case atteinte.
+fn search(
+    map: &Map,
+    start: Position,
+    goal: Position,
+    policy: UnknownTilePolicy,
+) -> HashMap<Position, Position> {
+    let mut open = BinaryHeap::new();
+    let mut came_from: HashMap<Position, Position> = HashMap::new();
+    let mut cost_so_far: HashMap<Position, u32> = HashMap::new();
+
+    open.push(Reverse((start.distance_to(&goal), 0, start.x, start.y)));
+    cost_so_far.insert(start, 0);
+
+    while let Some(Reverse((_, cost, x, y))) = open.pop() {
+        let current = Position { x, y };
+        if current == goal {
+            break;
+        }
+        if cost > cost_so_far[&current] {
+            continue;
+        }
+
+        for (dx, dy) in DIRECTIONS {
+            let next_x = x as i32 + dx;
+            let next_y = y as i32 + dy;
+            if next_x < 0 || next_y < 0 {
+                continue;
+            }
+            let next = Position {
+                x: next_x as u32,
+                y: next_y as u32,
+            };
+            if !policy.is_passable(map, next) {
+                continue;
+            }
+
+            let next_cost = cost + 1;
+            if cost_so_far
+                .get(&next)
+                .is_none_or(|&known| next_cost < known)
+            {
+                cost_so_far.insert(next, next_cost);
+                came_from.insert(next, current);
+                let priority = next_cost + next.distance_to(&goal);
+                open.push(Reverse((priority, next_cost, next.x, next.y)));
+            }
+        }
+    }
+
+    came_from
+}
+
+fn reconstruct_path(
+    came_from: &HashMap<Position, Position>,
+    start: Position,
+    goal: Position,
+) -> Vec<Position> {
+    let mut path = vec![goal];
+    let mut current = goal;
+    while let Some(&previous) = came_from.get(&current) {
+        if previous == start {
+            break;
+        }
+        path.push(previous);
+        current = previous;
+    }
+    path.reverse();
+    path
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::Tile;
+
+    fn open_map(width: u32, height: u32) -> Map {
+        let mut map = Map::new(width, height, 42);
+        for y in 0..height {
+            for x in 0..width {
+                *map.get_tile_mut(x, y).unwrap() = Tile::Empty;
+            }
+        }
+        map
+    }
+
+    #[test]
+    fn test_straight_path() {
+        let map = open_map(10, 10);
+        let path = find_path(&map, Position { x: 0, y: 0 }, Position { x: 3, y: 0 }).unwrap();
+
+        assert_eq!(path.len(), 3);
+        assert_eq!(path.last(), Some(&Position { x: 3, y: 0 }));
+    }
+
+    #[test]
+    fn test_path_goes_around_wall() {
+        let mut map = open_map(10, 10);
+        for y in 0..9 {
+            *map.get_tile_mut(5, y).unwrap() = Tile::Obstacle;
+        }
+
+        let path = find_path(&map, Position { x: 0, y: 0 }, Position { x: 9, y: 0 }).unwrap();
+
+        assert!(path.iter().all(|p| !map.is_obstacle(p.x, p.y)));
+        assert!(path.contains(&Position { x: 5, y: 9 }));
+        assert_eq!(path.len(), 27);
+    }
+
+    #[test]
+    fn test_unreachable_goal() {
+        let mut map = open_map(10, 10);
+        for y in 0..10 {
+            *map.get_tile_mut(5, y).unwrap() = Tile::Obstacle;
+        }
+
+        assert!(find_path(&map, Position { x: 0, y: 0 }, Position { x: 9, y: 0 }).is_none());
+    }
+
+    #[test]
+    fn test_unknown_tile_policies() {
+        let mut map = Map::unknown(10, 10);
+        for x in 0..10 {
+            *map.get_tile_mut(x, 5).unwrap() = Tile::Empty;
+        }
+        for y in 0..5 {
+            *map.get_tile_mut(0, y).unwrap() = Tile::Empty;
+        }
+        let start = Position { x: 0, y: 0 };
+        let goal = Position { x: 9, y: 5 };
+
+        let optimistic =
+            find_path_with_policy(&map, start, goal, UnknownTilePolicy::Optimistic).unwrap();
+        assert_eq!(optimistic.len(), 14);
+
+        let pessimistic =
+            find_path_with_policy(&map, start, goal, UnknownTilePolicy::Pessimistic).unwrap();
+        assert_eq!(pessimistic.len(), 14);
+        assert!(pessimistic.iter().all(|p| !map.is_unknown(p.x, p.y)));
+
+        let hidden_goal = Position { x: 9, y: 9 };
+        assert!(
+            find_path_with_policy(&map, start, hidden_goal, UnknownTilePolicy::Pessimistic)
+                .is_none()
+        );
+        assert!(
+            find_path_with_policy(&map, start, hidden_goal, UnknownTilePolicy::Optimistic)
+                .is_some()
+        );
+    }
+
+    #[test]
+    fn test_path_towards_an_unreachable_goal_stops_at_the_closest_tile() {
+        let mut map = open_map(10, 10);
+        for y in 0..10 {
+            *map.get_tile_mut(5, y).unwrap() = Tile::Obstacle;
+        }
+        let start = Position { x: 0, y: 0 };
+
+        let path = find_path_towards(
+            &map,
+            start,
+            Position { x: 9, y: 3 },
+            UnknownTilePolicy::Optimistic,
+        );
+        assert_eq!(path.last(), Some(&Position { x: 4, y: 3 }));
+
+        let path = find_path_towards(
+            &map,
+            Position { x: 4, y: 3 },
+            Position { x: 9, y: 3 },
+            UnknownTilePolicy::Optimistic,
+        );
+        assert!(path.is_empty());
+    }
+}
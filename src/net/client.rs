@@ -0,0 +1,158 @@
+use std::io::BufReader;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+
+use crossbeam::channel::{self, Sender};
+
+use crate::net::codec::{read_frame, write_frame};
+use crate::net::socket::{Address, Stream};
+use crate::net::{ClientFrame, ServerFrame, WireError};
+use crate::robot::communication::{LinkStatus, RobotCommunication};
+use crate::robot::{Resources, Robot, State};
+use crate::simulation::Simulation;
+use crate::station::RobotType;
+
+/// Nombre de ticks entre deux envois des cases découvertes à la station
+pub const MAP_SYNC_INTERVAL_TICKS: u64 = 10;
+
+/// Robot exécuté dans son propre processus. Il évolue sur une réplique locale
+/// du monde reçue à la connexion ; ses rapports et synchronisations de carte
+/// passent par la socket, et la station distante tient la carte de référence.
+/// La recharge et le déchargement se font sur la réplique de la station, puis
+/// sont transmis à la station distante qui tient le stock de référence.
+pub struct RobotClient {
+    pub sim: Simulation,
+    link: RobotCommunication,
+    frames: Sender<ClientFrame>,
+    stream: Stream,
+    connected: Arc<AtomicBool>,
+}
+
+impl RobotClient {
+    /// Se connecte à la station et lui demande de construire un robot
+    pub fn connect(address: &Address, robot_type: RobotType) -> Result<Self, WireError> {
+        let stream = Stream::connect(address)?;
+        let mut writer = stream.try_clone()?;
+        let mut reader = BufReader::new(stream.try_clone()?);
+
+        write_frame(&mut writer, &ClientFrame::Hello { robot_type })?;
+        let welcome = match read_frame(&mut reader)? {
+            Some(ServerFrame::Welcome(welcome)) => *welcome,
+            Some(ServerFrame::Refused { reason }) => return Err(WireError::Refused(reason)),
+            Some(ServerFrame::Message(_)) => return Err(WireError::UnexpectedFrame),
+            None => return Err(WireError::Disconnected),
+        };
+
+        let seed = welcome.map.seed;
+        let mut sim = Simulation::from_world(welcome.map, welcome.station, seed);
+        sim.tick = welcome.tick;
+        sim.sync_at_dock = false;
+        // Le générateur n'est pas transmis : il est réamorcé comme à la construction
+        let mut robot = welcome.robot;
+        robot.seed_rng(seed);
+        let robot_id = robot.id;
+        sim.robots.push(robot);
+
+        let (packet_sender, packets) = channel::unbounded();
+        let (frame_sender, frames) = channel::unbounded();
+        let (inbox_sender, inbox) = channel::unbounded();
+        let connected = Arc::new(AtomicBool::new(true));
+
+        thread::spawn(move || loop {
+            let frame = channel::select! {
+                recv(packets) -> packet => packet.map(ClientFrame::Packet),
+                recv(frames) -> frame => frame,
+            };
+            let Ok(frame) = frame else {
+                break;
+            };
+            if write_frame(&mut writer, &frame).is_err() {
+                break;
+            }
+        });
+
+        let reader_connected = Arc::clone(&connected);
+        thread::spawn(move || {
+            while let Ok(Some(ServerFrame::Message(message))) = read_frame(&mut reader) {
+                if inbox_sender.send(message).is_err() {
+                    break;
+                }
+            }
+            reader_connected.store(false, Ordering::Relaxed);
+        });
+
+        Ok(Self {
+            sim,
+            link: RobotCommunication::new(robot_id, packet_sender, inbox),
+            frames: frame_sender,
+            stream,
+            connected,
+        })
+    }
+
+    pub fn robot(&self) -> &Robot {
+        &self.sim.robots[0]
+    }
+
+    pub fn is_connected(&self) -> bool {
+        self.connected.load(Ordering::Relaxed)
+    }
+
+    /// Nombre de rapports envoyés et pas encore acquittés par la station
+    pub fn unacked(&self) -> usize {
+        self.link.unacked_count()
+    }
+
+    /// Avance le robot d'un tick puis échange avec la station
+    pub fn step(&mut self) -> Result<(), WireError> {
+        if !self.is_connected() {
+            return Err(WireError::Disconnected);
+        }
+
+        let robot = &self.sim.robots[0];
+        let docked = matches!(robot.state, State::Returning { .. })
+            && robot.is_at_station(&self.sim.station);
+        let (inventory, energy) = (robot.inventory.clone(), robot.energy);
+
+        self.sim.advance();
+        let now = self.sim.tick;
+        let robot = &mut self.sim.robots[0];
+        robot.link = LinkStatus::Direct;
+
+        if docked {
+            let unloaded = Resources {
+                energy: inventory.energy.saturating_sub(robot.inventory.energy),
+                minerals: inventory.minerals.saturating_sub(robot.inventory.minerals),
+                scientific_data: inventory
+                    .scientific_data
+                    .saturating_sub(robot.inventory.scientific_data),
+            };
+            if !unloaded.is_empty() {
+                let _ = self.frames.send(ClientFrame::Unload(unloaded));
+            }
+            let recharged = (robot.energy - energy).max(0.0) as u32;
+            if recharged > 0 {
+                let _ = self
+                    .frames
+                    .send(ClientFrame::Recharge { energy: recharged });
+            }
+        }
+
+        self.link.retransmit(now);
+        for message in robot.outbox.drain(..) {
+            self.link.send(message);
+        }
+        if now.is_multiple_of(MAP_SYNC_INTERVAL_TICKS) {
+            self.link.request_map_sync(&mut robot.knowledge);
+        }
+        self.link.process_station_messages(&mut robot.knowledge);
+        Ok(())
+    }
+}
+
+impl Drop for RobotClient {
+    fn drop(&mut self) {
+        let _ = self.stream.shutdown();
+    }
+}
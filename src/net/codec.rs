@@ -0,0 +1,99 @@
+use std::io::{self, Read, Write};
+
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+
+use crate::net::WireError;
+
+/// Taille maximale d'une trame ; la réponse `Welcome` contient la carte entière
+pub const MAX_FRAME_LEN: usize = 64 * 1024 * 1024;
+
+/// Écrit une trame : longueur sur 4 octets gros-boutiste suivie du contenu JSON
+pub fn write_frame<W: Write, T: Serialize>(writer: &mut W, frame: &T) -> Result<(), WireError> {
+    let payload = serde_json::to_vec(frame)?;
+    if payload.len() > MAX_FRAME_LEN {
+        return Err(WireError::FrameTooLarge(payload.len()));
+    }
+
+    writer.write_all(&(payload.len() as u32).to_be_bytes())?;
+    writer.write_all(&payload)?;
+    writer.flush()?;
+    Ok(())
+}
+
+/// Lit une trame. Retourne `None` si le flux se termine proprement entre deux trames.
+pub fn read_frame<R: Read, T: DeserializeOwned>(reader: &mut R) -> Result<Option<T>, WireError> {
+    let mut header = [0u8; 4];
+    let mut filled = 0;
+    while filled < header.len() {
+        match reader.read(&mut header[filled..]) {
+            Ok(0) if filled == 0 => return Ok(None),
+            Ok(0) => return Err(io::Error::from(io::ErrorKind::UnexpectedEof).into()),
+            Ok(read) => filled += read,
+            Err(error) if error.kind() == io::ErrorKind::Interrupted => {}
+            Err(error) => return Err(error.into()),
+        }
+    }
+
+    let len = u32::from_be_bytes(header) as usize;
+    if len > MAX_FRAME_LEN {
+        return Err(WireError::FrameTooLarge(len));
+    }
+
+    let mut payload = vec![0u8; len];
+    reader.read_exact(&mut payload)?;
+    Ok(Some(serde_json::from_slice(&payload)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::robot::Position;
+    use crate::robot::resources::ResourceType;
+    use crate::station::communication::{RobotMessage, RobotPacket};
+    use std::io::Cursor;
+
+    fn packet(message_id: u64) -> RobotPacket {
+        RobotPacket {
+            robot_id: 3,
+            message_id,
+            message: RobotMessage::ResourceDiscovered {
+                resource_type: ResourceType::Minerals,
+                position: Position { x: 4, y: 5 },
+                amount: 120,
+                confidence: 0.8,
+            },
+        }
+    }
+
+    #[test]
+    fn test_frames_round_trip_back_to_back() {
+        let mut buffer = Vec::new();
+        write_frame(&mut buffer, &packet(1)).unwrap();
+        write_frame(&mut buffer, &packet(2)).unwrap();
+
+        let mut reader = Cursor::new(buffer);
+        let first: RobotPacket = read_frame(&mut reader).unwrap().unwrap();
+        let second: RobotPacket = read_frame(&mut reader).unwrap().unwrap();
+        assert_eq!((first.message_id, second.message_id), (1, 2));
+        assert!(read_frame::<_, RobotPacket>(&mut reader).unwrap().is_none());
+    }
+
+    #[test]
+    fn test_truncated_and_oversized_frames_are_errors() {
+        let mut buffer = Vec::new();
+        write_frame(&mut buffer, &packet(1)).unwrap();
+        buffer.truncate(buffer.len() - 1);
+        let truncated = read_frame::<_, RobotPacket>(&mut Cursor::new(buffer));
+        assert!(matches!(truncated, Err(WireError::Io(_))));
+
+        let oversized = ((MAX_FRAME_LEN + 1) as u32).to_be_bytes().to_vec();
+        let oversized = read_frame::<_, RobotPacket>(&mut Cursor::new(oversized));
+        assert!(matches!(oversized, Err(WireError::FrameTooLarge(_))));
+
+        let mut garbage = 3u32.to_be_bytes().to_vec();
+        garbage.extend_from_slice(b"{]}");
+        let garbage = read_frame::<_, RobotPacket>(&mut Cursor::new(garbage));
+        assert!(matches!(garbage, Err(WireError::Codec(_))));
+    }
+}
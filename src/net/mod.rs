@@ -0,0 +1,86 @@
+//! Protocole réseau entre une station et des robots exécutés dans des
+//! processus séparés, sur une socket locale (TCP ou Unix).
+
+pub mod client;
+pub mod codec;
+pub mod server;
+pub mod socket;
+
+use std::fmt;
+use std::io;
+
+use serde::{Deserialize, Serialize};
+
+use crate::map::Map;
+use crate::robot::{Resources, Robot};
+use crate::station::communication::{RobotPacket, StationMessage};
+use crate::station::{RobotType, Station};
+
+/// Trames envoyées par un processus robot
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ClientFrame {
+    /// Première trame d'une connexion : demande la construction d'un robot
+    Hello { robot_type: RobotType },
+    Packet(RobotPacket),
+    /// Chargement déposé à quai sur la réplique, à créditer au stock de la station
+    Unload(Resources),
+    /// Énergie tirée de la réplique pendant une recharge à quai
+    Recharge { energy: u32 },
+}
+
+/// Trames envoyées par la station
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ServerFrame {
+    Welcome(Box<Welcome>),
+    Refused { reason: String },
+    Message(StationMessage),
+}
+
+/// Réponse à `Hello` : le robot construit et l'état du monde au moment de
+/// la connexion, dont le processus robot garde une réplique locale
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Welcome {
+    pub tick: u64,
+    pub map: Map,
+    pub station: Station,
+    pub robot: Robot,
+}
+
+#[derive(Debug)]
+pub enum WireError {
+    Io(io::Error),
+    Codec(serde_json::Error),
+    FrameTooLarge(usize),
+    InvalidAddress(String),
+    UnexpectedFrame,
+    Refused(String),
+    Disconnected,
+}
+
+impl fmt::Display for WireError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WireError::Io(error) => write!(f, "i/o error: {error}"),
+            WireError::Codec(error) => write!(f, "malformed frame: {error}"),
+            WireError::FrameTooLarge(len) => write!(f, "frame of {len} bytes exceeds the limit"),
+            WireError::InvalidAddress(address) => write!(f, "invalid address '{address}'"),
+            WireError::UnexpectedFrame => write!(f, "unexpected frame"),
+            WireError::Refused(reason) => write!(f, "refused by the station: {reason}"),
+            WireError::Disconnected => write!(f, "connection closed"),
+        }
+    }
+}
+
+impl std::error::Error for WireError {}
+
+impl From<io::Error> for WireError {
+    fn from(error: io::Error) -> Self {
+        WireError::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for WireError {
+    fn from(error: serde_json::Error) -> Self {
+        WireError::Codec(error)
+    }
+}
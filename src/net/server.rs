@@ -0,0 +1,442 @@
+use std::collections::HashSet;
+use std::io::BufReader;
+use std::thread;
+
+use crossbeam::channel::{self, Receiver, Sender};
+
+use crate::net::codec::{read_frame, write_frame};
+use crate::net::socket::{Address, Listener, Stream};
+use crate::net::{ClientFrame, ServerFrame, Welcome, WireError};
+use crate::robot::resources::ResourceType;
+use crate::robot::Resources;
+use crate::simulation::events::SimEvent;
+use crate::simulation::Simulation;
+use crate::station::communication::{
+    RobotMessage, RobotPacket, StationCommunication, StationMessage,
+};
+use crate::station::fleet::FleetEvent;
+use crate::station::RobotType;
+
+type JoinReply = Result<(Welcome, Receiver<StationMessage>), String>;
+
+/// Événements remontés par les threads de connexion à la boucle de simulation
+enum ServerEvent {
+    Join {
+        robot_type: RobotType,
+        reply: Sender<JoinReply>,
+    },
+    Left {
+        robot_id: u32,
+    },
+    Unload {
+        robot_id: u32,
+        cargo: Resources,
+    },
+    Recharge {
+        robot_id: u32,
+        energy: u32,
+    },
+}
+
+/// Station accessible par des robots exécutés dans d'autres processus. Chaque
+/// connexion est servie par deux threads (lecture et écriture) ; la simulation
+/// n'est modifiée que par `exchange`, depuis la boucle principale. La perte
+/// d'un processus robot ferme sa connexion sans affecter les autres ; un robot
+/// silencieux jusqu'à être déclaré perdu est déconnecté.
+pub struct StationServer {
+    address: Address,
+    events: Receiver<ServerEvent>,
+    station: StationCommunication,
+    connected: HashSet<u32>,
+}
+
+impl StationServer {
+    pub fn bind(address: &Address) -> Result<Self, WireError> {
+        let listener = Listener::bind(address)?;
+        let address = listener.local_address()?;
+        let (event_sender, events) = channel::unbounded();
+        let (packet_sender, packets) = channel::unbounded();
+
+        thread::spawn(move || {
+            while let Ok(stream) = listener.accept() {
+                let events = event_sender.clone();
+                let packets = packet_sender.clone();
+                thread::spawn(move || {
+                    let _ = serve_connection(stream, events, packets);
+                });
+            }
+        });
+
+        Ok(Self {
+            address,
+            events,
+            station: StationCommunication::new(packets),
+            connected: HashSet::new(),
+        })
+    }
+
+    pub fn address(&self) -> &Address {
+        &self.address
+    }
+
+    /// Identifiants des robots actuellement connectés
+    pub fn connected(&self) -> &HashSet<u32> {
+        &self.connected
+    }
+
+    /// Accueille les nouveaux robots, retire ceux dont la connexion est perdue
+    /// et applique les messages reçus à la simulation
+    pub fn exchange(&mut self, sim: &mut Simulation) {
+        while let Ok(event) = self.events.try_recv() {
+            match event {
+                ServerEvent::Join { robot_type, reply } => {
+                    let robot = match sim.station.try_create_robot(robot_type) {
+                        Ok(robot) => robot,
+                        Err(error) => {
+                            let reason =
+                                format!("station cannot build a {}: {error}", robot_type.name());
+                            let _ = reply.send(Err(reason));
+                            continue;
+                        }
+                    };
+
+                    sim.events.publish(
+                        sim.tick,
+                        Some(robot.id),
+                        SimEvent::RobotSpawned {
+                            robot_type,
+                            position: robot.position,
+                        },
+                    );
+                    let (sender, outbox) = channel::unbounded();
+                    self.station.register_robot(robot.id, sender);
+                    self.connected.insert(robot.id);
+                    let welcome = Welcome {
+                        tick: sim.tick,
+                        map: sim.map.clone(),
+                        station: sim.station.clone(),
+                        robot,
+                    };
+                    let _ = reply.send(Ok((welcome, outbox)));
+                }
+                ServerEvent::Left { robot_id } => {
+                    self.station.unregister_robot(robot_id);
+                    self.connected.remove(&robot_id);
+                }
+                // Le robot est servi à quai sur sa réplique : le stock de référence est ici
+                ServerEvent::Unload { robot_id, cargo } => {
+                    if self.connected.contains(&robot_id) {
+                        sim.station.receive_cargo(&cargo);
+                    }
+                }
+                ServerEvent::Recharge { robot_id, energy } => {
+                    if self.connected.contains(&robot_id) {
+                        sim.station.draw_energy(energy);
+                    }
+                }
+            }
+        }
+
+        for (robot_id, message) in self.station.process_messages(&mut sim.station.knowledge) {
+            sim.station.fleet.observe_message(robot_id, sim.tick, &message);
+            match message {
+                RobotMessage::ResourceDiscovered {
+                    resource_type,
+                    position,
+                    amount,
+                    ..
+                } => {
+                    sim.station
+                        .report_resource_found(resource_type.clone(), position);
+                    sim.events.publish(
+                        sim.tick,
+                        Some(robot_id),
+                        SimEvent::ResourceDiscovered {
+                            resource_type,
+                            position,
+                            amount,
+                        },
+                    );
+                }
+                // Le robot extrait sur sa réplique : la carte de référence est ici
+                RobotMessage::ResourceConsumed {
+                    resource_type,
+                    position,
+                    amount,
+                    robot_id: _,
+                } => {
+                    sim.events.publish(
+                        sim.tick,
+                        Some(robot_id),
+                        SimEvent::ResourceConsumed {
+                            resource_type: resource_type.clone(),
+                            position,
+                            amount,
+                        },
+                    );
+                    let (x, y) = (position.x, position.y);
+                    match resource_type {
+                        ResourceType::Energy => sim.map.consume_energy(x, y, amount),
+                        ResourceType::Minerals => sim.map.consume_mineral(x, y, amount),
+                        ResourceType::ScientificData => {
+                            sim.map.analyse_scientific_point(x, y, amount)
+                        }
+                    };
+                }
+                _ => {}
+            }
+        }
+
+        for event in sim.station.fleet.check(sim.tick) {
+            if let FleetEvent::Lost(robot_id) = event {
+                self.station.unregister_robot(robot_id);
+                self.connected.remove(&robot_id);
+            }
+        }
+    }
+}
+
+fn serve_connection(
+    stream: Stream,
+    events: Sender<ServerEvent>,
+    packets: Sender<RobotPacket>,
+) -> Result<(), WireError> {
+    let mut reader = BufReader::new(stream.try_clone()?);
+    let mut writer = stream;
+
+    let Some(ClientFrame::Hello { robot_type }) = read_frame(&mut reader)? else {
+        return Err(WireError::UnexpectedFrame);
+    };
+    let (reply, joined) = channel::bounded(1);
+    events
+        .send(ServerEvent::Join { robot_type, reply })
+        .map_err(|_| WireError::Disconnected)?;
+
+    let (welcome, outbox) = match joined.recv().map_err(|_| WireError::Disconnected)? {
+        Ok(joined) => joined,
+        Err(reason) => {
+            write_frame(&mut writer, &ServerFrame::Refused { reason })?;
+            return Ok(());
+        }
+    };
+    let robot_id = welcome.robot.id;
+    let written = write_frame(&mut writer, &ServerFrame::Welcome(Box::new(welcome)));
+    if let Err(error) = written {
+        let _ = events.send(ServerEvent::Left { robot_id });
+        return Err(error);
+    }
+
+    // Le canal se ferme quand la station oublie le robot
+    thread::spawn(move || {
+        for message in outbox.iter() {
+            if write_frame(&mut writer, &ServerFrame::Message(message)).is_err() {
+                break;
+            }
+        }
+        let _ = writer.shutdown();
+    });
+
+    let result = loop {
+        match read_frame(&mut reader) {
+            // L'identifiant est celui de la connexion, pas celui annoncé
+            Ok(Some(ClientFrame::Packet(mut packet))) => {
+                packet.robot_id = robot_id;
+                if packets.send(packet).is_err() {
+                    break Err(WireError::Disconnected);
+                }
+            }
+            Ok(Some(ClientFrame::Unload(cargo))) => {
+                if events
+                    .send(ServerEvent::Unload { robot_id, cargo })
+                    .is_err()
+                {
+                    break Err(WireError::Disconnected);
+                }
+            }
+            Ok(Some(ClientFrame::Recharge { energy })) => {
+                if events
+                    .send(ServerEvent::Recharge { robot_id, energy })
+                    .is_err()
+                {
+                    break Err(WireError::Disconnected);
+                }
+            }
+            Ok(Some(ClientFrame::Hello { .. })) => break Err(WireError::UnexpectedFrame),
+            Ok(None) => break Ok(()),
+            Err(error) => break Err(error),
+        }
+    };
+    let _ = events.send(ServerEvent::Left { robot_id });
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::{Mineral, Tile};
+    use crate::net::client::RobotClient;
+    use crate::robot::{Position, State};
+    use std::time::{Duration, Instant};
+
+    fn tcp_server() -> (StationServer, Simulation) {
+        let server = StationServer::bind(&"127.0.0.1:0".parse().unwrap()).unwrap();
+        (server, Simulation::new(60, 60, 42))
+    }
+
+    /// La connexion attend la réponse de la boucle de la station : elle est
+    /// faite depuis un autre thread pendant que la station tourne
+    fn join(
+        server: &mut StationServer,
+        sim: &mut Simulation,
+        robot_type: RobotType,
+    ) -> Result<RobotClient, WireError> {
+        let address = server.address().clone();
+        let handle = thread::spawn(move || RobotClient::connect(&address, robot_type));
+        while !handle.is_finished() {
+            server.exchange(sim);
+            thread::sleep(Duration::from_millis(1));
+        }
+        handle.join().unwrap()
+    }
+
+    fn run_until(
+        server: &mut StationServer,
+        sim: &mut Simulation,
+        clients: &mut [RobotClient],
+        done: impl Fn(&StationServer, &Simulation, &[RobotClient]) -> bool,
+    ) -> bool {
+        let deadline = Instant::now() + Duration::from_secs(10);
+        while Instant::now() < deadline {
+            sim.step();
+            server.exchange(sim);
+            for client in clients.iter_mut() {
+                let _ = client.step();
+            }
+            if done(server, sim, clients) {
+                return true;
+            }
+            thread::sleep(Duration::from_millis(1));
+        }
+        false
+    }
+
+    #[test]
+    fn test_remote_robot_reports_and_syncs_its_map() {
+        let (mut server, mut sim) = tcp_server();
+        let mut client = join(&mut server, &mut sim, RobotType::Explorer).unwrap();
+        let robot_id = client.robot().id;
+        assert!(server.connected().contains(&robot_id));
+        assert!(sim.station.robots.iter().any(|robot| robot.id == robot_id));
+        assert_eq!(client.sim.station.position, sim.station.position);
+
+        let deposit = Position { x: 50, y: 50 };
+        *sim.map.get_tile_mut(deposit.x, deposit.y).unwrap() = Tile::Mineral(Mineral {
+            amount: 300,
+            is_base: false,
+        });
+        client.sim.robots[0]
+            .outbox
+            .push_back(RobotMessage::ResourceDiscovered {
+                resource_type: ResourceType::Minerals,
+                position: deposit,
+                amount: 300,
+                confidence: 1.0,
+            });
+
+        let mut clients = [client];
+        let synced = run_until(&mut server, &mut sim, &mut clients, |_, sim, clients| {
+            sim.station
+                .discovered_resources
+                .mineral_locations
+                .contains(&deposit)
+                && clients[0].unacked() == 0
+                && sim.station.knowledge.explored_count() > 0
+                && clients[0].robot().knowledge.version == sim.station.knowledge.version
+        });
+        assert!(synced);
+    }
+
+    #[test]
+    fn test_welcome_leaves_the_station_history_behind() {
+        let mut sim = Simulation::new(60, 60, 42);
+        sim.station.history.add_minerals_collected(3, 40);
+        let robot = sim.station.try_create_robot(RobotType::Explorer).unwrap();
+        let welcome = Welcome {
+            tick: sim.tick,
+            map: sim.map.clone(),
+            station: sim.station.clone(),
+            robot,
+        };
+
+        let json = serde_json::to_string(&welcome).unwrap();
+        let received: Welcome = serde_json::from_str(&json).unwrap();
+        assert!(received.station.history.collected.is_empty());
+        assert_eq!(received.station.resources, sim.station.resources);
+    }
+
+    #[test]
+    fn test_remote_robot_unloads_into_the_station_stock() {
+        let (mut server, mut sim) = tcp_server();
+        let mut client = join(&mut server, &mut sim, RobotType::Miner).unwrap();
+        let minerals = sim.station.resources.minerals;
+
+        let robot = &mut client.sim.robots[0];
+        robot.inventory.minerals = 40;
+        robot.state = State::Returning {
+            base_position: sim.station.position,
+            path: Vec::new(),
+        };
+
+        let mut clients = [client];
+        let unloaded = run_until(&mut server, &mut sim, &mut clients, |_, sim, clients| {
+            clients[0].robot().inventory.is_empty()
+                && sim.station.history.total_collected().minerals == 40
+        });
+        assert!(unloaded);
+        assert_eq!(sim.station.resources.minerals, minerals + 40);
+    }
+
+    #[test]
+    fn test_crashed_robot_does_not_affect_the_others() {
+        let (mut server, mut sim) = tcp_server();
+        let first = join(&mut server, &mut sim, RobotType::Explorer).unwrap();
+        let second = join(&mut server, &mut sim, RobotType::Explorer).unwrap();
+        let survivor = second.robot().id;
+        assert_eq!(server.connected().len(), 2);
+
+        drop(first);
+        let mut clients = [second];
+        let isolated = run_until(&mut server, &mut sim, &mut clients, |server, _, clients| {
+            server.connected().len() == 1
+                && server.connected().contains(&survivor)
+                && clients[0].is_connected()
+        });
+        assert!(isolated);
+        assert!(clients[0].step().is_ok());
+    }
+
+    #[test]
+    fn test_station_refuses_robots_it_cannot_build() {
+        let (mut server, mut sim) = tcp_server();
+        sim.station.resources.energy = 0;
+
+        let refused = join(&mut server, &mut sim, RobotType::Miner);
+        assert!(matches!(refused, Err(WireError::Refused(_))));
+        assert!(server.connected().is_empty());
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_unix_socket_transport() {
+        let path = std::env::temp_dir().join(format!("robot_swarm_{}.sock", std::process::id()));
+        let mut server = StationServer::bind(&Address::Unix(path.clone())).unwrap();
+        let mut sim = Simulation::new(60, 60, 42);
+
+        let client = join(&mut server, &mut sim, RobotType::Scientist).unwrap();
+        assert_eq!(client.robot().role(), RobotType::Scientist);
+        assert!(server.connected().contains(&client.robot().id));
+
+        drop(client);
+        let _ = std::fs::remove_file(path);
+    }
+}
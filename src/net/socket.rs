@@ -0,0 +1,227 @@
+use std::fmt;
+use std::io::{self, Read, Write};
+use std::net::{Shutdown, TcpListener, TcpStream};
+#[cfg(unix)]
+use std::os::unix::fs::FileTypeExt;
+#[cfg(unix)]
+use std::os::unix::net::{UnixListener, UnixStream};
+#[cfg(unix)]
+use std::path::PathBuf;
+use std::str::FromStr;
+
+use crate::net::WireError;
+
+/// Adresse d'écoute par défaut de la station
+pub const DEFAULT_ADDRESS: &str = "127.0.0.1:7878";
+
+/// Adresse locale : `hôte:port` pour TCP, `unix:chemin` pour une socket Unix
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Address {
+    Tcp(String),
+    #[cfg(unix)]
+    Unix(PathBuf),
+}
+
+impl FromStr for Address {
+    type Err = WireError;
+
+    fn from_str(address: &str) -> Result<Self, Self::Err> {
+        #[cfg(unix)]
+        if let Some(path) = address.strip_prefix("unix:") {
+            if path.is_empty() {
+                return Err(WireError::InvalidAddress(address.to_string()));
+            }
+            return Ok(Address::Unix(PathBuf::from(path)));
+        }
+
+        let host_port = address.strip_prefix("tcp:").unwrap_or(address);
+        match host_port.rsplit_once(':') {
+            Some((host, port)) if !host.is_empty() && port.parse::<u16>().is_ok() => {
+                Ok(Address::Tcp(host_port.to_string()))
+            }
+            _ => Err(WireError::InvalidAddress(address.to_string())),
+        }
+    }
+}
+
+impl fmt::Display for Address {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Address::Tcp(host_port) => write!(f, "{host_port}"),
+            #[cfg(unix)]
+            Address::Unix(path) => write!(f, "unix:{}", path.display()),
+        }
+    }
+}
+
+/// Connexion établie, quel que soit le type de socket
+pub enum Stream {
+    Tcp(TcpStream),
+    #[cfg(unix)]
+    Unix(UnixStream),
+}
+
+impl Stream {
+    pub fn connect(address: &Address) -> io::Result<Stream> {
+        match address {
+            Address::Tcp(host_port) => {
+                let stream = TcpStream::connect(host_port)?;
+                stream.set_nodelay(true)?;
+                Ok(Stream::Tcp(stream))
+            }
+            #[cfg(unix)]
+            Address::Unix(path) => Ok(Stream::Unix(UnixStream::connect(path)?)),
+        }
+    }
+
+    pub fn try_clone(&self) -> io::Result<Stream> {
+        match self {
+            Stream::Tcp(stream) => Ok(Stream::Tcp(stream.try_clone()?)),
+            #[cfg(unix)]
+            Stream::Unix(stream) => Ok(Stream::Unix(stream.try_clone()?)),
+        }
+    }
+
+    /// Ferme la connexion dans les deux sens, débloquant les lectures en cours
+    pub fn shutdown(&self) -> io::Result<()> {
+        match self {
+            Stream::Tcp(stream) => stream.shutdown(Shutdown::Both),
+            #[cfg(unix)]
+            Stream::Unix(stream) => stream.shutdown(Shutdown::Both),
+        }
+    }
+}
+
+impl Read for Stream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        match self {
+            Stream::Tcp(stream) => stream.read(buf),
+            #[cfg(unix)]
+            Stream::Unix(stream) => stream.read(buf),
+        }
+    }
+}
+
+impl Write for Stream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Stream::Tcp(stream) => stream.write(buf),
+            #[cfg(unix)]
+            Stream::Unix(stream) => stream.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Stream::Tcp(stream) => stream.flush(),
+            #[cfg(unix)]
+            Stream::Unix(stream) => stream.flush(),
+        }
+    }
+}
+
+pub enum Listener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(UnixListener),
+}
+
+impl Listener {
+    /// Écoute sur l'adresse. Une socket Unix laissée par un processus
+    /// précédent est remplacée ; tout autre fichier à ce chemin est conservé
+    /// et l'écoute échoue.
+    pub fn bind(address: &Address) -> io::Result<Listener> {
+        match address {
+            Address::Tcp(host_port) => Ok(Listener::Tcp(TcpListener::bind(host_port)?)),
+            #[cfg(unix)]
+            Address::Unix(path) => {
+                match std::fs::symlink_metadata(path) {
+                    Ok(metadata) if metadata.file_type().is_socket() => {
+                        std::fs::remove_file(path)?;
+                    }
+                    Ok(_) => {
+                        return Err(io::Error::new(
+                            io::ErrorKind::AlreadyExists,
+                            format!("{} exists and is not a socket", path.display()),
+                        ))
+                    }
+                    Err(error) if error.kind() == io::ErrorKind::NotFound => {}
+                    Err(error) => return Err(error),
+                }
+                Ok(Listener::Unix(UnixListener::bind(path)?))
+            }
+        }
+    }
+
+    pub fn accept(&self) -> io::Result<Stream> {
+        match self {
+            Listener::Tcp(listener) => {
+                let (stream, _) = listener.accept()?;
+                stream.set_nodelay(true)?;
+                Ok(Stream::Tcp(stream))
+            }
+            #[cfg(unix)]
+            Listener::Unix(listener) => Ok(Stream::Unix(listener.accept()?.0)),
+        }
+    }
+
+    /// Adresse effective, utile quand le port TCP demandé est 0
+    pub fn local_address(&self) -> io::Result<Address> {
+        match self {
+            Listener::Tcp(listener) => Ok(Address::Tcp(listener.local_addr()?.to_string())),
+            #[cfg(unix)]
+            Listener::Unix(listener) => {
+                let address = listener.local_addr()?;
+                let path = address
+                    .as_pathname()
+                    .ok_or_else(|| io::Error::other("unnamed unix socket"))?;
+                Ok(Address::Unix(path.to_path_buf()))
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_addresses() {
+        assert_eq!(
+            "127.0.0.1:7878".parse::<Address>().unwrap(),
+            Address::Tcp("127.0.0.1:7878".to_string())
+        );
+        assert_eq!(
+            "tcp:localhost:9000".parse::<Address>().unwrap(),
+            Address::Tcp("localhost:9000".to_string())
+        );
+        #[cfg(unix)]
+        assert_eq!(
+            "unix:/tmp/swarm.sock".parse::<Address>().unwrap(),
+            Address::Unix(PathBuf::from("/tmp/swarm.sock"))
+        );
+        assert!("localhost".parse::<Address>().is_err());
+        assert!("127.0.0.1:port".parse::<Address>().is_err());
+        assert!("unix:".parse::<Address>().is_err());
+
+        let address: Address = DEFAULT_ADDRESS.parse().unwrap();
+        assert_eq!(address.to_string(), DEFAULT_ADDRESS);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_bind_only_replaces_stale_sockets() {
+        let dir = std::env::temp_dir();
+        let socket = dir.join(format!("robot_swarm_stale_{}.sock", std::process::id()));
+        drop(Listener::bind(&Address::Unix(socket.clone())).unwrap());
+        assert!(Listener::bind(&Address::Unix(socket.clone())).is_ok());
+        std::fs::remove_file(&socket).unwrap();
+
+        let file = dir.join(format!("robot_swarm_file_{}.sock", std::process::id()));
+        std::fs::write(&file, "keep").unwrap();
+        let error = Listener::bind(&Address::Unix(file.clone())).err().unwrap();
+        assert_eq!(error.kind(), io::ErrorKind::AlreadyExists);
+        assert_eq!(std::fs::read_to_string(&file).unwrap(), "keep");
+        std::fs::remove_file(&file).unwrap();
+    }
+}
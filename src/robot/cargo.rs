@@ -0,0 +1,153 @@
+use serde::{Deserialize, Serialize};
+
+use crate::robot::resources::ResourceType;
+use crate::robot::{HardwareModule, Robot};
+
+/// Capacité de chargement d'un robot sans soute
+pub const BASE_CARGO_CAPACITY: u32 = 20;
+
+/// Règles de décision de retour à la station en fonction du chargement
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct CargoPolicy {
+    /// Taux de remplissage à partir duquel un robot rentre quand son gisement est épuisé
+    pub partial_return_ratio: f32,
+}
+
+impl Default for CargoPolicy {
+    fn default() -> Self {
+        Self {
+            partial_return_ratio: 0.5,
+        }
+    }
+}
+
+impl Robot {
+    pub fn cargo_capacity(&self) -> u32 {
+        BASE_CARGO_CAPACITY
+            + self
+                .modules
+                .iter()
+                .map(|module| match module {
+                    HardwareModule::CargoHold { capacity } => *capacity,
+                    _ => 0,
+                })
+                .sum::<u32>()
+    }
+
+    pub fn cargo_load(&self) -> u32 {
+        self.inventory.energy + self.inventory.minerals + self.inventory.scientific_data
+    }
+
+    pub fn free_cargo(&self) -> u32 {
+        self.cargo_capacity().saturating_sub(self.cargo_load())
+    }
+
+    /// Quantité récoltée par tick pour un type de ressource, si le robot a le module adapté
+    pub fn harvest_rate(&self, resource_type: &ResourceType) -> Option<u32> {
+        self.modules
+            .iter()
+            .find_map(|module| match (module, resource_type) {
+                (HardwareModule::DeepDrill { mining_speed }, ResourceType::Minerals) => {
+                    Some((mining_speed * 5.0) as u32)
+                }
+                (HardwareModule::EnergyHarvester { collection_rate }, ResourceType::Energy) => {
+                    Some((collection_rate * 5.0) as u32)
+                }
+                (
+                    HardwareModule::SpectralAnalyzer { analysis_accuracy },
+                    ResourceType::ScientificData,
+                ) => Some((analysis_accuracy * 10.0) as u32),
+                _ => None,
+            })
+    }
+
+    /// Décide si le robot doit rentrer décharger : soute pleine, ou chargement
+    /// partiel suffisant alors que le gisement en cours est épuisé
+    pub fn should_return_with_cargo(&self, policy: &CargoPolicy, deposit_depleted: bool) -> bool {
+        let capacity = self.cargo_capacity();
+        if capacity == 0 || self.free_cargo() == 0 {
+            return true;
+        }
+
+        let load_ratio = self.cargo_load() as f32 / capacity as f32;
+        deposit_depleted && load_ratio >= policy.partial_return_ratio
+    }
+
+    /// Transfère autant de chargement que possible vers un autre robot.
+    /// Retourne la quantité transférée.
+    pub fn transfer_cargo_to(&mut self, other: &mut Robot) -> u32 {
+        let mut free = other.free_cargo();
+        let mut transferred = 0;
+
+        for (from, to) in [
+            (&mut self.inventory.minerals, &mut other.inventory.minerals),
+            (&mut self.inventory.energy, &mut other.inventory.energy),
+            (
+                &mut self.inventory.scientific_data,
+                &mut other.inventory.scientific_data,
+            ),
+        ] {
+            let amount = (*from).min(free);
+            *from -= amount;
+            *to += amount;
+            free -= amount;
+            transferred += amount;
+        }
+
+        transferred
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::robot::Position;
+
+    fn hauler(capacity: u32) -> Robot {
+        Robot::new(
+            Position { x: 0, y: 0 },
+            vec![HardwareModule::CargoHold { capacity }],
+        )
+    }
+
+    #[test]
+    fn test_capacity_comes_from_cargo_holds() {
+        assert_eq!(
+            Robot::new(Position { x: 0, y: 0 }, vec![]).cargo_capacity(),
+            BASE_CARGO_CAPACITY
+        );
+        assert_eq!(hauler(100).cargo_capacity(), BASE_CARGO_CAPACITY + 100);
+    }
+
+    #[test]
+    fn test_partial_load_return_decision() {
+        let policy = CargoPolicy::default();
+        let mut robot = hauler(80);
+
+        robot.inventory.minerals = 40;
+        assert!(!robot.should_return_with_cargo(&policy, false));
+        assert!(!robot.should_return_with_cargo(&policy, true));
+
+        robot.inventory.minerals = 60;
+        assert!(!robot.should_return_with_cargo(&policy, false));
+        assert!(robot.should_return_with_cargo(&policy, true));
+
+        robot.inventory.minerals = 100;
+        assert!(robot.should_return_with_cargo(&policy, false));
+    }
+
+    #[test]
+    fn test_transfer_respects_receiver_capacity() {
+        let mut giver = hauler(100);
+        giver.inventory.minerals = 15;
+        giver.inventory.energy = 30;
+        let mut receiver = Robot::new(Position { x: 1, y: 0 }, vec![]);
+
+        let transferred = giver.transfer_cargo_to(&mut receiver);
+
+        assert_eq!(transferred, BASE_CARGO_CAPACITY);
+        assert_eq!(receiver.inventory.minerals, 15);
+        assert_eq!(receiver.inventory.energy, BASE_CARGO_CAPACITY - 15);
+        assert_eq!(giver.cargo_load(), 45 - BASE_CARGO_CAPACITY);
+    }
+}
@@ -1,22 +1,75 @@
 use crate::robot::{Position, ResourceType};
-use crate::station::communication::{RobotMessage, StationMessage};
+use crate::station::communication::{RobotMessage, RobotPacket, StationMessage};
+use crate::station::sync::MapKnowledge;
 use crossbeam::channel::{Receiver, Sender};
-use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+type ResourceTable = HashMap<Position, u32>;
+
+/// Délai avant la première réémission d'un rapport non acquitté, en ticks
+pub const RETRANSMIT_BASE_TICKS: u64 = 3;
+
+/// Délai maximal entre deux réémissions, en ticks
+pub const RETRANSMIT_MAX_TICKS: u64 = 48;
+
+/// Délai d'attente après `attempts` émissions, doublé à chaque tentative
+pub fn retransmit_delay(attempts: u32) -> u64 {
+    let doublings = attempts.saturating_sub(1).min(16);
+    (RETRANSMIT_BASE_TICKS << doublings).min(RETRANSMIT_MAX_TICKS)
+}
+
+/// Rapport émis en attente d'acquittement
+#[derive(Debug, Clone)]
+struct PendingReport {
+    message: RobotMessage,
+    sent_at: u64,
+    attempts: u32,
+}
+
+/// État de la liaison radio d'un robot avec la station
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LinkStatus {
+    #[default]
+    NoSignal,
+    Direct,
+    Relayed {
+        via: u32,
+    },
+}
+
+impl LinkStatus {
+    pub fn is_connected(&self) -> bool {
+        !matches!(self, LinkStatus::NoSignal)
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            LinkStatus::NoSignal => "no signal".to_string(),
+            LinkStatus::Direct => "direct link".to_string(),
+            LinkStatus::Relayed { via } => format!("relayed via #{via}"),
+        }
+    }
+}
 
 pub struct RobotCommunication {
     pub robot_id: u32,
-    pub station_sender: Sender<(u32, RobotMessage)>,
+    pub station_sender: Sender<RobotPacket>,
     pub station_receiver: Receiver<StationMessage>,
     local_energy_resources: HashMap<Position, u32>,
     local_mineral_resources: HashMap<Position, u32>,
     local_scientific_resources: HashMap<Position, u32>,
     pending_consumed_resources: Vec<(ResourceType, Position, u32)>,
+    depleted_resources: HashSet<Position>,
+    next_message_id: u64,
+    unacked: BTreeMap<u64, PendingReport>,
+    now: u64,
 }
 
 impl RobotCommunication {
     pub fn new(
         robot_id: u32,
-        station_sender: Sender<(u32, RobotMessage)>,
+        station_sender: Sender<RobotPacket>,
         station_receiver: Receiver<StationMessage>,
     ) -> Self {
         Self {
@@ -27,15 +80,82 @@ impl RobotCommunication {
             local_mineral_resources: HashMap::new(),
             local_scientific_resources: HashMap::new(),
             pending_consumed_resources: Vec::new(),
+            depleted_resources: HashSet::new(),
+            next_message_id: 0,
+            unacked: BTreeMap::new(),
+            now: 0,
+        }
+    }
+
+    /// Envoie un message avec un nouvel identifiant. Les rapports sont gardés
+    /// jusqu'à leur acquittement pour être réémis. Retourne l'identifiant.
+    pub fn send(&mut self, message: RobotMessage) -> u64 {
+        let message_id = self.next_message_id;
+        self.next_message_id += 1;
+
+        if message.requires_ack() {
+            self.unacked.insert(
+                message_id,
+                PendingReport {
+                    message: message.clone(),
+                    sent_at: self.now,
+                    attempts: 1,
+                },
+            );
+        }
+        self.transmit(message_id, message);
+        message_id
+    }
+
+    /// Avance l'horloge et réémet, avec le même identifiant, les rapports dont
+    /// le délai d'attente est écoulé. Retourne le nombre de réémissions.
+    pub fn retransmit(&mut self, now: u64) -> usize {
+        self.now = now;
+        let due: Vec<u64> = self
+            .unacked
+            .iter()
+            .filter(|(_, pending)| now >= pending.sent_at + retransmit_delay(pending.attempts))
+            .map(|(message_id, _)| *message_id)
+            .collect();
+
+        for message_id in &due {
+            let Some(pending) = self.unacked.get_mut(message_id) else {
+                continue;
+            };
+            pending.sent_at = now;
+            pending.attempts += 1;
+            let message = pending.message.clone();
+            self.transmit(*message_id, message);
         }
+        due.len()
+    }
+
+    /// Nombre de rapports émis et pas encore acquittés
+    pub fn unacked_count(&self) -> usize {
+        self.unacked.len()
     }
 
-    pub fn report_resource_discovered(&self, resource_type: ResourceType, position: Position) {
-        let message = RobotMessage::ResourceDiscovered {
+    fn transmit(&self, message_id: u64, message: RobotMessage) {
+        let _ = self.station_sender.send(RobotPacket {
+            robot_id: self.robot_id,
+            message_id,
+            message,
+        });
+    }
+
+    pub fn report_resource_discovered(
+        &mut self,
+        resource_type: ResourceType,
+        position: Position,
+        amount: u32,
+        confidence: f32,
+    ) {
+        self.send(RobotMessage::ResourceDiscovered {
             resource_type,
             position,
-        };
-        let _ = self.station_sender.send((self.robot_id, message));
+            amount,
+            confidence,
+        });
     }
 
     pub fn register_consumed_resource(
@@ -62,7 +182,12 @@ impl RobotCommunication {
                 }
             }
             ResourceType::ScientificData => {
-                self.local_scientific_resources.remove(&position);
+                if let Some(current) = self.local_scientific_resources.get_mut(&position) {
+                    *current = current.saturating_sub(amount);
+                    if *current == 0 {
+                        self.local_scientific_resources.remove(&position);
+                    }
+                }
             }
         }
 
@@ -70,24 +195,34 @@ impl RobotCommunication {
             .push((resource_type, position, amount));
     }
 
+    /// Les consommations quittent la file d'attente mais restent réémises
+    /// jusqu'à leur acquittement
     pub fn report_pending_consumed_resources(&mut self) {
-        for (resource_type, position, amount) in self.pending_consumed_resources.drain(..) {
-            let message = RobotMessage::ResourceConsumed {
+        let pending: Vec<_> = self.pending_consumed_resources.drain(..).collect();
+        for (resource_type, position, amount) in pending {
+            self.send(RobotMessage::ResourceConsumed {
                 resource_type,
                 position,
                 amount,
                 robot_id: self.robot_id,
-            };
-            let _ = self.station_sender.send((self.robot_id, message));
+            });
         }
     }
 
-    pub fn request_resources_state(&self) {
-        let message = RobotMessage::RequestResourcesState;
-        let _ = self.station_sender.send((self.robot_id, message));
+    pub fn request_resources_state(&mut self) {
+        self.send(RobotMessage::RequestResourcesState);
+    }
+
+    /// Envoie les cases découvertes depuis la dernière synchronisation avec
+    /// la version de carte connue, pour ne recevoir que les changements
+    pub fn request_map_sync(&mut self, knowledge: &mut MapKnowledge) {
+        self.send(RobotMessage::MapSync {
+            last_seen_version: knowledge.version,
+            tiles: knowledge.take_local_changes().into_iter().collect(),
+        });
     }
 
-    pub fn process_station_messages(&mut self) {
+    pub fn process_station_messages(&mut self, knowledge: &mut MapKnowledge) {
         while let Ok(message) = self.station_receiver.try_recv() {
             match message {
                 StationMessage::ResourcesUpdate {
@@ -95,41 +230,42 @@ impl RobotCommunication {
                     mineral_resources,
                     scientific_resources,
                 } => {
-                    self.local_energy_resources = energy_resources;
-                    self.local_mineral_resources = mineral_resources;
-                    self.local_scientific_resources = scientific_resources;
+                    let depleted = &self.depleted_resources;
+                    let known = |table: ResourceTable| -> ResourceTable {
+                        table
+                            .into_iter()
+                            .filter(|(position, _)| !depleted.contains(position))
+                            .collect()
+                    };
+                    self.local_energy_resources = known(energy_resources);
+                    self.local_mineral_resources = known(mineral_resources);
+                    self.local_scientific_resources = known(scientific_resources);
                 }
                 StationMessage::ResourceUpdate {
                     resource_type,
                     position,
                     remaining,
                 } => {
-                    match resource_type {
-                        ResourceType::Energy => {
-                            if remaining > 0 {
-                                self.local_energy_resources.insert(position, remaining);
-                            } else {
-                                self.local_energy_resources.remove(&position);
-                            }
-                        }
-                        ResourceType::Minerals => {
-                            if remaining > 0 {
-                                self.local_mineral_resources.insert(position, remaining);
-                            } else {
-                                self.local_mineral_resources.remove(&position);
-                            }
-                        }
-                        ResourceType::ScientificData => {
-                            if remaining > 0 {
-                                self.local_scientific_resources.insert(position, remaining);
-                            } else {
-                                self.local_scientific_resources.remove(&position);
-                            }
-                        }
+                    let table = match resource_type {
+                        ResourceType::Energy => &mut self.local_energy_resources,
+                        ResourceType::Minerals => &mut self.local_mineral_resources,
+                        ResourceType::ScientificData => &mut self.local_scientific_resources,
+                    };
+                    // Les gisements ne font que décroître : une mise à jour retardée
+                    // ou dupliquée ne doit pas faire réapparaître des ressources
+                    if remaining == 0 {
+                        table.remove(&position);
+                        self.depleted_resources.insert(position);
+                    } else if !self.depleted_resources.contains(&position) {
+                        let current = table.entry(position).or_insert(remaining);
+                        *current = (*current).min(remaining);
                     }
                 }
-                StationMessage::Acknowledgement { message: _ } => {
-                    // Confirmer une action (pourrait être utilisé pour la log)
+                StationMessage::Acknowledgement { message_id } => {
+                    self.unacked.remove(&message_id);
+                }
+                StationMessage::MapDelta { sync } => {
+                    knowledge.apply_sync(sync);
                 }
             }
         }
@@ -160,13 +296,7 @@ impl RobotCommunication {
         }
     }
 
-    pub fn get_local_resources_state(
-        &self,
-    ) -> (
-        &HashMap<Position, u32>,
-        &HashMap<Position, u32>,
-        &HashMap<Position, u32>,
-    ) {
+    pub fn get_local_resources_state(&self) -> (&ResourceTable, &ResourceTable, &ResourceTable) {
         (
             &self.local_energy_resources,
             &self.local_mineral_resources,
@@ -201,20 +331,24 @@ mod tests {
         let (station_sender, station_receiver) = channel::unbounded();
         let (_, robot_receiver) = channel::unbounded();
         
-        let robot_comm = RobotCommunication::new(1, station_sender, robot_receiver);
+        let mut robot_comm = RobotCommunication::new(1, station_sender, robot_receiver);
         
         let position = Position { x: 15, y: 15 };
-        robot_comm.report_resource_discovered(ResourceType::Energy, position);
+        robot_comm.report_resource_discovered(ResourceType::Energy, position, 250, 0.9);
         
-        if let Ok((robot_id, message)) = station_receiver.recv_timeout(Duration::from_millis(100)) {
+        if let Ok(RobotPacket { robot_id, message, .. }) = station_receiver.recv_timeout(Duration::from_millis(100)) {
             assert_eq!(robot_id, 1);
             match message {
                 RobotMessage::ResourceDiscovered { 
                     resource_type, 
-                    position: pos 
+                    position: pos,
+                    amount,
+                    confidence,
                 } => {
                     assert_eq!(resource_type, ResourceType::Energy);
                     assert_eq!(pos, position);
+                    assert_eq!(amount, 250);
+                    assert_eq!(confidence, 0.9);
                 },
                 _ => panic!("Type de message incorrect envoyé"),
             }
@@ -241,6 +375,27 @@ mod tests {
         assert_eq!(robot_comm.pending_consumed_resources[0], 
                    (ResourceType::Energy, position, 100));
     }
+
+    #[test]
+    fn test_partially_analysed_point_stays_available() {
+        let (station_sender, _) = channel::unbounded();
+        let (_, station_receiver) = channel::unbounded();
+
+        let mut robot_comm = RobotCommunication::new(1, station_sender, station_receiver);
+
+        let position = Position { x: 12, y: 3 };
+        robot_comm.local_scientific_resources.insert(position, 300);
+
+        robot_comm.register_consumed_resource(ResourceType::ScientificData, position, 100);
+        assert_eq!(
+            robot_comm.local_scientific_resources.get(&position),
+            Some(&200)
+        );
+
+        robot_comm.register_consumed_resource(ResourceType::ScientificData, position, 200);
+        assert_eq!(robot_comm.local_scientific_resources.get(&position), None);
+        assert_eq!(robot_comm.pending_consumed_resources.len(), 2);
+    }
     
     #[test]
     fn test_report_pending_consumed_resources() {
@@ -260,7 +415,7 @@ mod tests {
         assert_eq!(robot_comm.pending_consumed_resources.len(), 0);
         
         for _ in 0..2 {
-            if let Ok((robot_id, message)) = station_receiver.recv_timeout(Duration::from_millis(100)) {
+            if let Ok(RobotPacket { robot_id, message, .. }) = station_receiver.recv_timeout(Duration::from_millis(100)) {
                 assert_eq!(robot_id, 1);
                 match message {
                     RobotMessage::ResourceConsumed { .. } => (),
@@ -278,6 +433,7 @@ mod tests {
         let (robot_sender, robot_receiver) = channel::unbounded();
         
         let mut robot_comm = RobotCommunication::new(1, station_sender, robot_receiver);
+        let mut knowledge = MapKnowledge::new();
         
         let position = Position { x: 30, y: 30 };
         let _ = robot_sender.send(StationMessage::ResourceUpdate {
@@ -286,7 +442,7 @@ mod tests {
             remaining: 800,
         });
         
-        robot_comm.process_station_messages();
+        robot_comm.process_station_messages(&mut knowledge);
         
         assert_eq!(robot_comm.local_energy_resources.get(&position), Some(&800));
         
@@ -301,7 +457,7 @@ mod tests {
             scientific_resources: scientific_map.clone(),
         });
         
-        robot_comm.process_station_messages();
+        robot_comm.process_station_messages(&mut knowledge);
         
         assert_eq!(robot_comm.local_energy_resources, energy_map);
         assert_eq!(robot_comm.local_mineral_resources, mineral_map);
@@ -330,4 +486,66 @@ mod tests {
         assert!(robot_comm.is_resource_available(ResourceType::ScientificData, science_pos, 1));
         assert!(!robot_comm.is_resource_available(ResourceType::Energy, Position { x: 999, y: 999 }, 10));
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_delayed_updates_do_not_restore_resources() {
+        let (station_sender, _) = channel::unbounded();
+        let (robot_sender, robot_receiver) = channel::unbounded();
+        let mut robot_comm = RobotCommunication::new(1, station_sender, robot_receiver);
+        let mut knowledge = MapKnowledge::new();
+        let position = Position { x: 30, y: 30 };
+
+        for remaining in [500, 800, 0, 300] {
+            let _ = robot_sender.send(StationMessage::ResourceUpdate {
+                resource_type: ResourceType::Minerals,
+                position,
+                remaining,
+            });
+            robot_comm.process_station_messages(&mut knowledge);
+            if remaining == 800 {
+                assert_eq!(robot_comm.local_mineral_resources.get(&position), Some(&500));
+            }
+        }
+
+        assert!(!robot_comm.local_mineral_resources.contains_key(&position));
+    }
+
+    #[test]
+    fn test_unacked_reports_are_retransmitted_with_backoff() {
+        let (station_sender, station_receiver) = channel::unbounded();
+        let (robot_sender, robot_receiver) = channel::unbounded();
+        let mut robot_comm = RobotCommunication::new(1, station_sender, robot_receiver);
+        let mut knowledge = MapKnowledge::new();
+
+        let report = robot_comm.send(RobotMessage::ResourceConsumed {
+            resource_type: ResourceType::Minerals,
+            position: Position { x: 3, y: 3 },
+            amount: 10,
+            robot_id: 1,
+        });
+        let request = robot_comm.send(RobotMessage::RequestResourcesState);
+        assert_ne!(report, request);
+        assert_eq!(robot_comm.unacked_count(), 1);
+
+        // Réémissions aux ticks 3, 9 et 21 : le délai double à chaque tentative
+        let mut retransmitted_at = Vec::new();
+        for tick in 1..=25 {
+            if robot_comm.retransmit(tick) > 0 {
+                retransmitted_at.push(tick);
+            }
+        }
+        assert_eq!(retransmitted_at, vec![3, 9, 21]);
+
+        let sent: Vec<u64> = station_receiver
+            .try_iter()
+            .map(|packet| packet.message_id)
+            .collect();
+        assert_eq!(sent, vec![report, request, report, report, report]);
+
+        let _ = robot_sender.send(StationMessage::Acknowledgement { message_id: report });
+        robot_comm.process_station_messages(&mut knowledge);
+        assert_eq!(robot_comm.unacked_count(), 0);
+        assert_eq!(robot_comm.retransmit(200), 0);
+        assert_eq!(retransmit_delay(10), RETRANSMIT_MAX_TICKS);
+    }
+}
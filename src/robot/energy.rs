@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+
+use crate::robot::HardwareModule;
+use crate::station::RobotType;
+
+/// Actions ponctuelles qui consomment de l'énergie en plus des déplacements
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum RobotAction {
+    Scan,
+    Drill,
+    Analyse,
+}
+
+/// Modèle de consommation et de recharge de l'énergie des robots
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+pub struct EnergyModel {
+    /// Coût par case parcourue sur terrain plat
+    pub move_cost: f32,
+    pub scan_cost: f32,
+    pub drill_cost: f32,
+    pub analyse_cost: f32,
+    /// Énergie transférée par tick depuis la station vers un robot amarré
+    pub recharge_rate: f32,
+    /// Marge gardée en plus du coût estimé du retour à la station
+    pub return_reserve: f32,
+    /// Énergie minimale pour quitter la station si elle ne peut plus recharger
+    pub min_departure_energy: f32,
+}
+
+/// Facteur de terrain maximal, utilisé pour estimer le pire coût d'un retour
+pub const MAX_TERRAIN_FACTOR: f32 = 2.0;
+
+/// Attente prévue devant les baies d'amarrage avant la recharge, en ticks
+pub const DOCKING_WAIT_TICKS: u32 = 30;
+
+impl Default for EnergyModel {
+    fn default() -> Self {
+        Self {
+            move_cost: 0.1,
+            scan_cost: 0.05,
+            drill_cost: 0.5,
+            analyse_cost: 0.8,
+            recharge_rate: 5.0,
+            return_reserve: 2.0,
+            min_departure_energy: 10.0,
+        }
+    }
+}
+
+impl EnergyModel {
+    pub fn battery_capacity(&self, robot_type: RobotType) -> f32 {
+        match robot_type {
+            RobotType::Explorer => 120.0,
+            RobotType::EnergyCollector => 150.0,
+            RobotType::Miner => 100.0,
+            RobotType::Scientist => 100.0,
+        }
+    }
+
+    /// Consommation passive d'un module par tick
+    pub fn module_drain(&self, module: &HardwareModule) -> f32 {
+        match module {
+            HardwareModule::TerrainScanner { .. } => 0.02,
+            HardwareModule::DeepDrill { .. } => 0.03,
+            HardwareModule::SpectralAnalyzer { .. } => 0.03,
+            HardwareModule::EnergyHarvester { .. } => 0.01,
+            HardwareModule::SignalRelay { .. } => 0.02,
+            HardwareModule::CargoHold { .. } => 0.0,
+        }
+    }
+
+    pub fn idle_drain(&self, modules: &[HardwareModule]) -> f32 {
+        modules.iter().map(|module| self.module_drain(module)).sum()
+    }
+
+    pub fn move_cost(&self, distance: u32, terrain_factor: f32) -> f32 {
+        distance as f32 * self.move_cost * terrain_factor
+    }
+
+    pub fn action_cost(&self, action: RobotAction) -> f32 {
+        match action {
+            RobotAction::Scan => self.scan_cost,
+            RobotAction::Drill => self.drill_cost,
+            RobotAction::Analyse => self.analyse_cost,
+        }
+    }
+
+    /// Estimation pessimiste de l'énergie nécessaire pour rentrer à la station
+    /// par un chemin de `path_length` cases : déplacements sur le pire terrain,
+    /// puis consommation des modules pendant le trajet et l'attente d'une baie
+    pub fn return_cost(&self, path_length: u32, idle_drain: f32) -> f32 {
+        let ticks = path_length + DOCKING_WAIT_TICKS;
+        self.move_cost(path_length, MAX_TERRAIN_FACTOR)
+            + idle_drain * ticks as f32
+            + self.return_reserve
+    }
+}
@@ -0,0 +1,106 @@
+use crate::robot::{HardwareModule, Position, Robot};
+
+/// Portée de communication d'un robot sans relais
+pub const BASE_COMM_RANGE: u32 = 5;
+
+/// Nombre de ticks entre deux échanges de cartes entre robots voisins
+pub const GOSSIP_INTERVAL_TICKS: u64 = 5;
+
+impl Robot {
+    /// Portée de communication, étendue par le meilleur relais installé
+    pub fn comm_range(&self) -> u32 {
+        self.modules
+            .iter()
+            .filter_map(|module| match module {
+                HardwareModule::SignalRelay { range } => Some(*range),
+                _ => None,
+            })
+            .fold(BASE_COMM_RANGE, u32::max)
+    }
+
+    /// Deux robots communiquent si l'un des deux a la portée nécessaire
+    pub fn in_comm_range(&self, other: &Robot) -> bool {
+        let distance = self.position.distance_to(&other.position);
+        distance <= self.comm_range().max(other.comm_range())
+    }
+
+    /// Échange bidirectionnel des cartes partagées. Les cases apprises sont
+    /// intégrées à la connaissance locale. Retourne le nombre de cases apprises.
+    pub fn gossip_with(&mut self, other: &mut Robot) -> usize {
+        let learned = self.shared_map.merge(&other.shared_map);
+        let other_learned = other.shared_map.merge(&self.shared_map);
+
+        self.absorb_shared_tiles(&learned);
+        other.absorb_shared_tiles(&other_learned);
+        learned.len() + other_learned.len()
+    }
+
+    fn absorb_shared_tiles(&mut self, positions: &[Position]) {
+        let policy = self.knowledge.conflict_policy;
+        let tiles: Vec<_> = positions
+            .iter()
+            .filter_map(|position| {
+                self.shared_map
+                    .tile(*position, policy)
+                    .map(|tile| ((position.x, position.y), tile))
+            })
+            .collect();
+        self.knowledge.merge_peer_tiles(tiles);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::{Mineral, Tile};
+    use crate::station::conflict::Observer;
+
+    #[test]
+    fn test_relay_extends_comm_range() {
+        let mut a = Robot::new(Position { x: 0, y: 0 }, vec![]);
+        let b = Robot::new(Position { x: 0, y: 20 }, vec![]);
+        assert_eq!(a.comm_range(), BASE_COMM_RANGE);
+        assert!(!a.in_comm_range(&b));
+
+        a.modules.push(HardwareModule::SignalRelay { range: 30 });
+        assert!(a.in_comm_range(&b));
+        assert!(b.in_comm_range(&a));
+    }
+
+    #[test]
+    fn test_gossip_shares_observations_and_consumption() {
+        let deposit = Position { x: 3, y: 3 };
+        let mut a = Robot::new(Position { x: 3, y: 3 }, vec![]);
+        a.id = 1;
+        let mut b = Robot::new(Position { x: 4, y: 3 }, vec![]);
+        b.id = 2;
+
+        let observer = Observer {
+            robot_id: 1,
+            tick: 1,
+            sensor_efficiency: 0.5,
+        };
+        let tile = Tile::Mineral(Mineral {
+            amount: 100,
+            is_base: false,
+        });
+        a.shared_map.observe(deposit, &tile, observer);
+        a.shared_map.record_consumption(deposit, 1, 30);
+        b.shared_map.record_consumption(deposit, 2, 20);
+
+        assert_eq!(a.gossip_with(&mut b), 2);
+        assert_eq!(a.shared_map, b.shared_map);
+        assert_eq!(b.shared_map.remaining(deposit), Some(50));
+
+        let known = b.knowledge.get_tile(3, 3).unwrap();
+        assert_eq!(
+            known.tile,
+            Tile::Mineral(Mineral {
+                amount: 50,
+                is_base: false,
+            })
+        );
+        assert!(b.knowledge.local_changes.contains(&(3, 3)));
+        assert_eq!(a.gossip_with(&mut b), 0);
+    }
+}
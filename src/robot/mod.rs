@@ -1,39 +1,101 @@
+pub mod cargo;
 pub mod communication;
+pub mod energy;
+pub mod gossip;
 pub mod module;
 pub mod position;
 pub mod resources;
 pub mod state;
 
+use communication::LinkStatus;
+use energy::{EnergyModel, RobotAction, MAX_TERRAIN_FACTOR};
 pub use module::HardwareModule;
 pub use position::Position;
 use resources::ResourceType;
 pub use resources::Resources;
-pub use state::State;
+pub use state::{RobotOrder, State, StateKind};
 
 use crate::{
-    map::{Map, Tile},
-    station::Station,
+    map::{pathfinding::find_path_towards, Map, Tile},
+    simulation::events::SimEvent,
+    station::{
+        communication::RobotMessage, conflict::Observer, crdt::SharedMap, fleet::Heartbeat,
+        sync::MapKnowledge, RobotType, Station,
+    },
 };
-use rand::{seq::SliceRandom, Rng};
+use rand::{rngs::StdRng, seq::SliceRandom, Rng, SeedableRng};
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Robot {
+    pub id: u32,
     pub position: Position,
     pub state: State,
     pub energy: f32,
+    pub battery_capacity: f32,
     pub modules: Vec<HardwareModule>,
     pub inventory: Resources,
     pub visited_positions: Vec<Position>,
-    pub steps_since_last_energy: u32,
+    pub service_requested: bool,
+    pub knowledge: MapKnowledge,
+    /// Tick de simulation courant, utilisé pour dater les observations
+    #[serde(default)]
+    pub clock: u64,
+    /// Carte répliquée échangée directement avec les robots voisins
+    #[serde(default)]
+    pub shared_map: SharedMap,
+    /// Messages en attente d'une liaison radio avec la station
+    #[serde(default)]
+    pub outbox: VecDeque<RobotMessage>,
+    #[serde(default)]
+    pub link: LinkStatus,
+    /// Ordre manuel en cours, exécuté dans l'état `Exploring`
+    #[serde(default)]
+    pub order: Option<RobotOrder>,
+    /// Événements du tick, relevés et datés par la simulation
+    #[serde(skip)]
+    pub events: Vec<SimEvent>,
+    /// Tirages aléatoires du robot, reproductibles pour une graine donnée
+    #[serde(skip, default = "unseeded_rng")]
+    rng: StdRng,
+    /// Dernier chemin de retour planifié, pour estimer l'énergie du retour
+    #[serde(skip)]
+    return_plan: Option<ReturnPlan>,
 }
 
+/// Longueur estimée du retour à la station, planifiée depuis `from` au tick `tick`
+#[derive(Debug, Clone, Copy)]
+struct ReturnPlan {
+    tick: u64,
+    from: Position,
+    length: u32,
+}
+
+fn unseeded_rng() -> StdRng {
+    StdRng::seed_from_u64(0)
+}
+
+/// Rapport entre la portée nominale d'un scanner et le rayon cartographié à chaque tick
+const SCAN_RANGE_DIVISOR: u32 = 5;
+
+/// Ticks entre deux planifications du chemin de retour servant à estimer son coût
+const RETURN_PLAN_INTERVAL_TICKS: u64 = 10;
+
+/// Cases comptées pour chaque case inconnue d'un chemin de retour
+const UNKNOWN_TILE_DETOUR: u32 = 2;
+
+/// Distance maximale parcourue sur chaque axe par un déplacement d'exploration
+const MAX_EXPLORATION_STRIDE: u32 = 8;
+
 impl Robot {
     pub fn new(initial_pos: Position, modules: Vec<HardwareModule>) -> Self {
         Self {
+            id: 0,
             position: initial_pos,
             state: State::Idle,
             energy: 100.0,
+            battery_capacity: 100.0,
             modules,
             inventory: Resources {
                 energy: 0,
@@ -41,15 +103,44 @@ impl Robot {
                 scientific_data: 0,
             },
             visited_positions: vec![initial_pos], 
-            steps_since_last_energy: 0,
+            service_requested: false,
+            knowledge: MapKnowledge::new(),
+            clock: 0,
+            shared_map: SharedMap::new(),
+            outbox: VecDeque::new(),
+            link: LinkStatus::default(),
+            order: None,
+            events: Vec::new(),
+            rng: unseeded_rng(),
+            return_plan: None,
         }
     }
-    pub fn move_randomly(&mut self, map: &Map) {
-        let mut rng = rand::thread_rng();
 
+    /// Dérive le générateur du robot de la graine de la simulation et de son identifiant
+    pub fn seed_rng(&mut self, seed: u64) {
+        let id = u64::from(self.id).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        self.rng = StdRng::seed_from_u64(seed ^ id);
+    }
+
+    /// Déduit le rôle du robot à partir de ses modules installés
+    pub fn role(&self) -> RobotType {
+        for module in &self.modules {
+            match module {
+                HardwareModule::DeepDrill { .. } => return RobotType::Miner,
+                HardwareModule::EnergyHarvester { .. } => return RobotType::EnergyCollector,
+                HardwareModule::SpectralAnalyzer { .. } => return RobotType::Scientist,
+                HardwareModule::TerrainScanner { .. }
+                | HardwareModule::SignalRelay { .. }
+                | HardwareModule::CargoHold { .. } => {}
+            }
+        }
+        RobotType::Explorer
+    }
+
+    pub fn move_randomly(&mut self, map: &Map) {
         // Generate random direction
-        let dx = rng.gen_range(-1..=1);
-        let dy = rng.gen_range(-1..=1);
+        let dx = self.rng.gen_range(-1..=1);
+        let dy = self.rng.gen_range(-1..=1);
 
         let new_x = self.position.x.saturating_add_signed(dx);
         let new_y = self.position.y.saturating_add_signed(dy);
@@ -60,58 +151,361 @@ impl Robot {
         }
     }
     // Explore la carte en recherchant des ressources
-    pub fn explore_map(&mut self, map: &Map, station: &mut Station) {
-        if self.energy < 10.0 {
+    pub fn explore_map(&mut self, map: &Map, station: &Station) {
+        let energy_model = station.energy_model;
+
+        // Le prochain déplacement peut éloigner le robot d'un bond en terrain inconnu
+        let next_move = energy_model.move_cost(
+            2 * MAX_EXPLORATION_STRIDE * (1 + UNKNOWN_TILE_DETOUR),
+            MAX_TERRAIN_FACTOR,
+        );
+        if self.energy <= self.return_cost(map, station) + next_move {
+            self.order = None;
             self.state = State::Returning {
                 base_position: station.position,
+                path: Vec::new(),
             };
             return;
         }
 
-        self.steps_since_last_energy += 1;
-        if self.steps_since_last_energy >= 15 {
-            self.energy -= 10.0;
-            self.steps_since_last_energy = 0;
+        let current_x = self.position.x;
+        let current_y = self.position.y;
+
+        self.perform_action(RobotAction::Scan, &energy_model);
+        self.scan_surroundings(map);
+
+        // Ordre manuel : le robot rejoint sa cible sans s'arrêter en chemin
+        if let Some(order) = self.order {
+            let target = order.target();
+            if self.position != target {
+                self.walk_path(map, station);
+            }
+            if self.order.is_some() && self.position == target {
+                self.complete_order(map);
+            }
+            return;
+        }
+        let found = map
+            .get_tile(current_x, current_y)
+            .and_then(|tile| self.measure_resource(tile));
+        if let Some((resource_type, amount, confidence)) = found {
+            self.outbox.push_back(RobotMessage::ResourceDiscovered {
+                resource_type: resource_type.clone(),
+                position: self.position,
+                amount,
+                confidence,
+            });
+            self.events.push(SimEvent::ResourceDiscovered {
+                resource_type: resource_type.clone(),
+                position: self.position,
+                amount,
+            });
+
+            if self.harvest_rate(&resource_type).is_some() && self.free_cargo() > 0 {
+                self.state = State::Collecting {
+                    resource_type,
+                    target: self.position,
+                };
+                return;
+            }
+        }
+
+        let previous_position = self.position;
+        self.strategic_move(map);
+        self.spend_move_energy(map, previous_position, &energy_model);
+
+        if !self.visited_positions.contains(&self.position) {
+            self.visited_positions.push(self.position);
+        }
+    }
+
+    /// Rayon cartographié autour du robot, déduit de son meilleur scanner
+    pub fn scan_radius(&self) -> u32 {
+        self.modules
+            .iter()
+            .filter_map(|module| match module {
+                HardwareModule::TerrainScanner { range, .. } => Some(range / SCAN_RANGE_DIVISOR),
+                _ => None,
+            })
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// Efficacité du meilleur scanner installé, 0 sans scanner
+    pub fn sensor_efficiency(&self) -> f32 {
+        self.modules
+            .iter()
+            .filter_map(|module| match module {
+                HardwareModule::TerrainScanner { efficiency, .. } => Some(*efficiency),
+                _ => None,
+            })
+            .fold(0.0, f32::max)
+    }
+
+    /// Mesure un gisement. L'erreur relative atteint au plus `1 - efficacité`
+    /// du scanner, efficacité annoncée comme confiance de la mesure.
+    pub fn measure_resource(&mut self, tile: &Tile) -> Option<(ResourceType, u32, f32)> {
+        let (resource_type, amount) = tile.resource()?;
+
+        let confidence = self.sensor_efficiency().clamp(0.0, 1.0);
+        let error = 1.0 - confidence;
+        let factor = if error > 0.0 {
+            1.0 + self.rng.gen_range(-error..=error)
+        } else {
+            1.0
+        };
+        let measured = (amount as f32 * factor).round() as u32;
+        Some((resource_type, measured, confidence))
+    }
+
+    /// Enregistre dans la connaissance locale les cases à portée de scanner
+    pub fn scan_surroundings(&mut self, map: &Map) {
+        let radius = self.scan_radius() as i32;
+        for dy in -radius..=radius {
+            for dx in -radius..=radius {
+                let x = self.position.x as i32 + dx;
+                let y = self.position.y as i32 + dy;
+                if x < 0 || y < 0 {
+                    continue;
+                }
+                self.observe(
+                    map,
+                    Position {
+                        x: x as u32,
+                        y: y as u32,
+                    },
+                );
+            }
+        }
+    }
+
+    fn observe(&mut self, map: &Map, position: Position) {
+        if let Some(tile) = map.get_tile(position.x, position.y) {
+            let observer = Observer {
+                robot_id: self.id,
+                tick: self.clock,
+                sensor_efficiency: self.sensor_efficiency(),
+            };
+            self.knowledge.observe_tile(position, tile, observer);
+            self.shared_map.observe(position, tile, observer);
+        }
+    }
+
+    /// Remplace le signal de vie en attente d'envoi par l'état courant.
+    /// Un robot sans énergie ne peut plus émettre.
+    pub fn queue_heartbeat(&mut self, tick: u64) {
+        if self.energy <= 0.0 {
+            return;
+        }
+        self.push_heartbeat(tick);
+    }
+
+    fn push_heartbeat(&mut self, tick: u64) {
+        self.outbox
+            .retain(|message| !matches!(message, RobotMessage::Heartbeat(_)));
+        self.outbox.push_back(RobotMessage::Heartbeat(Heartbeat {
+            tick,
+            position: self.position,
+            energy: self.energy,
+            state: self.state.clone(),
+        }));
+    }
+
+    /// Signale une extraction à la station. Les extractions d'un même gisement
+    /// encore en attente d'envoi sont regroupées en un seul rapport.
+    fn report_consumption(&mut self, resource_type: ResourceType, position: Position, amount: u32) {
+        if let Some(RobotMessage::ResourceConsumed {
+            resource_type: queued_type,
+            position: queued_position,
+            amount: queued_amount,
+            ..
+        }) = self.outbox.back_mut()
+        {
+            if *queued_type == resource_type && *queued_position == position {
+                *queued_amount += amount;
+                return;
+            }
         }
 
-        let distance_to_base = self.position.distance_to(&station.position);
-        let energy_needed_to_return = distance_to_base as f32 * 0.5;
+        self.outbox.push_back(RobotMessage::ResourceConsumed {
+            resource_type,
+            position,
+            amount,
+            robot_id: self.id,
+        });
+    }
+
+    // Récolte la ressource sous le robot jusqu'à remplir la soute ou épuiser le gisement
+    pub fn collect_resource(&mut self, map: &mut Map, station: &Station) {
+        let State::Collecting {
+            resource_type,
+            target,
+        } = &self.state
+        else {
+            return;
+        };
+        let (resource_type, target) = (resource_type.clone(), *target);
+        let energy_model = station.energy_model;
 
-        if self.energy <= energy_needed_to_return + 1.0 {
+        if self.energy <= self.return_cost(map, station) {
             self.state = State::Returning {
                 base_position: station.position,
+                path: Vec::new(),
             };
             return;
         }
 
-        let current_x = self.position.x;
-        let current_y = self.position.y;
+        let Some(rate) = self.harvest_rate(&resource_type) else {
+            self.state = State::Idle;
+            return;
+        };
+        let amount = rate.min(self.free_cargo());
 
-        if let Some(tile) = map.get_tile(current_x, current_y) {
-            match tile {
-                Tile::Energy(_) => {
-                    station.report_resource_found(ResourceType::Energy, self.position);
-                }
-                Tile::Mineral(_) => {
-                    station.report_resource_found(ResourceType::Minerals, self.position);
-                }
-                Tile::ScientificPoint(_) => {
-                    station.report_resource_found(ResourceType::ScientificData, self.position);
-                }
-                _ => {}
+        let collected = match resource_type {
+            ResourceType::Energy => {
+                self.perform_action(RobotAction::Drill, &energy_model);
+                map.consume_energy(target.x, target.y, amount)
+            }
+            ResourceType::Minerals => {
+                self.perform_action(RobotAction::Drill, &energy_model);
+                map.consume_mineral(target.x, target.y, amount)
             }
+            ResourceType::ScientificData => {
+                self.perform_action(RobotAction::Analyse, &energy_model);
+                map.analyse_scientific_point(target.x, target.y, amount)
+            }
+        };
+
+        let collected = collected.unwrap_or(0);
+        if collected > 0 {
+            self.shared_map.record_consumption(target, self.id, collected);
+            self.report_consumption(resource_type.clone(), target, collected);
+            self.events.push(SimEvent::ResourceConsumed {
+                resource_type: resource_type.clone(),
+                position: target,
+                amount: collected,
+            });
         }
 
-        self.strategic_move(map);
+        match resource_type {
+            ResourceType::Energy => self.inventory.energy += collected,
+            ResourceType::Minerals => self.inventory.minerals += collected,
+            ResourceType::ScientificData => self.inventory.scientific_data += collected,
+        }
 
-        if !self.visited_positions.contains(&self.position) {
-            self.visited_positions.push(self.position);
+        self.observe(map, target);
+
+        let depleted = !matches!(
+            (map.get_tile(target.x, target.y), &resource_type),
+            (Some(Tile::Energy(_)), ResourceType::Energy)
+                | (Some(Tile::Mineral(_)), ResourceType::Minerals)
+                | (Some(Tile::ScientificPoint(_)), ResourceType::ScientificData)
+        );
+        if depleted && collected > 0 {
+            self.events.push(SimEvent::ResourceDepleted {
+                resource_type: resource_type.clone(),
+                position: target,
+            });
+        }
+
+        if self.should_return_with_cargo(&station.cargo_policy, depleted) {
+            self.state = State::Returning {
+                base_position: station.position,
+                path: Vec::new(),
+            };
+        } else if depleted {
+            self.state = State::Idle;
         }
     }
 
-    fn strategic_move(&mut self, map: &Map) {
-        let mut rng = rand::thread_rng();
+    /// Énergie nécessaire pour rentrer à la station depuis la position courante,
+    /// d'après un chemin planifié sur la carte connue. Le chemin est replanifié
+    /// quand le robot se déplace, et tous les `RETURN_PLAN_INTERVAL_TICKS` ticks
+    /// pour tenir compte des nouvelles observations.
+    pub fn return_cost(&mut self, map: &Map, station: &Station) -> f32 {
+        let (clock, position) = (self.clock, self.position);
+        let stale = self.return_plan.is_none_or(|plan| {
+            plan.from != position
+                || clock < plan.tick
+                || clock - plan.tick >= RETURN_PLAN_INTERVAL_TICKS
+        });
+        if stale {
+            self.return_plan = Some(ReturnPlan {
+                tick: clock,
+                from: position,
+                length: self.plan_return_length(map, station),
+            });
+        }
 
+        let length = self.return_plan.map_or(0, |plan| plan.length);
+        let energy_model = station.energy_model;
+        energy_model.return_cost(length, energy_model.idle_drain(&self.modules))
+    }
+
+    /// Dernière estimation du coût du retour, sans replanifier le chemin
+    pub fn estimated_return_cost(&self, energy_model: &EnergyModel) -> Option<f32> {
+        self.return_plan.map(|plan| {
+            energy_model.return_cost(plan.length, energy_model.idle_drain(&self.modules))
+        })
+    }
+
+    /// Longueur pessimiste du retour : les cases inconnues du chemin, et la
+    /// distance restante au-delà de la partie praticable connue, peuvent cacher
+    /// des obstacles à contourner
+    fn plan_return_length(&self, map: &Map, station: &Station) -> u32 {
+        let known_map = self.knowledge.generate_partial_map(map.width, map.height);
+        let path = find_path_towards(
+            &known_map,
+            self.position,
+            station.position,
+            station.unknown_tile_policy,
+        );
+        let unknown = path
+            .iter()
+            .filter(|step| matches!(known_map.get_tile(step.x, step.y), Some(Tile::Unknown)))
+            .count() as u32;
+        let end = path.last().copied().unwrap_or(self.position);
+        let uncertain = unknown + end.distance_to(&station.position);
+        path.len() as u32 - unknown + uncertain * UNKNOWN_TILE_DETOUR
+    }
+
+    /// Consommation passive des modules installés, appliquée à chaque tick hors station
+    pub fn apply_idle_drain(&mut self, energy_model: &EnergyModel) {
+        self.consume_energy(energy_model.idle_drain(&self.modules));
+    }
+
+    pub fn perform_action(&mut self, action: RobotAction, energy_model: &EnergyModel) {
+        self.consume_energy(energy_model.action_cost(action));
+    }
+
+    fn spend_move_energy(&mut self, map: &Map, from: Position, energy_model: &EnergyModel) {
+        let distance = from.distance_to(&self.position);
+        if distance > 0 {
+            let terrain_factor = map.terrain_factor(self.position.x, self.position.y);
+            self.consume_energy(energy_model.move_cost(distance, terrain_factor));
+        }
+    }
+
+    /// Cède à un robot en panne la moitié de l'énergie disponible au-delà de
+    /// `reserve`. Retourne la quantité cédée.
+    pub fn share_energy_with(&mut self, other: &mut Robot, reserve: f32) -> f32 {
+        let spare = (self.energy - reserve).max(0.0) / 2.0;
+        let given = spare.min((other.battery_capacity - other.energy).max(0.0));
+        self.energy -= given;
+        other.energy += given;
+        given
+    }
+
+    fn consume_energy(&mut self, amount: f32) {
+        let was_running = self.energy > 0.0;
+        self.energy = (self.energy - amount).max(0.0);
+        // Dernier signal avant l'extinction : la station saura que le robot est à plat
+        if was_running && self.energy <= 0.0 {
+            self.push_heartbeat(self.clock);
+        }
+    }
+
+    fn strategic_move(&mut self, map: &Map) {
         let directions = [
             (0, 1),   
             (1, 0),   
@@ -123,10 +517,10 @@ impl Robot {
             (-1, -1), 
         ];
 
-        let distance = rng.gen_range(4..=8);
+        let distance = self.rng.gen_range(4..=MAX_EXPLORATION_STRIDE);
 
         let mut shuffled_directions = directions.to_vec();
-        shuffled_directions.shuffle(&mut rng);
+        shuffled_directions.shuffle(&mut self.rng);
 
         // Explorer dans une direction jusqu'à ce qu'on trouve une position valide
         for (dx, dy) in shuffled_directions {
@@ -147,8 +541,13 @@ impl Robot {
                     _ => final_y,
                 };
 
-                // Vérifier si la position est valide
-                if new_x < map.width && new_y < map.height && !map.is_obstacle(new_x, new_y) {
+                // Vérifier si la position est valide et atteignable case par case
+                let from = Position {
+                    x: final_x,
+                    y: final_y,
+                };
+                let to = Position { x: new_x, y: new_y };
+                if new_x < map.width && new_y < map.height && is_walkable_stride(map, from, to) {
                     final_x = new_x;
                     final_y = new_y;
                 } else {
@@ -164,29 +563,167 @@ impl Robot {
         }
     }
 
-    // Retour à la station
-    pub fn return_to_station(&mut self, station: &Station) {
-        let dx = station.position.x as i32 - self.position.x as i32;
-        let dy = station.position.y as i32 - self.position.y as i32;
+    // Retour à la station, en suivant un chemin vers la baie attribuée
+    pub fn return_to_station(&mut self, map: &Map, station: &Station) {
+        // Batterie vide : le robot est immobilisé
+        if self.energy <= 0.0 {
+            return;
+        }
+        self.walk_path(map, station);
+    }
 
-        let dir_x = dx.signum();
-        let dir_y = dy.signum();
+    /// Confie un ordre manuel au robot, qui abandonne sa tâche en cours
+    pub fn give_order(&mut self, order: RobotOrder) {
+        self.order = Some(order);
+        self.state = State::Exploring {
+            target: order.target(),
+            path: Vec::new(),
+        };
+    }
 
-        self.position.x = self.position.x.saturating_add_signed(dir_x);
-        self.position.y = self.position.y.saturating_add_signed(dir_y);
+    /// Termine l'ordre manuel une fois la cible atteinte
+    fn complete_order(&mut self, map: &Map) {
+        self.state = State::Idle;
+        let Some(RobotOrder::Harvest(target)) = self.order.take() else {
+            return;
+        };
+        let resource_type = map
+            .get_tile(target.x, target.y)
+            .and_then(Tile::resource)
+            .map(|(resource_type, _)| resource_type)
+            .filter(|resource_type| self.harvest_rate(resource_type).is_some());
+        if let Some(resource_type) = resource_type {
+            self.state = State::Collecting {
+                resource_type,
+                target,
+            };
+        }
+    }
+
+    /// Avance d'une case vers la destination de l'état courant (retour ou
+    /// ordre manuel) en suivant le chemin planifié
+    fn walk_path(&mut self, map: &Map, station: &Station) {
+        let previous_position = self.position;
+        let (State::Returning {
+            base_position: goal,
+            path,
+        }
+        | State::Exploring { target: goal, path }) = &mut self.state
+        else {
+            return;
+        };
+
+        // Le chemin est planifié sur la carte connue du robot, pas sur le terrain réel
+        if path.last() != Some(goal) {
+            let known_map = self.knowledge.generate_partial_map(map.width, map.height);
+            // Sans chemin connu jusqu'à l'objectif, le robot rejoint la case
+            // accessible la plus proche pour découvrir la suite du terrain
+            *path = find_path_towards(
+                &known_map,
+                self.position,
+                *goal,
+                station.unknown_tile_policy,
+            );
+        }
+
+        // Obstacle découvert sur le chemin : il sera replanifié au prochain tick
+        if let Some(&next) = path.first() {
+            if map.is_obstacle(next.x, next.y) {
+                path.clear();
+                self.observe(map, next);
+                return;
+            }
+        }
 
-        self.energy -= 0.5;
+        // Bloqué au plus près de l'objectif : le robot sonde les cases voisines
+        // et attend qu'un chemin conforme à la politique apparaisse. Un ordre
+        // manuel sans issue est abandonné.
+        if path.is_empty() {
+            let explored = self.knowledge.explored_count();
+            self.probe_neighbours(map);
+            if self.order.is_some() && self.knowledge.explored_count() == explored {
+                self.order = None;
+                self.state = State::Idle;
+            }
+            return;
+        }
+        self.position = path.remove(0);
+
+        self.spend_move_energy(map, previous_position, &station.energy_model);
+        self.observe(map, self.position);
+    }
+
+    fn probe_neighbours(&mut self, map: &Map) {
+        for (dx, dy) in [(0, 1), (1, 0), (0, -1), (-1, 0)] {
+            let (Some(x), Some(y)) = (
+                self.position.x.checked_add_signed(dx),
+                self.position.y.checked_add_signed(dy),
+            ) else {
+                continue;
+            };
+            self.observe(map, Position { x, y });
+        }
+    }
+
+    /// Redirige un robot en retour vers une nouvelle destination (par exemple une baie libre)
+    pub fn set_return_target(&mut self, target: Position) {
+        if let State::Returning {
+            base_position,
+            path,
+        } = &mut self.state
+        {
+            if *base_position != target {
+                *base_position = target;
+                path.clear();
+            }
+        }
     }
 
     pub fn is_at_station(&self, station: &Station) -> bool {
-        self.position.x == station.position.x && self.position.y == station.position.y
+        station.docking.is_docked(self.id, self.position)
+    }
+}
+
+/// Vrai si le robot peut aller en ligne droite de `from` à `to` sans traverser
+/// d'obstacle. Les chemins planifiés ne passent jamais en diagonale : un pas en
+/// diagonale n'est permis que si l'une des deux cases qui l'encadrent est libre,
+/// pour que le robot puisse toujours refaire le trajet en sens inverse.
+fn is_walkable_stride(map: &Map, from: Position, to: Position) -> bool {
+    let mut current = from;
+    while current != to {
+        let next = Position {
+            x: step_towards(current.x, to.x),
+            y: step_towards(current.y, to.y),
+        };
+        let diagonal = next.x != current.x && next.y != current.y;
+        if map.is_obstacle(next.x, next.y)
+            || (diagonal
+                && map.is_obstacle(next.x, current.y)
+                && map.is_obstacle(current.x, next.y))
+        {
+            return false;
+        }
+        current = next;
+    }
+    true
+}
+
+/// Coordonnée voisine de `from` en direction de `to`
+fn step_towards(from: u32, to: u32) -> u32 {
+    match from.cmp(&to) {
+        std::cmp::Ordering::Less => from + 1,
+        std::cmp::Ordering::Greater => from - 1,
+        std::cmp::Ordering::Equal => from,
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::map::{Map, Tile};
+    use crate::map::{
+        pathfinding::{find_path, UnknownTilePolicy},
+        Map, Tile,
+    };
 
     #[test]
     fn test_robot_initialization() {
@@ -232,4 +769,235 @@ mod tests {
             );
         }
     }
+
+    #[test]
+    fn test_reseeded_robot_replays_its_random_moves() {
+        let mut map = Map::new(30, 30, 7);
+        for y in 0..30 {
+            for x in 0..30 {
+                *map.get_tile_mut(x, y).unwrap() = Tile::Empty;
+            }
+        }
+        let mut robot = Robot::new(Position { x: 15, y: 15 }, vec![]);
+        robot.id = 3;
+        let json = serde_json::to_string(&robot).unwrap();
+        robot.seed_rng(7);
+        let mut replica: Robot = serde_json::from_str(&json).unwrap();
+        replica.seed_rng(7);
+
+        for _ in 0..10 {
+            robot.move_randomly(&map);
+            replica.move_randomly(&map);
+            assert_eq!(robot.position, replica.position);
+        }
+    }
+
+    #[test]
+    fn test_return_replans_around_discovered_obstacles() {
+        let mut map = Map::new(20, 20, 42);
+        for y in 0..20 {
+            for x in 0..20 {
+                *map.get_tile_mut(x, y).unwrap() = Tile::Empty;
+            }
+        }
+        let station = Station::new(&mut map);
+        for y in 0..19 {
+            *map.get_tile_mut(5, y).unwrap() = Tile::Obstacle;
+        }
+
+        let target = Position { x: 8, y: 2 };
+        let mut robot = Robot::new(Position { x: 2, y: 2 }, vec![]);
+        robot.energy = 1000.0;
+        robot.state = State::Returning {
+            base_position: target,
+            path: Vec::new(),
+        };
+
+        for _ in 0..100 {
+            if robot.position == target {
+                break;
+            }
+            robot.return_to_station(&map, &station);
+            assert!(!map.is_obstacle(robot.position.x, robot.position.y));
+        }
+
+        assert_eq!(robot.position, target);
+        assert_eq!(robot.knowledge.get_tile(5, 2).unwrap().tile, Tile::Obstacle);
+    }
+
+    #[test]
+    fn test_return_cost_follows_the_known_path_home() {
+        let mut map = Map::new(20, 20, 42);
+        for y in 0..20 {
+            for x in 0..20 {
+                *map.get_tile_mut(x, y).unwrap() = Tile::Empty;
+            }
+        }
+        let station = Station::new(&mut map);
+        for y in 0..19 {
+            *map.get_tile_mut(5, y).unwrap() = Tile::Obstacle;
+        }
+
+        let modules = vec![HardwareModule::TerrainScanner {
+            efficiency: 0.8,
+            range: 10,
+        }];
+        let mut robot = Robot::new(Position { x: 2, y: 10 }, modules.clone());
+        for y in 0..20 {
+            for x in 0..20 {
+                robot.observe(&map, Position { x, y });
+            }
+        }
+
+        let path = find_path(&map, robot.position, station.position)
+            .unwrap()
+            .len() as u32;
+        let model = station.energy_model;
+        let drain = model.idle_drain(&modules);
+        assert_eq!(robot.estimated_return_cost(&model), None);
+        let cost = robot.return_cost(&map, &station);
+        assert_eq!(cost, model.return_cost(path, drain));
+        assert_eq!(robot.estimated_return_cost(&model), Some(cost));
+        assert!(cost > model.return_cost(robot.position.distance_to(&station.position), drain));
+        assert!(model.return_cost(path, drain) > model.return_cost(path, 0.0));
+    }
+
+    #[test]
+    fn test_exploration_does_not_cross_a_wall() {
+        let mut map = Map::new(20, 20, 42);
+        for y in 0..20 {
+            for x in 0..20 {
+                *map.get_tile_mut(x, y).unwrap() = if x + y == 12 {
+                    Tile::Obstacle
+                } else {
+                    Tile::Empty
+                };
+            }
+        }
+        let mut station = Station::new(&mut map);
+        station.position = Position { x: 0, y: 0 };
+
+        let mut robot = Robot::new(Position { x: 2, y: 2 }, vec![]);
+        robot.energy = 1000.0;
+        for _ in 0..200 {
+            robot.explore_map(&map, &station);
+            assert!(robot.position.x + robot.position.y < 12);
+        }
+    }
+
+    #[test]
+    fn test_pessimistic_return_only_crosses_observed_tiles() {
+        let mut map = Map::new(20, 20, 42);
+        for y in 0..20 {
+            for x in 0..20 {
+                *map.get_tile_mut(x, y).unwrap() = Tile::Empty;
+            }
+        }
+        let mut station = Station::new(&mut map);
+        station.unknown_tile_policy = UnknownTilePolicy::Pessimistic;
+
+        let target = Position { x: 8, y: 2 };
+        let mut robot = Robot::new(Position { x: 2, y: 2 }, vec![]);
+        robot.energy = 1000.0;
+        robot.state = State::Returning {
+            base_position: target,
+            path: Vec::new(),
+        };
+
+        for _ in 0..100 {
+            if robot.position == target {
+                break;
+            }
+            let before = robot.knowledge.generate_partial_map(map.width, map.height);
+            let previous = robot.position;
+            robot.return_to_station(&map, &station);
+            if robot.position != previous {
+                assert!(!before.is_unknown(robot.position.x, robot.position.y));
+            }
+        }
+
+        assert_eq!(robot.position, target);
+    }
+
+    #[test]
+    fn test_return_waits_when_the_base_is_walled_off() {
+        let mut map = Map::new(20, 20, 42);
+        for y in 0..20 {
+            for x in 0..20 {
+                *map.get_tile_mut(x, y).unwrap() = Tile::Empty;
+            }
+        }
+        let station = Station::new(&mut map);
+        for y in 0..20 {
+            *map.get_tile_mut(5, y).unwrap() = Tile::Obstacle;
+        }
+
+        let mut robot = Robot::new(Position { x: 2, y: 2 }, vec![]);
+        robot.energy = 1000.0;
+        robot.state = State::Returning {
+            base_position: Position { x: 8, y: 2 },
+            path: Vec::new(),
+        };
+
+        for _ in 0..100 {
+            robot.return_to_station(&map, &station);
+            assert!(robot.position.x < 5);
+        }
+    }
+
+    #[test]
+    fn test_order_without_a_way_through_is_dropped() {
+        let mut map = Map::new(20, 20, 42);
+        for y in 0..20 {
+            for x in 0..20 {
+                *map.get_tile_mut(x, y).unwrap() = Tile::Empty;
+            }
+        }
+        let station = Station::new(&mut map);
+        for y in 0..20 {
+            *map.get_tile_mut(5, y).unwrap() = Tile::Obstacle;
+        }
+
+        let mut robot = Robot::new(Position { x: 2, y: 2 }, vec![]);
+        robot.energy = 1000.0;
+        robot.give_order(RobotOrder::GoTo(Position { x: 8, y: 2 }));
+
+        for _ in 0..200 {
+            if robot.order.is_none() {
+                break;
+            }
+            robot.explore_map(&map, &station);
+            assert!(robot.position.x < 5);
+        }
+        assert_eq!(robot.order, None);
+        assert_eq!(robot.state, State::Idle);
+    }
+
+    #[test]
+    fn test_measurement_error_is_bounded_by_scanner_efficiency() {
+        let deposit = Tile::Mineral(crate::map::Mineral {
+            amount: 1000,
+            is_base: false,
+        });
+        let scanner = |efficiency| {
+            Robot::new(
+                Position { x: 0, y: 0 },
+                vec![HardwareModule::TerrainScanner {
+                    efficiency,
+                    range: 5,
+                }],
+            )
+        };
+
+        let exact = scanner(1.0).measure_resource(&deposit);
+        assert_eq!(exact, Some((ResourceType::Minerals, 1000, 1.0)));
+
+        let mut coarse = scanner(0.8);
+        for _ in 0..50 {
+            let (_, amount, confidence) = coarse.measure_resource(&deposit).unwrap();
+            assert!((800..=1200).contains(&amount));
+            assert_eq!(confidence, 0.8);
+        }
+        assert_eq!(coarse.measure_resource(&Tile::Empty), None);
+    }
 }
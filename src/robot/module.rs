@@ -15,4 +15,82 @@ pub enum HardwareModule {
     EnergyHarvester {
         collection_rate: f32,
     },
-}
\ No newline at end of file
+    SignalRelay {
+        range: u32,
+    },
+    CargoHold {
+        capacity: u32,
+    },
+}
+
+impl HardwareModule {
+    pub fn name(&self) -> &'static str {
+        match self {
+            HardwareModule::TerrainScanner { .. } => "Terrain Scanner",
+            HardwareModule::DeepDrill { .. } => "Deep Drill",
+            HardwareModule::SpectralAnalyzer { .. } => "Spectral Analyzer",
+            HardwareModule::EnergyHarvester { .. } => "Energy Harvester",
+            HardwareModule::SignalRelay { .. } => "Signal Relay",
+            HardwareModule::CargoHold { .. } => "Cargo Hold",
+        }
+    }
+
+    /// Vrai si les deux modules sont du même type, quelles que soient leurs statistiques
+    pub fn is_same_kind(&self, other: &HardwareModule) -> bool {
+        std::mem::discriminant(self) == std::mem::discriminant(other)
+    }
+
+    /// Retourne une version améliorée du module
+    pub fn upgraded(&self) -> HardwareModule {
+        match self {
+            HardwareModule::TerrainScanner { efficiency, range } => {
+                HardwareModule::TerrainScanner {
+                    efficiency: (efficiency + 0.05).min(1.0),
+                    range: range + 5,
+                }
+            }
+            HardwareModule::DeepDrill { mining_speed } => HardwareModule::DeepDrill {
+                mining_speed: mining_speed + 0.5,
+            },
+            HardwareModule::SpectralAnalyzer { analysis_accuracy } => {
+                HardwareModule::SpectralAnalyzer {
+                    analysis_accuracy: (analysis_accuracy + 0.02).min(1.0),
+                }
+            }
+            HardwareModule::EnergyHarvester { collection_rate } => {
+                HardwareModule::EnergyHarvester {
+                    collection_rate: collection_rate + 0.5,
+                }
+            }
+            HardwareModule::SignalRelay { range } => HardwareModule::SignalRelay {
+                range: range + 10,
+            },
+            HardwareModule::CargoHold { capacity } => HardwareModule::CargoHold {
+                capacity: capacity + 50,
+            },
+        }
+    }
+
+    pub fn describe(&self) -> String {
+        match self {
+            HardwareModule::TerrainScanner { efficiency, range } => {
+                format!("{} (eff {:.2}, range {})", self.name(), efficiency, range)
+            }
+            HardwareModule::DeepDrill { mining_speed } => {
+                format!("{} (speed {:.1})", self.name(), mining_speed)
+            }
+            HardwareModule::SpectralAnalyzer { analysis_accuracy } => {
+                format!("{} (acc {:.2})", self.name(), analysis_accuracy)
+            }
+            HardwareModule::EnergyHarvester { collection_rate } => {
+                format!("{} (rate {:.1})", self.name(), collection_rate)
+            }
+            HardwareModule::SignalRelay { range } => {
+                format!("{} (range {})", self.name(), range)
+            }
+            HardwareModule::CargoHold { capacity } => {
+                format!("{} (capacity {})", self.name(), capacity)
+            }
+        }
+    }
+}
@@ -8,13 +8,19 @@ pub enum ResourceType {
     ScientificData,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize, PartialEq)]
 pub struct Resources {
     pub energy: u32,
     pub minerals: u32,
     pub scientific_data: u32,
 }
 
+impl Resources {
+    pub fn is_empty(&self) -> bool {
+        self.energy == 0 && self.minerals == 0 && self.scientific_data == 0
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[derive(PartialEq)]
 pub struct ScientificSample {
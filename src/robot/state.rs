@@ -1,4 +1,5 @@
 use serde::{Serialize, Deserialize};
+use std::fmt;
 
 use crate::robot::position::Position;
 use crate::robot::resources::ScientificSample;
@@ -7,6 +8,7 @@ use crate::robot::resources::ResourceType;
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum State {
     Idle,
+    /// En route vers la cible de l'ordre manuel en cours
     Exploring {
         target: Position,
         path: Vec<Position>,
@@ -20,5 +22,76 @@ pub enum State {
     },
     Returning {
         base_position: Position,
+        path: Vec<Position>,
     },
-}
\ No newline at end of file
+}
+
+/// Ordre donné à la main à un robot depuis l'interface
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RobotOrder {
+    /// Rejoindre la case puis reprendre l'exploration
+    GoTo(Position),
+    /// Rejoindre la case puis récolter son gisement
+    Harvest(Position),
+}
+
+impl RobotOrder {
+    pub fn target(&self) -> Position {
+        match self {
+            RobotOrder::GoTo(target) | RobotOrder::Harvest(target) => *target,
+        }
+    }
+}
+
+/// Nature d'un état, sans les données qui l'accompagnent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum StateKind {
+    Idle,
+    Exploring,
+    Collecting,
+    Analyzing,
+    Returning,
+}
+
+impl StateKind {
+    pub const ALL: [StateKind; 5] = [
+        StateKind::Idle,
+        StateKind::Exploring,
+        StateKind::Collecting,
+        StateKind::Analyzing,
+        StateKind::Returning,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            StateKind::Idle => "idle",
+            StateKind::Exploring => "exploring",
+            StateKind::Collecting => "collecting",
+            StateKind::Analyzing => "analyzing",
+            StateKind::Returning => "returning",
+        }
+    }
+}
+
+impl fmt::Display for StateKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+impl State {
+    pub fn kind(&self) -> StateKind {
+        match self {
+            State::Idle => StateKind::Idle,
+            State::Exploring { .. } => StateKind::Exploring,
+            State::Collecting { .. } => StateKind::Collecting,
+            State::Analyzing { .. } => StateKind::Analyzing,
+            State::Returning { .. } => StateKind::Returning,
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.kind().name()
+    }
+}
@@ -0,0 +1,187 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::time::{Duration, Instant};
+
+/// Durée d'un tick à vitesse normale
+pub const BASE_TICK_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Fenêtre sur laquelle la cadence effective est mesurée
+const TPS_WINDOW: Duration = Duration::from_secs(1);
+
+/// Vitesses proposées, de la plus lente à la plus rapide
+const SPEEDS: [Speed; 7] = [
+    Speed::Multiplier(0.25),
+    Speed::Multiplier(0.5),
+    Speed::Multiplier(1.0),
+    Speed::Multiplier(2.0),
+    Speed::Multiplier(4.0),
+    Speed::Multiplier(8.0),
+    Speed::Max,
+];
+const NORMAL_SPEED: usize = 2;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Speed {
+    /// Multiple de la vitesse normale
+    Multiplier(f32),
+    /// Aussi vite que la machine le permet
+    Max,
+}
+
+impl fmt::Display for Speed {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Speed::Multiplier(multiplier) => write!(f, "{multiplier}x"),
+            Speed::Max => write!(f, "max"),
+        }
+    }
+}
+
+/// Horloge de la simulation interactive : pause, pas à pas, vitesse et
+/// mesure du nombre de ticks réellement joués par seconde
+#[derive(Debug)]
+pub struct SimClock {
+    paused: bool,
+    speed: usize,
+    pending_steps: u32,
+    recent_ticks: VecDeque<Instant>,
+}
+
+impl Default for SimClock {
+    fn default() -> Self {
+        Self {
+            paused: false,
+            speed: NORMAL_SPEED,
+            pending_steps: 0,
+            recent_ticks: VecDeque::new(),
+        }
+    }
+}
+
+impl SimClock {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
+
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        self.pending_steps = 0;
+    }
+
+    /// Met en pause et demande un seul tick
+    pub fn request_step(&mut self) {
+        self.paused = true;
+        self.pending_steps += 1;
+    }
+
+    pub fn speed(&self) -> Speed {
+        SPEEDS[self.speed]
+    }
+
+    pub fn faster(&mut self) {
+        self.speed = (self.speed + 1).min(SPEEDS.len() - 1);
+    }
+
+    pub fn slower(&mut self) {
+        self.speed = self.speed.saturating_sub(1);
+    }
+
+    /// Attente entre deux ticks, aucune en vitesse maximale
+    pub fn tick_interval(&self) -> Duration {
+        match self.speed() {
+            Speed::Multiplier(multiplier) => BASE_TICK_INTERVAL.div_f64(f64::from(multiplier)),
+            Speed::Max => Duration::ZERO,
+        }
+    }
+
+    /// Indique si un tick doit être joué maintenant, en consommant un pas
+    /// demandé pendant la pause
+    pub fn take_tick(&mut self) -> bool {
+        if !self.paused {
+            return true;
+        }
+        if self.pending_steps == 0 {
+            return false;
+        }
+        self.pending_steps -= 1;
+        true
+    }
+
+    pub fn record_tick(&mut self, now: Instant) {
+        self.recent_ticks.push_back(now);
+        self.forget_before(now);
+    }
+
+    /// Ticks joués pendant la dernière seconde
+    pub fn ticks_per_second(&mut self, now: Instant) -> usize {
+        self.forget_before(now);
+        self.recent_ticks.len()
+    }
+
+    fn forget_before(&mut self, now: Instant) {
+        while let Some(&oldest) = self.recent_ticks.front() {
+            if now.duration_since(oldest) < TPS_WINDOW {
+                break;
+            }
+            self.recent_ticks.pop_front();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_paused_clock_only_plays_requested_steps() {
+        let mut clock = SimClock::new();
+        assert!(clock.take_tick());
+
+        clock.toggle_pause();
+        assert!(!clock.take_tick());
+        clock.request_step();
+        clock.request_step();
+        assert!(clock.take_tick());
+        assert!(clock.take_tick());
+        assert!(!clock.take_tick());
+
+        clock.toggle_pause();
+        assert!(!clock.is_paused());
+        assert!(clock.take_tick());
+    }
+
+    #[test]
+    fn test_speed_levels_are_bounded() {
+        let mut clock = SimClock::new();
+        assert_eq!(clock.tick_interval(), BASE_TICK_INTERVAL);
+
+        for _ in 0..10 {
+            clock.slower();
+        }
+        assert_eq!(clock.speed(), Speed::Multiplier(0.25));
+        assert_eq!(clock.tick_interval(), BASE_TICK_INTERVAL * 4);
+
+        for _ in 0..10 {
+            clock.faster();
+        }
+        assert_eq!(clock.speed(), Speed::Max);
+        assert_eq!(clock.tick_interval(), Duration::ZERO);
+        assert_eq!(clock.speed().to_string(), "max");
+    }
+
+    #[test]
+    fn test_ticks_per_second_uses_a_sliding_window() {
+        let mut clock = SimClock::new();
+        let start = Instant::now();
+        for tick in 0..20 {
+            clock.record_tick(start + Duration::from_millis(100 * tick));
+        }
+        let end = start + Duration::from_millis(1900);
+        assert_eq!(clock.ticks_per_second(end), 10);
+        assert_eq!(clock.ticks_per_second(end + TPS_WINDOW), 0);
+    }
+}
@@ -0,0 +1,198 @@
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::map::{pathfinding::find_path, Tile};
+use crate::robot::{HardwareModule, Position, Robot, RobotOrder, State};
+use crate::simulation::Simulation;
+use crate::station::production::BuildError;
+use crate::station::research::{ResearchError, ResearchTopic};
+use crate::station::workshop::WorkshopError;
+use crate::station::{RobotType, Station};
+
+/// Action de l'utilisateur sur la simulation. Toutes passent par
+/// `Simulation::apply`, qui les enregistre pour pouvoir les rejouer.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum UserCommand {
+    CreateRobot(RobotType),
+    /// Paie un robot et le place dans la file de production
+    QueueRobot(RobotType),
+    Regenerate { seed: u64 },
+    StartResearch(ResearchTopic),
+    /// Rappelle un robot pour entretien, ou le libère s'il l'était déjà
+    ToggleService { robot_id: u32 },
+    UpgradeModule { robot_id: u32, module: usize },
+    RemoveModule { robot_id: u32, module: usize },
+    InstallModule { robot_id: u32, storage: usize },
+    FabricateModule { robot_id: u32, module: HardwareModule },
+    Refit { robot_id: u32, robot_type: RobotType },
+    /// Envoie un robot sur une case, d'où il reprend l'exploration
+    GoTo { robot_id: u32, target: Position },
+    /// Envoie un robot récolter le gisement d'une case
+    Harvest { robot_id: u32, target: Position },
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum CommandError {
+    UnknownRobot(u32),
+    Build(BuildError),
+    Workshop(WorkshopError),
+    Research(ResearchError),
+    /// Case hors de la carte, infranchissable ou sans chemin depuis le robot
+    Unreachable(Position),
+    CannotHarvest { robot_id: u32, position: Position },
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CommandError::UnknownRobot(robot_id) => write!(f, "no robot #{robot_id}"),
+            CommandError::Build(error) => write!(f, "{error}"),
+            CommandError::Workshop(error) => write!(f, "{error}"),
+            CommandError::Research(error) => write!(f, "{error}"),
+            CommandError::Unreachable(position) => {
+                write!(f, "({}, {}) cannot be reached", position.x, position.y)
+            }
+            CommandError::CannotHarvest { robot_id, position } => write!(
+                f,
+                "robot #{robot_id} cannot harvest anything at ({}, {})",
+                position.x, position.y
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+impl From<WorkshopError> for CommandError {
+    fn from(error: WorkshopError) -> Self {
+        CommandError::Workshop(error)
+    }
+}
+
+impl From<BuildError> for CommandError {
+    fn from(error: BuildError) -> Self {
+        CommandError::Build(error)
+    }
+}
+
+impl From<ResearchError> for CommandError {
+    fn from(error: ResearchError) -> Self {
+        CommandError::Research(error)
+    }
+}
+
+impl Simulation {
+    /// Applique une commande de l'utilisateur entre deux ticks
+    pub fn apply(&mut self, command: UserCommand) -> Result<(), CommandError> {
+        if let Some(recorder) = self.recorder.as_mut() {
+            recorder.record_command(self.tick, &command);
+        }
+
+        match command {
+            UserCommand::CreateRobot(robot_type) => {
+                self.create_robot(robot_type)?;
+            }
+            UserCommand::QueueRobot(robot_type) => self.station.queue_robot(robot_type)?,
+            UserCommand::Regenerate { seed } => self.regenerate(seed),
+            UserCommand::StartResearch(topic) => self.station.start_research(topic)?,
+            UserCommand::ToggleService { robot_id } => {
+                let (station, robot) = self.station_and_robot(robot_id)?;
+                if robot.service_requested {
+                    robot.service_requested = false;
+                    if robot.is_at_station(station) {
+                        station.docking.release(robot.id);
+                        robot.state = State::Idle;
+                    }
+                } else {
+                    robot.service_requested = true;
+                    robot.order = None;
+                    if !matches!(robot.state, State::Returning { .. }) {
+                        robot.state = State::Returning {
+                            base_position: station.position,
+                            path: Vec::new(),
+                        };
+                    }
+                }
+            }
+            UserCommand::UpgradeModule { robot_id, module } => {
+                let (station, robot) = self.station_and_robot(robot_id)?;
+                station.upgrade_module(robot, module)?;
+            }
+            UserCommand::RemoveModule { robot_id, module } => {
+                let (station, robot) = self.station_and_robot(robot_id)?;
+                station.remove_module(robot, module)?;
+            }
+            UserCommand::InstallModule { robot_id, storage } => {
+                let (station, robot) = self.station_and_robot(robot_id)?;
+                station.install_stored_module(robot, storage)?;
+            }
+            UserCommand::FabricateModule { robot_id, module } => {
+                let (station, robot) = self.station_and_robot(robot_id)?;
+                station.fabricate_module(robot, module)?;
+            }
+            UserCommand::Refit {
+                robot_id,
+                robot_type,
+            } => {
+                let (station, robot) = self.station_and_robot(robot_id)?;
+                station.refit_robot(robot, robot_type)?;
+            }
+            UserCommand::GoTo { robot_id, target } => {
+                self.give_order(robot_id, RobotOrder::GoTo(target))?
+            }
+            UserCommand::Harvest { robot_id, target } => {
+                self.give_order(robot_id, RobotOrder::Harvest(target))?
+            }
+        }
+        Ok(())
+    }
+
+    /// Vérifie qu'un ordre manuel est réalisable avant de le confier au robot
+    fn give_order(&mut self, robot_id: u32, order: RobotOrder) -> Result<(), CommandError> {
+        let target = order.target();
+        let start = self
+            .robots
+            .iter()
+            .find(|robot| robot.id == robot_id)
+            .map(|robot| robot.position)
+            .ok_or(CommandError::UnknownRobot(robot_id))?;
+
+        // La cible doit être accessible sur le terrain réel, obstacles contournés
+        if find_path(&self.map, start, target).is_none() {
+            return Err(CommandError::Unreachable(target));
+        }
+        let resource_type = self
+            .map
+            .get_tile(target.x, target.y)
+            .and_then(Tile::resource)
+            .map(|(resource_type, _)| resource_type);
+
+        let (station, robot) = self.station_and_robot(robot_id)?;
+        if let RobotOrder::Harvest(position) = order {
+            let harvestable = resource_type
+                .is_some_and(|resource_type| robot.harvest_rate(&resource_type).is_some());
+            if !harvestable {
+                return Err(CommandError::CannotHarvest { robot_id, position });
+            }
+        }
+
+        // Le robot abandonne sa baie ou sa place dans la file d'attente
+        station.docking.release(robot.id);
+        robot.service_requested = false;
+        robot.give_order(order);
+        Ok(())
+    }
+
+    fn station_and_robot(
+        &mut self,
+        robot_id: u32,
+    ) -> Result<(&mut Station, &mut Robot), CommandError> {
+        let robot = self
+            .robots
+            .iter_mut()
+            .find(|robot| robot.id == robot_id)
+            .ok_or(CommandError::UnknownRobot(robot_id))?;
+        Ok((&mut self.station, robot))
+    }
+}
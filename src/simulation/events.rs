@@ -0,0 +1,294 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, LineWriter, Write};
+use std::path::Path;
+
+use crossbeam::channel::{self, Receiver, Sender};
+use serde::{Deserialize, Serialize};
+
+use crate::robot::resources::ResourceType;
+use crate::robot::{Position, StateKind};
+use crate::station::RobotType;
+
+/// Nombre d'événements récents conservés pour l'affichage
+pub const RECENT_EVENTS_CAPACITY: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum LinkDirection {
+    /// Du robot vers la station
+    Uplink,
+    /// De la station vers le robot
+    Downlink,
+}
+
+/// Fait marquant de la simulation
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum SimEvent {
+    RobotSpawned {
+        robot_type: RobotType,
+        position: Position,
+    },
+    StateChanged {
+        from: StateKind,
+        to: StateKind,
+    },
+    /// Quantité mesurée par le robot, pas la quantité réelle
+    ResourceDiscovered {
+        resource_type: ResourceType,
+        position: Position,
+        amount: u32,
+    },
+    ResourceConsumed {
+        resource_type: ResourceType,
+        position: Position,
+        amount: u32,
+    },
+    ResourceDepleted {
+        resource_type: ResourceType,
+        position: Position,
+    },
+    RobotDocked {
+        position: Position,
+    },
+    ProductionCompleted {
+        robot_type: RobotType,
+    },
+    /// Message perdu par le transport ; seuls les rapports ont un identifiant
+    MessageDropped {
+        direction: LinkDirection,
+        message_id: Option<u64>,
+    },
+}
+
+/// Événement daté, rattaché au robot concerné s'il y en a un
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventRecord {
+    pub tick: u64,
+    pub robot_id: Option<u32>,
+    pub event: SimEvent,
+}
+
+impl fmt::Display for EventRecord {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "[{}] ", self.tick)?;
+        if let Some(robot_id) = self.robot_id {
+            write!(f, "#{robot_id} ")?;
+        }
+
+        match &self.event {
+            SimEvent::RobotSpawned {
+                robot_type,
+                position,
+            } => write!(
+                f,
+                "{} spawned at ({}, {})",
+                robot_type.name(),
+                position.x,
+                position.y
+            ),
+            SimEvent::StateChanged { from, to } => write!(f, "{from} -> {to}"),
+            SimEvent::ResourceDiscovered {
+                resource_type,
+                position,
+                amount,
+            } => write!(
+                f,
+                "found {resource_type:?} ~{amount} at ({}, {})",
+                position.x, position.y
+            ),
+            SimEvent::ResourceConsumed {
+                resource_type,
+                position,
+                amount,
+            } => write!(
+                f,
+                "took {amount} {resource_type:?} at ({}, {})",
+                position.x, position.y
+            ),
+            SimEvent::ResourceDepleted {
+                resource_type,
+                position,
+            } => write!(
+                f,
+                "{resource_type:?} depleted at ({}, {})",
+                position.x, position.y
+            ),
+            SimEvent::RobotDocked { position } => {
+                write!(f, "docked at ({}, {})", position.x, position.y)
+            }
+            SimEvent::ProductionCompleted { robot_type } => {
+                write!(f, "{} built", robot_type.name())
+            }
+            SimEvent::MessageDropped {
+                direction,
+                message_id,
+            } => {
+                write!(f, "{direction:?} message dropped")?;
+                match message_id {
+                    Some(message_id) => write!(f, " (id {message_id})"),
+                    None => Ok(()),
+                }
+            }
+        }
+    }
+}
+
+/// Destinataire des événements publiés
+pub trait EventSink: Send {
+    fn record(&mut self, record: &EventRecord);
+}
+
+/// Un abonné reçoit les événements sur un canal ; il est oublié quand il
+/// abandonne son récepteur
+impl EventSink for Sender<EventRecord> {
+    fn record(&mut self, record: &EventRecord) {
+        let _ = self.send(record.clone());
+    }
+}
+
+/// Journal au format JSON lines, un événement par ligne
+pub struct JsonLinesSink<W: Write + Send> {
+    writer: W,
+}
+
+impl JsonLinesSink<LineWriter<File>> {
+    pub fn create(path: &Path) -> io::Result<Self> {
+        Ok(Self::new(LineWriter::new(File::create(path)?)))
+    }
+}
+
+impl<W: Write + Send> JsonLinesSink<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<W: Write + Send> EventSink for JsonLinesSink<W> {
+    fn record(&mut self, record: &EventRecord) {
+        // Un journal illisible ne doit pas interrompre la simulation
+        if serde_json::to_writer(&mut self.writer, record).is_ok() {
+            let _ = self.writer.write_all(b"\n");
+        }
+    }
+}
+
+/// Journal des événements : garde les plus récents et les diffuse aux abonnés
+#[derive(Default)]
+pub struct EventLog {
+    recent: VecDeque<EventRecord>,
+    sinks: Vec<Box<dyn EventSink>>,
+    published: u64,
+}
+
+impl EventLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn publish(&mut self, tick: u64, robot_id: Option<u32>, event: SimEvent) {
+        let record = EventRecord {
+            tick,
+            robot_id,
+            event,
+        };
+        for sink in self.sinks.iter_mut() {
+            sink.record(&record);
+        }
+
+        if self.recent.len() == RECENT_EVENTS_CAPACITY {
+            self.recent.pop_front();
+        }
+        self.recent.push_back(record);
+        self.published += 1;
+    }
+
+    pub fn add_sink(&mut self, sink: impl EventSink + 'static) {
+        self.sinks.push(Box::new(sink));
+    }
+
+    /// Reçoit tous les événements publiés à partir de maintenant
+    pub fn subscribe(&mut self) -> Receiver<EventRecord> {
+        let (sender, receiver) = channel::unbounded();
+        self.add_sink(sender);
+        receiver
+    }
+
+    /// Événements récents, du plus ancien au plus récent
+    pub fn recent(&self) -> impl DoubleEndedIterator<Item = &EventRecord> + ExactSizeIterator {
+        self.recent.iter()
+    }
+
+    /// Nombre total d'événements publiés
+    pub fn published(&self) -> u64 {
+        self.published
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_events_reach_subscribers_and_json_sink() {
+        let mut log = EventLog::new();
+        let subscriber = log.subscribe();
+        log.publish(
+            3,
+            Some(2),
+            SimEvent::ResourceDepleted {
+                resource_type: ResourceType::Minerals,
+                position: Position { x: 4, y: 5 },
+            },
+        );
+        log.publish(
+            4,
+            None,
+            SimEvent::ProductionCompleted {
+                robot_type: RobotType::Miner,
+            },
+        );
+
+        let received: Vec<EventRecord> = subscriber.try_iter().collect();
+        assert_eq!(received.len(), 2);
+        assert_eq!(received[0].tick, 3);
+        assert_eq!(received[0].robot_id, Some(2));
+        assert_eq!(
+            received[0].to_string(),
+            "[3] #2 Minerals depleted at (4, 5)"
+        );
+        assert_eq!(log.recent().last(), Some(&received[1]));
+
+        let mut sink = JsonLinesSink::new(Vec::new());
+        for record in &received {
+            sink.record(record);
+        }
+        let output = String::from_utf8(sink.into_inner()).unwrap();
+        let parsed: Vec<EventRecord> = output
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        assert_eq!(parsed, received);
+    }
+
+    #[test]
+    fn test_recent_events_are_bounded() {
+        let mut log = EventLog::new();
+        for tick in 0..RECENT_EVENTS_CAPACITY as u64 + 10 {
+            log.publish(
+                tick,
+                None,
+                SimEvent::ProductionCompleted {
+                    robot_type: RobotType::Explorer,
+                },
+            );
+        }
+        assert_eq!(log.recent().len(), RECENT_EVENTS_CAPACITY);
+        assert_eq!(log.recent().next().unwrap().tick, 10);
+        assert_eq!(log.published(), RECENT_EVENTS_CAPACITY as u64 + 10);
+    }
+}
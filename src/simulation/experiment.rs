@@ -0,0 +1,514 @@
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::thread;
+
+use crossbeam::channel;
+use serde::{Deserialize, Serialize};
+
+use crate::map::pathfinding::UnknownTilePolicy;
+use crate::robot::cargo::CargoPolicy;
+use crate::robot::Resources;
+use crate::simulation::metrics::MetricsSample;
+use crate::simulation::radio::RadioConfig;
+use crate::simulation::transport::FaultConfig;
+use crate::simulation::{SimConfig, Simulation};
+use crate::station::RobotType;
+
+/// Côté minimal d'une carte : la station et les cases qui l'entourent
+pub const MIN_MAP_SIDE: u32 = 3;
+
+/// Configuration nommée comparée lors d'une expérience. Les champs absents
+/// d'un fichier prennent leur valeur par défaut.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ExperimentConfig {
+    pub name: String,
+    pub width: u32,
+    pub height: u32,
+    /// Flotte construite avant le premier tick
+    pub robots: Vec<RobotType>,
+    pub radio: RadioConfig,
+    pub faults: FaultConfig,
+    pub cargo_policy: CargoPolicy,
+    pub unknown_tile_policy: UnknownTilePolicy,
+}
+
+impl Default for ExperimentConfig {
+    fn default() -> Self {
+        let sim = SimConfig::default();
+        Self {
+            name: "baseline".to_string(),
+            width: sim.width,
+            height: sim.height,
+            robots: RobotType::ALL.to_vec(),
+            radio: sim.radio,
+            faults: sim.faults,
+            cargo_policy: CargoPolicy::default(),
+            unknown_tile_policy: UnknownTilePolicy::default(),
+        }
+    }
+}
+
+impl ExperimentConfig {
+    /// Configurations comparées quand aucun fichier n'est fourni
+    pub fn presets() -> Vec<Self> {
+        let named = |name: &str| Self {
+            name: name.to_string(),
+            ..Self::default()
+        };
+        vec![
+            Self::default(),
+            Self {
+                robots: vec![
+                    RobotType::Explorer,
+                    RobotType::Explorer,
+                    RobotType::Explorer,
+                    RobotType::Miner,
+                ],
+                ..named("explorers")
+            },
+            Self {
+                unknown_tile_policy: UnknownTilePolicy::Pessimistic,
+                ..named("cautious-paths")
+            },
+            Self {
+                cargo_policy: CargoPolicy {
+                    partial_return_ratio: 0.2,
+                },
+                ..named("early-return")
+            },
+            Self {
+                faults: FaultConfig::adversarial(),
+                ..named("lossy-radio")
+            },
+        ]
+    }
+
+    /// Liste de configurations au format JSON. Les noms identifient les
+    /// configurations dans le tableau des résultats : ils doivent être uniques.
+    pub fn load(path: &Path) -> Result<Vec<Self>, ExperimentError> {
+        let configs: Vec<Self> = serde_json::from_str(&fs::read_to_string(path)?)?;
+        if configs.is_empty() {
+            return Err(ExperimentError::NoConfigs);
+        }
+        for (index, config) in configs.iter().enumerate() {
+            if config.name.trim().is_empty() {
+                return Err(ExperimentError::MissingName { index });
+            }
+            if configs[..index]
+                .iter()
+                .any(|other| other.name == config.name)
+            {
+                return Err(ExperimentError::DuplicateName(config.name.clone()));
+            }
+            let too_small = config.width < MIN_MAP_SIDE || config.height < MIN_MAP_SIDE;
+            if too_small || config.width.checked_mul(config.height).is_none() {
+                return Err(ExperimentError::InvalidMapSize {
+                    name: config.name.clone(),
+                    width: config.width,
+                    height: config.height,
+                });
+            }
+        }
+        Ok(configs)
+    }
+
+    /// Simulation prête à démarrer. La carte ne dépend que de la graine :
+    /// toutes les configurations sont comparées sur les mêmes cartes.
+    pub fn simulation(&self, seed: u64) -> Simulation {
+        let mut sim = Simulation::with_config(&SimConfig {
+            width: self.width,
+            height: self.height,
+            seed,
+            radio: self.radio,
+            faults: self.faults,
+        });
+        sim.station.cargo_policy = self.cargo_policy;
+        sim.station.unknown_tile_policy = self.unknown_tile_policy;
+        for robot_type in &self.robots {
+            // Les robots que la station ne peut pas payer manquent à la flotte
+            let _ = sim.create_robot(*robot_type);
+        }
+        sim
+    }
+}
+
+#[derive(Debug)]
+pub enum ExperimentError {
+    Io(io::Error),
+    Parse(serde_json::Error),
+    NoConfigs,
+    /// Configuration sans nom, repérée par sa position dans le fichier
+    MissingName {
+        index: usize,
+    },
+    DuplicateName(String),
+    InvalidMapSize {
+        name: String,
+        width: u32,
+        height: u32,
+    },
+}
+
+impl fmt::Display for ExperimentError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExperimentError::Io(error) => write!(f, "experiment file error: {error}"),
+            ExperimentError::Parse(error) => write!(f, "invalid experiment file: {error}"),
+            ExperimentError::NoConfigs => write!(f, "experiment file has no configuration"),
+            ExperimentError::MissingName { index } => {
+                write!(f, "configuration #{index} has no name")
+            }
+            ExperimentError::DuplicateName(name) => {
+                write!(f, "configuration name {name:?} is used more than once")
+            }
+            ExperimentError::InvalidMapSize {
+                name,
+                width,
+                height,
+            } => write!(
+                f,
+                "configuration {name:?} has an invalid {width}x{height} map (at least {MIN_MAP_SIDE}x{MIN_MAP_SIDE})"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for ExperimentError {}
+
+impl From<io::Error> for ExperimentError {
+    fn from(error: io::Error) -> Self {
+        ExperimentError::Io(error)
+    }
+}
+
+impl From<serde_json::Error> for ExperimentError {
+    fn from(error: serde_json::Error) -> Self {
+        ExperimentError::Parse(error)
+    }
+}
+
+/// État final d'une simulation pour une configuration et une graine
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunOutcome {
+    pub config: String,
+    pub seed: u64,
+    pub sample: MetricsSample,
+    /// Ressources rapportées à la station pendant la partie
+    pub collected: Resources,
+}
+
+/// Valeur d'une mesure pour une partie
+pub type OutcomeMetric = fn(&RunOutcome) -> f64;
+
+/// Mesures comparées entre configurations
+pub const OUTCOME_METRICS: [(&str, OutcomeMetric); 11] = [
+    ("explored tiles", |run| run.sample.explored_tiles as f64),
+    ("discovered deposits", |run| {
+        (run.sample.discovered_energy
+            + run.sample.discovered_minerals
+            + run.sample.discovered_science) as f64
+    }),
+    ("energy collected", |run| f64::from(run.collected.energy)),
+    ("minerals collected", |run| {
+        f64::from(run.collected.minerals)
+    }),
+    ("science collected", |run| {
+        f64::from(run.collected.scientific_data)
+    }),
+    ("station energy", |run| f64::from(run.sample.station.energy)),
+    ("station minerals", |run| {
+        f64::from(run.sample.station.minerals)
+    }),
+    ("robots", |run| run.sample.robots as f64),
+    ("messages delivered", |run| {
+        run.sample.messages_delivered as f64
+    }),
+    ("messages retransmitted", |run| {
+        run.sample.messages_retransmitted as f64
+    }),
+    ("messages dropped", |run| run.sample.uplink_dropped as f64),
+];
+
+/// Joue une partie sans interface pendant `ticks` ticks
+pub fn run_once(config: &ExperimentConfig, seed: u64, ticks: u64) -> RunOutcome {
+    let mut sim = config.simulation(seed);
+    for _ in 0..ticks {
+        sim.step();
+    }
+    RunOutcome {
+        config: config.name.clone(),
+        seed,
+        sample: MetricsSample::collect(&sim),
+        collected: sim.station.history.total_collected(),
+    }
+}
+
+/// Joue chaque configuration sur chaque graine, réparties sur `threads` fils
+/// d'exécution. Les résultats suivent l'ordre des configurations puis des
+/// graines, quel que soit l'ordre de fin des parties.
+pub fn run_experiment(
+    configs: &[ExperimentConfig],
+    seeds: &[u64],
+    ticks: u64,
+    threads: usize,
+) -> Vec<RunOutcome> {
+    let (job_sender, jobs) = channel::unbounded();
+    for config_index in 0..configs.len() {
+        for seed_index in 0..seeds.len() {
+            let _ = job_sender.send((config_index, seed_index));
+        }
+    }
+    drop(job_sender);
+
+    let (result_sender, results) = channel::unbounded();
+    thread::scope(|scope| {
+        for _ in 0..threads.max(1) {
+            let jobs = jobs.clone();
+            let result_sender = result_sender.clone();
+            scope.spawn(move || {
+                for (config_index, seed_index) in jobs {
+                    let outcome = run_once(&configs[config_index], seeds[seed_index], ticks);
+                    let _ = result_sender.send((config_index, seed_index, outcome));
+                }
+            });
+        }
+    });
+    drop(result_sender);
+
+    let mut outcomes: Vec<(usize, usize, RunOutcome)> = results.into_iter().collect();
+    outcomes.sort_by_key(|(config_index, seed_index, _)| (*config_index, *seed_index));
+    outcomes
+        .into_iter()
+        .map(|(_, _, outcome)| outcome)
+        .collect()
+}
+
+/// Statistiques descriptives d'une mesure sur plusieurs parties
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Summary {
+    pub mean: f64,
+    /// Écart type corrigé de l'échantillon
+    pub stddev: f64,
+    pub min: f64,
+    pub p10: f64,
+    pub median: f64,
+    pub p90: f64,
+    pub max: f64,
+}
+
+impl Summary {
+    pub fn of(values: &[f64]) -> Option<Self> {
+        if values.is_empty() {
+            return None;
+        }
+        let mut sorted = values.to_vec();
+        sorted.sort_by(f64::total_cmp);
+
+        let count = sorted.len() as f64;
+        let mean = sorted.iter().sum::<f64>() / count;
+        let variance = if sorted.len() > 1 {
+            sorted
+                .iter()
+                .map(|value| (value - mean).powi(2))
+                .sum::<f64>()
+                / (count - 1.0)
+        } else {
+            0.0
+        };
+
+        Some(Self {
+            mean,
+            stddev: variance.sqrt(),
+            min: sorted[0],
+            p10: percentile(&sorted, 0.1),
+            median: percentile(&sorted, 0.5),
+            p90: percentile(&sorted, 0.9),
+            max: sorted[sorted.len() - 1],
+        })
+    }
+}
+
+/// Percentile d'une série triée, interpolé entre les deux rangs voisins
+fn percentile(sorted: &[f64], quantile: f64) -> f64 {
+    let rank = quantile * (sorted.len() - 1) as f64;
+    let (lower, upper) = (rank.floor() as usize, rank.ceil() as usize);
+    sorted[lower] + (sorted[upper] - sorted[lower]) * (rank - lower as f64)
+}
+
+/// Tableau comparant les configurations, une section par mesure
+pub fn comparison_table(configs: &[ExperimentConfig], outcomes: &[RunOutcome]) -> String {
+    let name_width = configs
+        .iter()
+        .map(|config| config.name.len())
+        .max()
+        .unwrap_or(0)
+        .max("config".len());
+
+    let mut table = String::new();
+    for (metric, value) in OUTCOME_METRICS {
+        table.push_str(&format!(
+            "{metric}\n  {:<name_width$} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10} {:>10}\n",
+            "config", "mean", "stddev", "min", "p10", "median", "p90", "max"
+        ));
+        for config in configs {
+            let values: Vec<f64> = outcomes
+                .iter()
+                .filter(|outcome| outcome.config == config.name)
+                .map(value)
+                .collect();
+            let Some(summary) = Summary::of(&values) else {
+                continue;
+            };
+            table.push_str(&format!(
+                "  {:<name_width$} {:>10.1} {:>10.1} {:>10.1} {:>10.1} {:>10.1} {:>10.1} {:>10.1}\n",
+                config.name,
+                summary.mean,
+                summary.stddev,
+                summary.min,
+                summary.p10,
+                summary.median,
+                summary.p90,
+                summary.max
+            ));
+        }
+        table.push('\n');
+    }
+    table
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn small(name: &str) -> ExperimentConfig {
+        ExperimentConfig {
+            name: name.to_string(),
+            width: 40,
+            height: 30,
+            ..ExperimentConfig::default()
+        }
+    }
+
+    #[test]
+    fn test_summary_statistics() {
+        let summary = Summary::of(&[4.0, 1.0, 3.0, 2.0, 5.0]).unwrap();
+        assert_eq!(summary.mean, 3.0);
+        assert!((summary.stddev - 2.5f64.sqrt()).abs() < 1e-9);
+        assert_eq!(summary.min, 1.0);
+        assert_eq!(summary.median, 3.0);
+        assert!((summary.p10 - 1.4).abs() < 1e-9);
+        assert!((summary.p90 - 4.6).abs() < 1e-9);
+        assert_eq!(summary.max, 5.0);
+
+        let single = Summary::of(&[7.0]).unwrap();
+        assert_eq!((single.stddev, single.p90), (0.0, 7.0));
+        assert_eq!(Summary::of(&[]), None);
+    }
+
+    #[test]
+    fn test_parallel_runs_match_sequential_runs() {
+        let configs = vec![
+            small("baseline"),
+            ExperimentConfig {
+                robots: vec![RobotType::Explorer],
+                ..small("solo")
+            },
+        ];
+        let seeds = [3, 8];
+        let outcomes = run_experiment(&configs, &seeds, 60, 3);
+
+        assert_eq!(outcomes.len(), 4);
+        let mut expected = Vec::new();
+        for config in &configs {
+            for seed in seeds {
+                expected.push(run_once(config, seed, 60));
+            }
+        }
+        assert_eq!(outcomes, expected);
+        assert_eq!(outcomes[2].config, "solo");
+        assert_eq!(outcomes[2].sample.robots, 1);
+
+        let table = comparison_table(&configs, &outcomes);
+        assert!(table.contains("explored tiles"));
+        assert!(table.contains("messages retransmitted") && table.contains("messages dropped"));
+        assert!(table
+            .lines()
+            .any(|line| line.trim_start().starts_with("solo")));
+    }
+
+    /// Charge des configurations écrites dans un fichier temporaire
+    fn load(label: &str, json: &str) -> Result<Vec<ExperimentConfig>, ExperimentError> {
+        let path = std::env::temp_dir().join(format!(
+            "robot_swarm_experiment_{label}_{}.json",
+            std::process::id()
+        ));
+        fs::write(&path, json).unwrap();
+        let configs = ExperimentConfig::load(&path);
+        fs::remove_file(&path).unwrap();
+        configs
+    }
+
+    #[test]
+    fn test_configs_load_with_defaults() {
+        let configs = load(
+            "defaults",
+            r#"[{"name": "cautious", "unknown_tile_policy": "Pessimistic"}, {"name": "duo", "robots": ["Explorer", "Miner"]}]"#,
+        )
+        .unwrap();
+
+        assert_eq!(configs.len(), 2);
+        assert_eq!(
+            configs[0].unknown_tile_policy,
+            UnknownTilePolicy::Pessimistic
+        );
+        assert_eq!(configs[0].robots, RobotType::ALL.to_vec());
+        assert_eq!(
+            configs[1].robots,
+            vec![RobotType::Explorer, RobotType::Miner]
+        );
+        assert_eq!(configs[1].width, SimConfig::default().width);
+    }
+
+    #[test]
+    fn test_configs_need_unique_names_and_a_usable_map() {
+        assert!(matches!(
+            load("unnamed", r#"[{"name": "a"}, {"name": " "}]"#),
+            Err(ExperimentError::MissingName { index: 1 })
+        ));
+        assert!(matches!(
+            load("duplicate", r#"[{"name": "a"}, {"name": "b"}, {"name": "a"}]"#),
+            Err(ExperimentError::DuplicateName(name)) if name == "a"
+        ));
+        assert!(matches!(
+            load("empty_map", r#"[{"name": "a", "width": 0, "height": 0}]"#),
+            Err(ExperimentError::InvalidMapSize {
+                width: 0,
+                height: 0,
+                ..
+            })
+        ));
+        assert!(matches!(
+            load("thin_map", r#"[{"name": "a", "width": 50, "height": 2}]"#),
+            Err(ExperimentError::InvalidMapSize { .. })
+        ));
+        assert!(matches!(
+            load(
+                "huge_map",
+                r#"[{"name": "a", "width": 100000, "height": 100000}]"#
+            ),
+            Err(ExperimentError::InvalidMapSize { .. })
+        ));
+        assert_eq!(
+            load(
+                "smallest_map",
+                r#"[{"name": "a", "width": 3, "height": 3}]"#
+            )
+            .unwrap()
+            .len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,421 @@
+use std::collections::BTreeMap;
+use std::fs::File;
+use std::io::{self, LineWriter, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::robot::{Resources, StateKind};
+use crate::simulation::Simulation;
+use crate::station::RobotType;
+
+/// Ticks entre deux relevés quand l'intervalle n'est pas précisé
+pub const DEFAULT_METRICS_INTERVAL: u64 = 10;
+
+/// Format du fichier de mesures
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MetricsFormat {
+    /// Une ligne d'en-tête puis une ligne par relevé
+    Csv,
+    /// Un relevé JSON par ligne
+    JsonLines,
+}
+
+impl MetricsFormat {
+    /// CSV pour les fichiers `.csv`, JSON lines pour tous les autres
+    pub fn from_path(path: &Path) -> Self {
+        match path.extension().and_then(|extension| extension.to_str()) {
+            Some(extension) if extension.eq_ignore_ascii_case("csv") => MetricsFormat::Csv,
+            _ => MetricsFormat::JsonLines,
+        }
+    }
+}
+
+/// État de la simulation relevé à un tick donné
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MetricsSample {
+    pub tick: u64,
+    /// Stock de la station
+    pub station: Resources,
+    pub robots: usize,
+    pub robots_by_state: BTreeMap<String, usize>,
+    pub robots_by_type: BTreeMap<String, usize>,
+    pub explored_tiles: usize,
+    /// Gisements connus de la station, par type
+    pub discovered_energy: usize,
+    pub discovered_minerals: usize,
+    pub discovered_science: usize,
+    /// Quantités encore présentes sur la carte
+    pub remaining_energy: u32,
+    pub remaining_minerals: u32,
+    pub remaining_science: u32,
+    /// Messages émis par les robots, hors réémissions
+    pub messages_emitted: u64,
+    /// Paquets acceptés par le lien montant, c'est-à-dire envoyés et non perdus
+    pub messages_delivered: u64,
+    pub messages_retransmitted: u64,
+    pub messages_unacked: usize,
+    /// Messages en attente dans les files d'envoi des robots
+    pub messages_queued: usize,
+    pub uplink_sent: u64,
+    pub uplink_dropped: u64,
+    pub downlink_sent: u64,
+    pub downlink_dropped: u64,
+}
+
+impl MetricsSample {
+    pub fn collect(sim: &Simulation) -> Self {
+        let mut robots_by_state: BTreeMap<String, usize> = StateKind::ALL
+            .iter()
+            .map(|kind| (kind.name().to_string(), 0))
+            .collect();
+        let mut robots_by_type: BTreeMap<String, usize> = RobotType::ALL
+            .iter()
+            .map(|robot_type| (robot_type.name().to_string(), 0))
+            .collect();
+        for robot in &sim.robots {
+            *robots_by_state
+                .entry(robot.state.name().to_string())
+                .or_default() += 1;
+            *robots_by_type
+                .entry(robot.role().name().to_string())
+                .or_default() += 1;
+        }
+
+        let (discovered_energy, discovered_minerals, discovered_science) =
+            sim.station.get_discovered_resource_counts();
+        let (remaining_energy, remaining_minerals, remaining_science) =
+            sim.map.calculate_total_resources();
+        let (uplink, downlink) = sim.network.transport_stats();
+
+        Self {
+            tick: sim.tick,
+            station: sim.station.resources.clone(),
+            robots: sim.robots.len(),
+            robots_by_state,
+            robots_by_type,
+            explored_tiles: sim.station.knowledge.explored_count(),
+            discovered_energy,
+            discovered_minerals,
+            discovered_science,
+            remaining_energy,
+            remaining_minerals,
+            remaining_science,
+            messages_emitted: sim.network.emitted(),
+            messages_delivered: uplink.sent - uplink.dropped,
+            messages_retransmitted: sim.network.retransmitted(),
+            messages_unacked: sim.network.unacked(),
+            messages_queued: sim.robots.iter().map(|robot| robot.outbox.len()).sum(),
+            uplink_sent: uplink.sent,
+            uplink_dropped: uplink.dropped,
+            downlink_sent: downlink.sent,
+            downlink_dropped: downlink.dropped,
+        }
+    }
+
+    /// Colonnes CSV et leurs valeurs, toujours dans le même ordre
+    fn columns(&self) -> Vec<(String, String)> {
+        let mut columns = vec![
+            ("tick".to_string(), self.tick.to_string()),
+            (
+                "station_energy".to_string(),
+                self.station.energy.to_string(),
+            ),
+            (
+                "station_minerals".to_string(),
+                self.station.minerals.to_string(),
+            ),
+            (
+                "station_science".to_string(),
+                self.station.scientific_data.to_string(),
+            ),
+            ("robots".to_string(), self.robots.to_string()),
+        ];
+        for (state, count) in &self.robots_by_state {
+            columns.push((format!("state_{state}"), count.to_string()));
+        }
+        for (robot_type, count) in &self.robots_by_type {
+            columns.push((format!("type_{robot_type}"), count.to_string()));
+        }
+        for (name, value) in [
+            ("explored_tiles", self.explored_tiles as u64),
+            ("discovered_energy", self.discovered_energy as u64),
+            ("discovered_minerals", self.discovered_minerals as u64),
+            ("discovered_science", self.discovered_science as u64),
+            ("remaining_energy", u64::from(self.remaining_energy)),
+            ("remaining_minerals", u64::from(self.remaining_minerals)),
+            ("remaining_science", u64::from(self.remaining_science)),
+            ("messages_emitted", self.messages_emitted),
+            ("messages_delivered", self.messages_delivered),
+            ("messages_retransmitted", self.messages_retransmitted),
+            ("messages_unacked", self.messages_unacked as u64),
+            ("messages_queued", self.messages_queued as u64),
+            ("uplink_sent", self.uplink_sent),
+            ("uplink_dropped", self.uplink_dropped),
+            ("downlink_sent", self.downlink_sent),
+            ("downlink_dropped", self.downlink_dropped),
+        ] {
+            columns.push((name.to_string(), value.to_string()));
+        }
+        columns
+    }
+}
+
+/// Écrit un relevé de la simulation tous les `interval` ticks
+pub struct MetricsRecorder {
+    writer: Box<dyn Write + Send>,
+    format: MetricsFormat,
+    interval: u64,
+    header_written: bool,
+    /// Première erreur d'écriture, les relevés suivants sont abandonnés
+    error: Option<io::Error>,
+}
+
+impl MetricsRecorder {
+    pub fn create(path: &Path, interval: u64) -> io::Result<Self> {
+        Ok(Self::new(
+            LineWriter::new(File::create(path)?),
+            MetricsFormat::from_path(path),
+            interval,
+        ))
+    }
+
+    pub fn new(writer: impl Write + Send + 'static, format: MetricsFormat, interval: u64) -> Self {
+        Self {
+            writer: Box::new(writer),
+            format,
+            interval: interval.max(1),
+            header_written: false,
+            error: None,
+        }
+    }
+
+    /// Indique si un relevé est attendu à ce tick
+    pub fn is_due(&self, tick: u64) -> bool {
+        tick.is_multiple_of(self.interval)
+    }
+
+    /// Écrit un relevé. Une erreur n'interrompt pas la simulation : elle est
+    /// conservée et renvoyée par `finish`
+    pub fn record(&mut self, sample: &MetricsSample) {
+        if self.error.is_none() {
+            self.error = self.write(sample).err();
+        }
+    }
+
+    /// Vide le tampon et renvoie la première erreur d'écriture
+    pub fn finish(mut self) -> io::Result<()> {
+        match self.error.take() {
+            Some(error) => Err(error),
+            None => self.writer.flush(),
+        }
+    }
+
+    fn write(&mut self, sample: &MetricsSample) -> io::Result<()> {
+        match self.format {
+            MetricsFormat::JsonLines => serde_json::to_writer(&mut self.writer, sample)?,
+            MetricsFormat::Csv => {
+                let columns = sample.columns();
+                if !self.header_written {
+                    let header: Vec<&str> = columns.iter().map(|(name, _)| name.as_str()).collect();
+                    writeln!(self.writer, "{}", header.join(","))?;
+                    self.header_written = true;
+                }
+                let values: Vec<&str> = columns.iter().map(|(_, value)| value.as_str()).collect();
+                write!(self.writer, "{}", values.join(","))?;
+            }
+        }
+        self.writer.write_all(b"\n")
+    }
+}
+
+impl Simulation {
+    /// Relève l'état courant puis tous les `interval` ticks
+    pub fn start_metrics(&mut self, mut recorder: MetricsRecorder) {
+        recorder.record(&MetricsSample::collect(self));
+        self.metrics = Some(recorder);
+    }
+
+    /// Arrête les relevés et signale une éventuelle erreur d'écriture
+    pub fn finish_metrics(&mut self) -> io::Result<()> {
+        self.metrics.take().map_or(Ok(()), MetricsRecorder::finish)
+    }
+
+    pub(crate) fn record_metrics(&mut self) {
+        let due = self
+            .metrics
+            .as_ref()
+            .is_some_and(|metrics| metrics.is_due(self.tick));
+        if due {
+            let sample = MetricsSample::collect(self);
+            if let Some(metrics) = self.metrics.as_mut() {
+                metrics.record(&sample);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+
+    use super::*;
+    use crate::robot::resources::ResourceType;
+    use crate::simulation::transport::FaultConfig;
+    use crate::simulation::SimConfig;
+    use crate::station::communication::RobotMessage;
+
+    /// Tampon partagé pour relire ce que l'enregistreur a écrit
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, data: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(data);
+            Ok(data.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    impl SharedBuffer {
+        fn contents(&self) -> String {
+            String::from_utf8(self.0.lock().unwrap().clone()).unwrap()
+        }
+    }
+
+    /// Support plein : toute écriture échoue
+    struct FullDisk;
+
+    impl Write for FullDisk {
+        fn write(&mut self, _: &[u8]) -> io::Result<usize> {
+            Err(io::Error::new(io::ErrorKind::StorageFull, "disk full"))
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn simulation() -> Simulation {
+        let mut sim = Simulation::with_config(&SimConfig {
+            width: 60,
+            height: 40,
+            seed: 11,
+            ..SimConfig::default()
+        });
+        sim.create_robot(RobotType::Explorer).unwrap();
+        sim.create_robot(RobotType::Miner).unwrap();
+        sim
+    }
+
+    #[test]
+    fn test_sample_counts_robots_by_state_and_type() {
+        let sim = simulation();
+        let sample = MetricsSample::collect(&sim);
+        assert_eq!(sample.robots, 2);
+        assert_eq!(sample.robots_by_type["explorer"], 1);
+        assert_eq!(sample.robots_by_type["miner"], 1);
+        assert_eq!(sample.robots_by_type["scientist"], 0);
+        assert_eq!(sample.robots_by_state.values().sum::<usize>(), 2);
+        assert_eq!(sample.robots_by_state.len(), StateKind::ALL.len());
+        assert_eq!(
+            sample.remaining_energy,
+            sim.map.calculate_total_resources().0
+        );
+    }
+
+    #[test]
+    fn test_json_lines_are_written_every_interval() {
+        let buffer = SharedBuffer::default();
+        let mut sim = simulation();
+        sim.start_metrics(MetricsRecorder::new(
+            buffer.clone(),
+            MetricsFormat::JsonLines,
+            5,
+        ));
+        for _ in 0..12 {
+            sim.step();
+        }
+
+        let samples: Vec<MetricsSample> = buffer
+            .contents()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        let ticks: Vec<u64> = samples.iter().map(|sample| sample.tick).collect();
+        assert_eq!(ticks, vec![0, 5, 10]);
+    }
+
+    #[test]
+    fn test_lost_messages_are_emitted_but_not_delivered() {
+        let mut sim = simulation();
+        sim.network.set_faults(FaultConfig {
+            drop_rate: 1.0,
+            ..FaultConfig::perfect()
+        });
+        let position = sim.robots[0].position;
+        sim.robots[0]
+            .outbox
+            .push_back(RobotMessage::ResourceDiscovered {
+                resource_type: ResourceType::Minerals,
+                position,
+                amount: 100,
+                confidence: 1.0,
+            });
+        sim.step();
+
+        let sample = MetricsSample::collect(&sim);
+        assert!(sample.messages_emitted >= 1);
+        assert_eq!(sample.messages_delivered, 0);
+        assert_eq!(sample.uplink_dropped, sample.uplink_sent);
+    }
+
+    #[test]
+    fn test_csv_has_a_header_and_aligned_rows() {
+        let buffer = SharedBuffer::default();
+        let mut sim = simulation();
+        sim.start_metrics(MetricsRecorder::new(buffer.clone(), MetricsFormat::Csv, 2));
+        for _ in 0..4 {
+            sim.step();
+        }
+
+        let contents = buffer.contents();
+        let lines: Vec<&str> = contents.lines().collect();
+        assert_eq!(lines.len(), 4);
+        assert!(lines[0].starts_with("tick,station_energy,"));
+        assert!(lines[0].contains("state_exploring") && lines[0].contains("type_miner"));
+        let columns = lines[0].split(',').count();
+        assert!(lines[1..]
+            .iter()
+            .all(|line| line.split(',').count() == columns));
+        assert!(lines[3].starts_with("4,"));
+        assert_eq!(
+            MetricsFormat::from_path(Path::new("run.CSV")),
+            MetricsFormat::Csv
+        );
+        assert_eq!(
+            MetricsFormat::from_path(Path::new("run.jsonl")),
+            MetricsFormat::JsonLines
+        );
+    }
+
+    #[test]
+    fn test_write_errors_are_reported_when_metrics_finish() {
+        let mut sim = simulation();
+        sim.start_metrics(MetricsRecorder::new(FullDisk, MetricsFormat::Csv, 1));
+        for _ in 0..3 {
+            sim.step();
+        }
+        let error = sim.finish_metrics().unwrap_err();
+        assert_eq!(error.kind(), io::ErrorKind::StorageFull);
+        assert!(sim.metrics.is_none());
+
+        let buffer = SharedBuffer::default();
+        sim.start_metrics(MetricsRecorder::new(buffer.clone(), MetricsFormat::Csv, 1));
+        assert!(sim.finish_metrics().is_ok());
+        assert_eq!(buffer.contents().lines().count(), 2);
+    }
+}
@@ -0,0 +1,925 @@
+pub mod clock;
+pub mod command;
+pub mod events;
+pub mod experiment;
+pub mod metrics;
+pub mod network;
+pub mod radio;
+pub mod replay;
+pub mod transport;
+
+use crate::map::Map;
+use crate::robot::gossip::GOSSIP_INTERVAL_TICKS;
+use crate::robot::{Position, Robot, RobotOrder, State, StateKind};
+use crate::station::docking::APPROACH_RADIUS;
+use crate::station::fleet::{Liveness, HEARTBEAT_INTERVAL_TICKS, RESCUE_SEARCH_RADIUS};
+use crate::station::production::BuildError;
+use crate::station::{RobotType, Station};
+use events::{EventLog, SimEvent};
+use metrics::MetricsRecorder;
+use network::Network;
+use radio::RadioConfig;
+use replay::ReplayRecorder;
+use serde::{Deserialize, Serialize};
+use transport::FaultConfig;
+
+/// Paramètres de départ d'une simulation, suffisants pour la reproduire
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct SimConfig {
+    pub width: u32,
+    pub height: u32,
+    pub seed: u64,
+    pub radio: RadioConfig,
+    pub faults: FaultConfig,
+}
+
+impl Default for SimConfig {
+    fn default() -> Self {
+        Self {
+            width: 200,
+            height: 100,
+            seed: 0,
+            radio: RadioConfig::default(),
+            faults: FaultConfig::perfect(),
+        }
+    }
+}
+
+/// État complet de la simulation, avancé tick par tick par `step`
+pub struct Simulation {
+    pub map: Map,
+    pub station: Station,
+    pub robots: Vec<Robot>,
+    pub tick: u64,
+    pub network: Network,
+    /// Synchronise la carte des robots amarrés avec la station. Désactivé
+    /// quand la station est une réplique locale d'une station distante.
+    pub sync_at_dock: bool,
+    pub events: EventLog,
+    /// Enregistrement en cours des commandes et de l'empreinte de chaque tick
+    pub recorder: Option<ReplayRecorder>,
+    /// Relevés périodiques exportés pour l'analyse hors ligne
+    pub metrics: Option<MetricsRecorder>,
+}
+
+impl Simulation {
+    pub fn new(width: u32, height: u32, seed: u64) -> Self {
+        let mut map = Map::new(width, height, seed);
+        let station = Station::new(&mut map);
+        Self::from_world(map, station, seed)
+    }
+
+    pub fn with_config(config: &SimConfig) -> Self {
+        let mut sim = Self::new(config.width, config.height, config.seed);
+        sim.network = Network::new(config.radio, config.faults, config.seed);
+        sim
+    }
+
+    /// Paramètres courants, tels qu'ils seraient passés à `with_config`
+    pub fn config(&self) -> SimConfig {
+        SimConfig {
+            width: self.map.width,
+            height: self.map.height,
+            seed: self.map.seed,
+            radio: self.network.radio,
+            faults: self.network.faults(),
+        }
+    }
+
+    /// Simulation sans robot autour d'une carte et d'une station existantes
+    pub fn from_world(map: Map, station: Station, seed: u64) -> Self {
+        Self {
+            map,
+            station,
+            robots: Vec::new(),
+            tick: 0,
+            network: Network::new(RadioConfig::default(), FaultConfig::perfect(), seed),
+            sync_at_dock: true,
+            events: EventLog::new(),
+            recorder: None,
+            metrics: None,
+        }
+    }
+
+    /// Régénère la carte avec une nouvelle graine en gardant la station et les robots
+    pub fn regenerate(&mut self, seed: u64) {
+        self.map = Map::new(self.map.width, self.map.height, seed);
+        self.robots.iter_mut().for_each(|robot| {
+            robot.position = Position { x: 0, y: 0 };
+        });
+
+        Station::new(&mut self.map);
+        self.station.docking.reset();
+    }
+
+    /// Construit immédiatement un robot et renvoie son identifiant
+    pub fn create_robot(&mut self, robot_type: RobotType) -> Result<u32, BuildError> {
+        let robot = self.station.try_create_robot(robot_type)?;
+        Ok(self.spawn(robot))
+    }
+
+    /// Met en service un robot sorti de la station
+    fn spawn(&mut self, mut robot: Robot) -> u32 {
+        robot.seed_rng(self.map.seed);
+        self.events.publish(
+            self.tick,
+            Some(robot.id),
+            SimEvent::RobotSpawned {
+                robot_type: robot.role(),
+                position: robot.position,
+            },
+        );
+        let robot_id = robot.id;
+        self.robots.push(robot);
+        robot_id
+    }
+
+    /// Avance la simulation d'un tick
+    pub fn step(&mut self) {
+        self.advance();
+        self.network.exchange(
+            self.tick,
+            &mut self.map,
+            &mut self.station,
+            &mut self.robots,
+            &mut self.events,
+        );
+        let hash = self.recorder.is_some().then(|| self.state_hash());
+        if let (Some(recorder), Some(hash)) = (self.recorder.as_mut(), hash) {
+            recorder.record_hash(self.tick, hash);
+        }
+        self.record_metrics();
+    }
+
+    /// Avance le monde d'un tick sans échanger de messages radio : les messages
+    /// des robots restent dans leur file d'envoi
+    pub fn advance(&mut self) {
+        self.tick += 1;
+        self.station.clock = self.tick;
+        self.station.update(&self.map);
+        // Les robots de la file ont été payés à la commande
+        if let Some(robot_type) = self.station.production_queue.update() {
+            let robot = self.station.assemble_robot(robot_type);
+            let robot_id = self.spawn(robot);
+            self.events.publish(
+                self.tick,
+                Some(robot_id),
+                SimEvent::ProductionCompleted { robot_type },
+            );
+        }
+
+        let energy_model = self.station.energy_model;
+        let previous_states: Vec<StateKind> =
+            self.robots.iter().map(|robot| robot.state.kind()).collect();
+
+        for robot in self.robots.iter_mut() {
+            robot.clock = self.tick;
+            let docked =
+                matches!(robot.state, State::Returning { .. }) && robot.is_at_station(&self.station);
+            if !docked {
+                robot.apply_idle_drain(&energy_model);
+            }
+
+            match robot.state {
+                State::Idle => {
+                    robot.explore_map(&self.map, &self.station);
+                }
+                State::Exploring { .. } => {
+                    robot.explore_map(&self.map, &self.station);
+                }
+                State::Collecting { .. } => {
+                    robot.collect_resource(&mut self.map, &self.station);
+                }
+                State::Returning { .. } => {
+                    if docked {
+                        let arrived =
+                            Self::service_docked_robot(&mut self.station, robot, self.sync_at_dock);
+                        if arrived {
+                            self.events.publish(
+                                self.tick,
+                                Some(robot.id),
+                                SimEvent::RobotDocked {
+                                    position: robot.position,
+                                },
+                            );
+                        }
+                    } else if robot.energy > 0.0
+                        && robot.position.distance_to(&self.station.position) <= APPROACH_RADIUS
+                    {
+                        // Attente dans la file tant qu'aucune baie n'est libre
+                        if let Some(bay) = self.station.docking.request_bay(robot.id) {
+                            robot.set_return_target(bay);
+                            robot.return_to_station(&self.map, &self.station);
+                        }
+                    } else {
+                        robot.return_to_station(&self.map, &self.station);
+                    }
+                }
+
+                _ => {
+                    robot.move_randomly(&self.map);
+                }
+            }
+
+            if self.tick.is_multiple_of(HEARTBEAT_INTERVAL_TICKS) {
+                robot.queue_heartbeat(self.tick);
+            }
+        }
+
+        self.station.docking.drop_stalled_reservations(&self.robots);
+        self.hand_off_cargo();
+        self.rescue_lost_robots();
+        if self.tick.is_multiple_of(GOSSIP_INTERVAL_TICKS) {
+            self.gossip();
+        }
+        self.collect_robot_events(&previous_states);
+    }
+
+    /// Date les événements des robots et relève leurs changements d'état
+    fn collect_robot_events(&mut self, previous_states: &[StateKind]) {
+        for (index, robot) in self.robots.iter_mut().enumerate() {
+            for event in robot.events.drain(..) {
+                self.events.publish(self.tick, Some(robot.id), event);
+            }
+
+            let state = robot.state.kind();
+            match previous_states.get(index) {
+                Some(&previous) if previous != state => self.events.publish(
+                    self.tick,
+                    Some(robot.id),
+                    SimEvent::StateChanged {
+                        from: previous,
+                        to: state,
+                    },
+                ),
+                _ => {}
+            }
+        }
+    }
+
+    /// Les robots à portée de communication échangent leurs cartes partagées
+    fn gossip(&mut self) {
+        for j in 1..self.robots.len() {
+            let (left, right) = self.robots.split_at_mut(j);
+            let other = &mut right[0];
+            for robot in left.iter_mut() {
+                if robot.in_comm_range(other) {
+                    robot.gossip_with(other);
+                }
+            }
+        }
+    }
+
+    /// Un robot en récolte confie son chargement à un robot voisin qui rentre à la station
+    fn hand_off_cargo(&mut self) {
+        for i in 0..self.robots.len() {
+            for j in 0..self.robots.len() {
+                if i == j {
+                    continue;
+                }
+
+                let (giver, receiver) = if i < j {
+                    let (left, right) = self.robots.split_at_mut(j);
+                    (&mut left[i], &mut right[0])
+                } else {
+                    let (left, right) = self.robots.split_at_mut(i);
+                    (&mut right[0], &mut left[j])
+                };
+
+                let adjacent = giver.position.distance_to(&receiver.position) <= 1;
+                let giver_working = matches!(giver.state, State::Collecting { .. });
+                let receiver_returning = matches!(receiver.state, State::Returning { .. })
+                    && !receiver.is_at_station(&self.station);
+
+                if adjacent && giver_working && receiver_returning && giver.cargo_load() > 0 {
+                    giver.transfer_cargo_to(receiver);
+                }
+            }
+        }
+    }
+
+    /// Envoie le robot disponible le plus proche fouiller les alentours de la
+    /// dernière position connue de chaque robot perdu. Un robot aperçu est
+    /// rejoint, puis le sauveteur partage son énergie s'il est en panne. Faute
+    /// de le trouver, la recherche est close et le robot reste perdu ; seul un
+    /// robot disparu de la simulation cesse d'être suivi.
+    fn rescue_lost_robots(&mut self) {
+        let tasks = self.station.fleet.recoveries().to_vec();
+        for task in tasks {
+            let Some(waypoint) = task.search_waypoint(self.map.width, self.map.height) else {
+                if self.robots.iter().any(|robot| robot.id == task.robot_id) {
+                    self.station.fleet.close_recovery(task.robot_id);
+                } else {
+                    self.station.fleet.unregister(task.robot_id);
+                }
+                continue;
+            };
+            let order = RobotOrder::GoTo(waypoint);
+            let Some(rescuer_id) = task.rescuer else {
+                let fleet = &self.station.fleet;
+                let rescuer = self
+                    .robots
+                    .iter_mut()
+                    .filter(|robot| {
+                        robot.id != task.robot_id
+                            && robot.order.is_none()
+                            && matches!(robot.state, State::Idle)
+                            && fleet.liveness(robot.id) == Some(Liveness::Alive)
+                    })
+                    .min_by_key(|robot| (robot.position.distance_to(&waypoint), robot.id));
+                if let Some(rescuer) = rescuer {
+                    rescuer.give_order(order);
+                    let rescuer_id = rescuer.id;
+                    self.station
+                        .fleet
+                        .assign_rescuer(task.robot_id, Some(rescuer_id));
+                }
+                continue;
+            };
+
+            let Some(rescuer_index) = self.robots.iter().position(|robot| robot.id == rescuer_id)
+            else {
+                self.station.fleet.assign_rescuer(task.robot_id, None);
+                continue;
+            };
+            if self.robots[rescuer_index].order == Some(order) {
+                continue;
+            }
+            // Ordre interrompu (retour à la station, nouvel ordre) : un autre
+            // robot prendra le relais. Un sauveteur inactif a atteint le point
+            // de fouille, ou s'en est approché autant que possible.
+            if !matches!(self.robots[rescuer_index].state, State::Idle) {
+                self.station.fleet.assign_rescuer(task.robot_id, None);
+                continue;
+            }
+
+            let rescuer_position = self.robots[rescuer_index].position;
+            let sighted = self.robots.iter().position(|robot| {
+                robot.id == task.robot_id
+                    && robot.position.distance_to(&rescuer_position) <= RESCUE_SEARCH_RADIUS
+            });
+            match sighted {
+                Some(lost_index)
+                    if self.robots[lost_index]
+                        .position
+                        .distance_to(&rescuer_position)
+                        <= 1 =>
+                {
+                    let reserve = self.robots[rescuer_index].return_cost(&self.map, &self.station);
+                    let (rescuer, lost) = if rescuer_index < lost_index {
+                        let (left, right) = self.robots.split_at_mut(lost_index);
+                        (&mut left[rescuer_index], &mut right[0])
+                    } else {
+                        let (left, right) = self.robots.split_at_mut(rescuer_index);
+                        (&mut right[0], &mut left[lost_index])
+                    };
+                    if lost.energy <= 0.0 {
+                        rescuer.share_energy_with(lost, reserve);
+                    }
+                    self.station.fleet.close_recovery(task.robot_id);
+                }
+                Some(lost_index) => {
+                    let position = self.robots[lost_index].position;
+                    self.station.fleet.sight_lost_robot(task.robot_id, position);
+                    self.robots[rescuer_index].give_order(RobotOrder::GoTo(position));
+                }
+                None => {
+                    let mut next = task.clone();
+                    next.search_step += 1;
+                    self.station.fleet.advance_search(task.robot_id);
+                    if let Some(waypoint) = next.search_waypoint(self.map.width, self.map.height) {
+                        self.robots[rescuer_index].give_order(RobotOrder::GoTo(waypoint));
+                    }
+                }
+            }
+        }
+    }
+
+    /// Sert un robot à quai. Retourne vrai à son premier tick d'amarrage.
+    fn service_docked_robot(
+        station: &mut Station,
+        robot: &mut Robot,
+        sync_knowledge: bool,
+    ) -> bool {
+        let charged = station.recharge_robot(robot);
+
+        let arrived = station.docking.service_tick(robot.id) == 1;
+        if arrived && sync_knowledge {
+            station.sync_robot_knowledge(robot);
+        }
+        let unloaded = station.collect_robot_resources(robot);
+
+        if charged && unloaded && !robot.service_requested {
+            station.docking.release(robot.id);
+            robot.state = State::Idle;
+        }
+        arrived
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::robot::cargo::BASE_CARGO_CAPACITY;
+    use crate::robot::HardwareModule;
+
+    fn docked_robot(sim: &mut Simulation, id: u32, energy: f32) -> Robot {
+        let bay = sim.station.docking.request_bay(id).unwrap();
+        let mut robot = Robot::new(bay, vec![]);
+        robot.id = id;
+        robot.energy = energy;
+        robot.state = State::Returning {
+            base_position: bay,
+            path: Vec::new(),
+        };
+        robot
+    }
+
+    #[test]
+    fn test_recharge_takes_time_and_draws_station_energy() {
+        let mut sim = Simulation::new(50, 50, 42);
+        let robot = docked_robot(&mut sim, 1, 20.0);
+        sim.robots.push(robot);
+
+        let station_energy = sim.station.resources.energy;
+        let rate = sim.station.energy_model.recharge_rate;
+
+        sim.step();
+        assert_eq!(sim.robots[0].energy, 20.0 + rate);
+        assert_eq!(sim.station.resources.energy, station_energy - rate as u32);
+        assert_eq!(sim.station.history.total_spent().energy, rate as u32);
+        assert!(matches!(sim.robots[0].state, State::Returning { .. }));
+
+        let ticks_to_full = ((100.0 - 20.0) / rate) as usize;
+        for _ in 1..ticks_to_full {
+            sim.step();
+        }
+        assert_eq!(sim.robots[0].energy, 100.0);
+        assert!(matches!(sim.robots[0].state, State::Idle));
+        assert_eq!(sim.station.docking.occupied_bays(), 0);
+    }
+
+    #[test]
+    fn test_unloading_takes_time() {
+        let mut sim = Simulation::new(50, 50, 42);
+        let mut robot = docked_robot(&mut sim, 1, 100.0);
+        let rate = sim.station.docking.config.unload_rate;
+        robot.inventory.minerals = rate * 2 + rate / 2;
+        sim.robots.push(robot);
+        let minerals = sim.station.resources.minerals;
+
+        sim.step();
+        sim.step();
+        assert_eq!(sim.robots[0].inventory.minerals, rate / 2);
+        assert_eq!(sim.station.resources.minerals, minerals + rate * 2);
+        assert_eq!(sim.station.history.total_collected().minerals, rate * 2);
+        assert!(matches!(sim.robots[0].state, State::Returning { .. }));
+
+        sim.step();
+        assert_eq!(sim.robots[0].inventory.minerals, 0);
+        assert!(matches!(sim.robots[0].state, State::Idle));
+    }
+
+    #[test]
+    fn test_docking_merges_robot_knowledge_into_station() {
+        let mut sim = Simulation::new(50, 50, 42);
+        let mut robot = docked_robot(&mut sim, 1, 100.0);
+        robot.modules = vec![HardwareModule::TerrainScanner {
+            efficiency: 0.9,
+            range: 10,
+        }];
+        robot.scan_surroundings(&sim.map);
+        let observed = robot.knowledge.explored_count();
+        assert_eq!(observed, 25);
+        assert_eq!(sim.station.knowledge.explored_count(), 0);
+
+        sim.robots.push(robot);
+        sim.step();
+
+        assert_eq!(sim.station.knowledge.explored_count(), observed);
+        assert_eq!(sim.robots[0].knowledge.explored_count(), observed);
+        assert_eq!(sim.station.knowledge.updates_history[0].robot_id, 1);
+    }
+
+    #[test]
+    fn test_collecting_robot_hands_cargo_to_returning_neighbour() {
+        let mut sim = Simulation::new(50, 50, 42);
+        let site = Position {
+            x: sim.station.position.x + 20,
+            y: sim.station.position.y + 20,
+        };
+        for y in site.y - 2..=site.y + 2 {
+            for x in site.x - 2..=site.x + 2 {
+                *sim.map.get_tile_mut(x, y).unwrap() = crate::map::Tile::Empty;
+            }
+        }
+        *sim.map.get_tile_mut(site.x, site.y).unwrap() =
+            crate::map::Tile::Mineral(crate::map::Mineral {
+                amount: 10000,
+                is_base: true,
+            });
+
+        let mut miner = Robot::new(
+            site,
+            vec![
+                HardwareModule::DeepDrill { mining_speed: 2.0 },
+                HardwareModule::CargoHold { capacity: 100 },
+            ],
+        );
+        miner.id = 1;
+        miner.inventory.minerals = 30;
+        miner.state = State::Collecting {
+            resource_type: crate::robot::resources::ResourceType::Minerals,
+            target: site,
+        };
+
+        let mut hauler = Robot::new(
+            Position {
+                x: site.x + 1,
+                y: site.y + 1,
+            },
+            vec![],
+        );
+        hauler.id = 2;
+        hauler.state = State::Returning {
+            base_position: sim.station.position,
+            path: Vec::new(),
+        };
+        sim.robots.push(miner);
+        sim.robots.push(hauler);
+
+        sim.step();
+
+        assert_eq!(sim.robots[1].inventory.minerals, BASE_CARGO_CAPACITY);
+        assert!(matches!(sim.robots[0].state, State::Collecting { .. }));
+    }
+
+    #[test]
+    fn test_manual_orders_send_robot_to_target() {
+        use crate::simulation::command::{CommandError, UserCommand};
+
+        let mut sim = Simulation::new(50, 50, 42);
+        let start = Position {
+            x: sim.station.position.x + 10,
+            y: sim.station.position.y + 10,
+        };
+        let site = Position {
+            x: start.x + 6,
+            y: start.y,
+        };
+        for y in start.y - 1..=start.y + 1 {
+            for x in start.x - 1..=site.x + 1 {
+                *sim.map.get_tile_mut(x, y).unwrap() = crate::map::Tile::Empty;
+            }
+        }
+        *sim.map.get_tile_mut(site.x, site.y).unwrap() =
+            crate::map::Tile::Mineral(crate::map::Mineral {
+                amount: 500,
+                is_base: false,
+            });
+        *sim.map.get_tile_mut(start.x, start.y + 1).unwrap() = crate::map::Tile::Obstacle;
+
+        let mut miner = Robot::new(start, vec![HardwareModule::DeepDrill { mining_speed: 2.0 }]);
+        miner.id = 1;
+        let mut explorer = Robot::new(start, vec![]);
+        explorer.id = 2;
+        sim.robots.push(miner);
+        sim.robots.push(explorer);
+
+        assert_eq!(
+            sim.apply(UserCommand::Harvest {
+                robot_id: 2,
+                target: site
+            }),
+            Err(CommandError::CannotHarvest {
+                robot_id: 2,
+                position: site
+            })
+        );
+        let wall = Position {
+            x: start.x,
+            y: start.y + 1,
+        };
+        assert_eq!(
+            sim.apply(UserCommand::GoTo {
+                robot_id: 2,
+                target: wall
+            }),
+            Err(CommandError::Unreachable(wall))
+        );
+
+        // Case libre mais entourée d'obstacles
+        let pocket = Position {
+            x: start.x + 3,
+            y: start.y - 4,
+        };
+        for y in pocket.y - 1..=pocket.y + 1 {
+            for x in pocket.x - 1..=pocket.x + 1 {
+                *sim.map.get_tile_mut(x, y).unwrap() = crate::map::Tile::Obstacle;
+            }
+        }
+        *sim.map.get_tile_mut(pocket.x, pocket.y).unwrap() = crate::map::Tile::Empty;
+        assert_eq!(
+            sim.apply(UserCommand::GoTo {
+                robot_id: 2,
+                target: pocket
+            }),
+            Err(CommandError::Unreachable(pocket))
+        );
+
+        sim.apply(UserCommand::Harvest {
+            robot_id: 1,
+            target: site,
+        })
+        .unwrap();
+        sim.apply(UserCommand::GoTo {
+            robot_id: 2,
+            target: site,
+        })
+        .unwrap();
+        while sim.robots[0].order.is_some() && sim.tick < 20 {
+            sim.step();
+        }
+        sim.step();
+
+        assert_eq!(sim.robots[0].position, site);
+        assert!(matches!(sim.robots[0].state, State::Collecting { .. }));
+        assert!(sim.robots[0].inventory.minerals > 0);
+        assert_eq!(sim.robots[1].order, None);
+        assert_ne!(sim.robots[1].state.name(), "exploring");
+    }
+
+    #[test]
+    fn test_robots_queue_when_bays_are_full() {
+        let mut sim = Simulation::new(50, 50, 42);
+        let bay_count = sim.station.docking.config.bay_count as u32;
+        for id in 1..=bay_count {
+            let robot = docked_robot(&mut sim, id, 10.0);
+            sim.robots.push(robot);
+        }
+
+        let waiting_position = Position {
+            x: sim.station.position.x + 2,
+            y: sim.station.position.y + 2,
+        };
+        let mut waiting = Robot::new(waiting_position, vec![]);
+        waiting.id = bay_count + 1;
+        waiting.state = State::Returning {
+            base_position: sim.station.position,
+            path: Vec::new(),
+        };
+        sim.robots.push(waiting);
+
+        sim.step();
+        assert_eq!(sim.station.docking.queue_len(), 1);
+        assert_eq!(sim.robots[bay_count as usize].position, waiting_position);
+
+        while sim.station.docking.queue_len() > 0 {
+            sim.step();
+        }
+        for _ in 0..APPROACH_RADIUS {
+            sim.step();
+        }
+        assert!(sim.robots[bay_count as usize].is_at_station(&sim.station));
+    }
+
+    #[test]
+    fn test_robot_running_dry_on_approach_frees_its_bay() {
+        let mut sim = Simulation::new(50, 50, 42);
+        let mut robot = Robot::new(
+            Position {
+                x: sim.station.position.x + 3,
+                y: sim.station.position.y,
+            },
+            vec![],
+        );
+        robot.id = 1;
+        robot.energy = 0.0;
+        robot.state = State::Returning {
+            base_position: sim.station.position,
+            path: Vec::new(),
+        };
+        sim.station.docking.request_bay(robot.id).unwrap();
+        sim.robots.push(robot);
+
+        sim.step();
+        assert_eq!(sim.station.docking.occupied_bays(), 0);
+        assert_eq!(sim.station.docking.queue_len(), 0);
+    }
+
+    #[test]
+    fn test_empty_station_lets_charged_enough_robot_leave() {
+        let mut sim = Simulation::new(50, 50, 42);
+        sim.station.resources.energy = 0;
+        let robot = docked_robot(&mut sim, 1, 50.0);
+        sim.robots.push(robot);
+
+        sim.step();
+        assert_eq!(sim.robots[0].energy, 50.0);
+        assert!(matches!(sim.robots[0].state, State::Idle));
+    }
+
+    #[test]
+    fn test_exploration_drains_energy_from_modules_and_moves() {
+        let mut sim = Simulation::new(50, 50, 42);
+        assert!(sim.create_robot(RobotType::Miner).is_ok());
+        let capacity = sim.robots[0].battery_capacity;
+        assert_eq!(sim.robots[0].energy, capacity);
+
+        for _ in 0..10 {
+            sim.step();
+        }
+        assert!(sim.robots[0].energy < capacity);
+    }
+
+    #[test]
+    fn test_robots_come_back_before_running_dry() {
+        for seed in [1, 4, 7] {
+            let mut sim = Simulation::new(40, 30, seed);
+            for robot_type in RobotType::ALL {
+                assert!(sim.create_robot(robot_type).is_ok());
+            }
+
+            for _ in 0..1500 {
+                sim.step();
+                let dry = sim.robots.iter().find(|robot| robot.energy <= 0.0);
+                assert!(
+                    dry.is_none(),
+                    "seed {seed}: robot #{} ran dry at tick {}",
+                    dry.map_or(0, |robot| robot.id),
+                    sim.tick
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_nearby_robots_gossip_without_the_station() {
+        let mut sim = Simulation::new(50, 50, 42);
+        let site = Position { x: 10, y: 30 };
+
+        // Batteries vides : les deux robots restent immobiles, à portée l'un de l'autre
+        let mut scout = Robot::new(
+            site,
+            vec![HardwareModule::TerrainScanner {
+                efficiency: 0.9,
+                range: 10,
+            }],
+        );
+        scout.id = 1;
+        scout.energy = 0.0;
+        scout.scan_surroundings(&sim.map);
+        let observed = scout.shared_map.len();
+
+        let mut peer = Robot::new(
+            Position {
+                x: site.x + 3,
+                y: site.y,
+            },
+            vec![],
+        );
+        peer.id = 2;
+        peer.energy = 0.0;
+        sim.robots.push(scout);
+        sim.robots.push(peer);
+
+        for _ in 0..GOSSIP_INTERVAL_TICKS {
+            sim.step();
+        }
+
+        assert!(sim.robots[1].shared_map.len() >= observed);
+        assert!(sim.robots[1].knowledge.explored_count() >= observed);
+        assert_eq!(sim.station.knowledge.explored_count(), 0);
+    }
+
+    #[test]
+    fn test_robot_lifecycle_is_logged() {
+        let mut sim = Simulation::new(50, 50, 42);
+        let events = sim.events.subscribe();
+        let robot = docked_robot(&mut sim, 1, 100.0);
+        sim.robots.push(robot);
+
+        sim.step();
+        let logged: Vec<_> = events.try_iter().map(|record| record.event).collect();
+        assert_eq!(
+            logged,
+            vec![
+                SimEvent::RobotDocked {
+                    position: sim.robots[0].position
+                },
+                SimEvent::StateChanged {
+                    from: StateKind::Returning,
+                    to: StateKind::Idle,
+                },
+            ]
+        );
+
+        sim.station.production_queue.enqueue(RobotType::Explorer);
+        while sim.robots.len() == 1 {
+            sim.step();
+        }
+        let built = sim.robots[1].id;
+        let records: Vec<_> = events.try_iter().collect();
+        let completed = SimEvent::ProductionCompleted {
+            robot_type: RobotType::Explorer,
+        };
+        assert!(records.iter().any(|record| record.robot_id == Some(built)
+            && matches!(record.event, SimEvent::RobotSpawned { .. })));
+        assert!(records
+            .iter()
+            .any(|record| record.tick == sim.tick && record.event == completed));
+    }
+
+    /// Robot en panne près de la station, perdu au bout du délai de silence
+    fn lost_robot(sim: &mut Simulation) -> u32 {
+        let mut robot = Robot::new(
+            Position {
+                x: sim.station.position.x + 2,
+                y: sim.station.position.y,
+            },
+            vec![],
+        );
+        robot.id = 100;
+        robot.energy = 0.0;
+        sim.station.fleet.register(&robot);
+        sim.robots.push(robot);
+
+        sim.create_robot(RobotType::Explorer).unwrap();
+        for _ in 0..sim.station.fleet.config.lost_after_ticks {
+            sim.step();
+        }
+        assert_eq!(sim.station.fleet.liveness(100), Some(Liveness::Lost));
+        100
+    }
+
+    #[test]
+    fn test_stranded_robot_is_rescued() {
+        let mut sim = Simulation::new(100, 100, 42);
+        let lost = lost_robot(&mut sim);
+
+        for _ in 0..200 {
+            if sim.station.fleet.liveness(lost) == Some(Liveness::Alive) {
+                break;
+            }
+            sim.step();
+        }
+        assert_eq!(sim.station.fleet.liveness(lost), Some(Liveness::Alive));
+        assert!(sim.station.fleet.recoveries().is_empty());
+        assert!(sim.robots[0].energy > 0.0);
+    }
+
+    #[test]
+    fn test_search_widens_until_the_robot_is_found() {
+        let mut sim = Simulation::new(100, 100, 42);
+        let lost = lost_robot(&mut sim);
+        sim.robots[0].position.y += 2 * RESCUE_SEARCH_RADIUS;
+
+        for _ in 0..500 {
+            if sim.station.fleet.liveness(lost) == Some(Liveness::Alive) {
+                break;
+            }
+            sim.step();
+        }
+        assert_eq!(sim.station.fleet.liveness(lost), Some(Liveness::Alive));
+        assert!(sim.robots[0].energy > 0.0);
+    }
+
+    #[test]
+    fn test_unfound_robot_stays_registered_as_lost() {
+        let mut sim = Simulation::new(100, 100, 42);
+        let lost = lost_robot(&mut sim);
+        sim.robots[0].position.x += 8 * RESCUE_SEARCH_RADIUS;
+
+        for _ in 0..2000 {
+            if sim.station.fleet.recoveries().is_empty() {
+                break;
+            }
+            sim.step();
+        }
+        assert!(sim.station.fleet.recoveries().is_empty());
+        assert_eq!(sim.station.fleet.liveness(lost), Some(Liveness::Lost));
+        assert_eq!(sim.robots[0].energy, 0.0);
+    }
+
+    #[test]
+    fn test_robot_running_dry_is_reported_stranded() {
+        let mut sim = Simulation::new(100, 100, 42);
+        let robot_id = sim.create_robot(RobotType::Explorer).unwrap();
+        let far = Position {
+            x: sim.station.position.x + 20,
+            y: sim.station.position.y,
+        };
+        *sim.map.get_tile_mut(far.x, far.y).unwrap() = crate::map::Tile::Empty;
+        sim.robots[0].position = far;
+        sim.robots[0].energy = 0.5;
+
+        while sim.station.fleet.liveness(robot_id) != Some(Liveness::Lost) {
+            sim.step();
+            assert!(sim.tick < 500);
+        }
+        assert_eq!(sim.station.fleet.get(robot_id).unwrap().energy, 0.0);
+        let task = &sim.station.fleet.recoveries()[0];
+        assert_eq!(task.robot_id, robot_id);
+        assert!(task.stranded);
+    }
+}
@@ -0,0 +1,475 @@
+use std::collections::{BTreeMap, HashMap};
+
+use crossbeam::channel::{self, Receiver, Sender};
+
+use crate::map::Map;
+use crate::robot::communication::RobotCommunication;
+use crate::robot::Robot;
+use crate::simulation::events::{EventLog, LinkDirection, SimEvent};
+use crate::simulation::radio::RadioConfig;
+use crate::simulation::transport::{FaultConfig, FaultyLink, TransportStats};
+use crate::robot::Position;
+use crate::station::communication::{
+    RobotMessage, RobotPacket, StationCommunication, StationMessage,
+};
+use crate::station::fleet::FleetEvent;
+use crate::station::Station;
+
+/// Point d'accès radio d'un robot. Les messages de la station passent par le
+/// lien descendant avant d'atteindre le canal du robot.
+struct Endpoint {
+    robot: RobotCommunication,
+    /// Conservé pour réinscrire le robot auprès de la station s'il est retrouvé
+    station_sender: Sender<StationMessage>,
+    station_outbox: Receiver<StationMessage>,
+    robot_inbox: Sender<StationMessage>,
+}
+
+/// Réseau radio entre la station et les robots. Seuls les robots en liaison
+/// (directe ou relayée) peuvent émettre ; les autres conservent leurs messages
+/// dans leur file d'envoi jusqu'au retour du contact. Les messages transitent
+/// par des liens à défaillances simulées, dans les deux sens ; les rapports
+/// perdus sont réémis tant que la station ne les a pas acquittés. La station
+/// cesse d'écrire aux robots perdus jusqu'à ce qu'ils donnent signe de vie.
+pub struct Network {
+    pub radio: RadioConfig,
+    station: StationCommunication,
+    uplink_sender: Sender<RobotPacket>,
+    uplink_receiver: Receiver<RobotPacket>,
+    station_inbox: Sender<RobotPacket>,
+    /// Parcourus dans l'ordre des identifiants : les tirages des liens en dépendent
+    endpoints: BTreeMap<u32, Endpoint>,
+    uplink: FaultyLink<RobotPacket>,
+    downlink: FaultyLink<(u32, StationMessage)>,
+    emitted: u64,
+    retransmitted: u64,
+}
+
+impl Network {
+    pub fn new(radio: RadioConfig, faults: FaultConfig, seed: u64) -> Self {
+        let (uplink_sender, uplink_receiver) = channel::unbounded();
+        let (station_inbox, station_receiver) = channel::unbounded();
+        Self {
+            radio,
+            station: StationCommunication::new(station_receiver),
+            uplink_sender,
+            uplink_receiver,
+            station_inbox,
+            endpoints: BTreeMap::new(),
+            uplink: FaultyLink::new(faults, seed),
+            downlink: FaultyLink::new(faults, seed.wrapping_add(1)),
+            emitted: 0,
+            retransmitted: 0,
+        }
+    }
+
+    /// Change les défaillances injectées sur les deux liens
+    pub fn set_faults(&mut self, faults: FaultConfig) {
+        self.uplink.config = faults;
+        self.downlink.config = faults;
+    }
+
+    pub fn faults(&self) -> FaultConfig {
+        self.uplink.config
+    }
+
+    /// Met à jour les liaisons puis échange les messages des robots connectés.
+    /// Les messages perdus en route sont publiés dans le journal.
+    pub fn exchange(
+        &mut self,
+        now: u64,
+        map: &mut Map,
+        station: &mut Station,
+        robots: &mut [Robot],
+        events: &mut EventLog,
+    ) {
+        let links = self.radio.compute_links(map, station.position, robots);
+
+        for (robot, link) in robots.iter_mut().zip(links) {
+            robot.link = link;
+            if !link.is_connected() {
+                continue;
+            }
+            if robot.outbox.is_empty() && !self.endpoints.contains_key(&robot.id) {
+                continue;
+            }
+
+            self.emitted += robot.outbox.len() as u64;
+            let endpoint = self.endpoint(robot.id);
+            // L'horloge avance avant l'envoi des nouveaux messages
+            let retransmitted = endpoint.robot.retransmit(now);
+            for message in robot.outbox.drain(..) {
+                endpoint.robot.send(message);
+            }
+            self.retransmitted += retransmitted as u64;
+        }
+
+        while let Ok(packet) = self.uplink_receiver.try_recv() {
+            let (robot_id, message_id) = (packet.robot_id, packet.message_id);
+            if !self.uplink.send(now, packet) {
+                events.publish(
+                    now,
+                    Some(robot_id),
+                    SimEvent::MessageDropped {
+                        direction: LinkDirection::Uplink,
+                        message_id: Some(message_id),
+                    },
+                );
+            }
+        }
+        for packet in self.uplink.deliver(now) {
+            let _ = self.station_inbox.send(packet);
+        }
+
+        for (robot_id, message) in self.station.process_messages(&mut station.knowledge) {
+            // Un robot dont la station avait abandonné la recherche est réinscrit
+            let forgotten = station.fleet.get(robot_id).is_none();
+            let event = station.fleet.observe_message(robot_id, now, &message);
+            let reinstated = forgotten && station.fleet.get(robot_id).is_some();
+            if reinstated || event == Some(FleetEvent::Recovered(robot_id)) {
+                if let Some(endpoint) = self.endpoints.get(&robot_id) {
+                    self.station
+                        .register_robot(robot_id, endpoint.station_sender.clone());
+                }
+            }
+            if let RobotMessage::ResourceDiscovered {
+                resource_type,
+                position,
+                ..
+            } = message
+            {
+                station.report_resource_found(resource_type, position);
+            }
+        }
+        for event in station.fleet.check(now) {
+            if let FleetEvent::Lost(robot_id) = event {
+                self.station.unregister_robot(robot_id);
+            }
+        }
+
+        for (robot_id, endpoint) in &self.endpoints {
+            while let Ok(message) = endpoint.station_outbox.try_recv() {
+                if !self.downlink.send(now, (*robot_id, message)) {
+                    events.publish(
+                        now,
+                        Some(*robot_id),
+                        SimEvent::MessageDropped {
+                            direction: LinkDirection::Downlink,
+                            message_id: None,
+                        },
+                    );
+                }
+            }
+        }
+        for (robot_id, message) in self.downlink.deliver(now) {
+            if let Some(endpoint) = self.endpoints.get(&robot_id) {
+                let _ = endpoint.robot_inbox.send(message);
+            }
+        }
+
+        // Les réponses de la station attendent dans le canal d'un robot hors de portée
+        for robot in robots.iter_mut().filter(|robot| robot.link.is_connected()) {
+            if let Some(endpoint) = self.endpoints.get_mut(&robot.id) {
+                endpoint.robot.process_station_messages(&mut robot.knowledge);
+            }
+        }
+    }
+
+    /// Nombre total de messages émis par les robots
+    pub fn emitted(&self) -> u64 {
+        self.emitted
+    }
+
+    /// Nombre total de réémissions de rapports non acquittés
+    pub fn retransmitted(&self) -> u64 {
+        self.retransmitted
+    }
+
+    /// Nombre de rapports en attente d'acquittement, tous robots confondus
+    pub fn unacked(&self) -> usize {
+        self.endpoints
+            .values()
+            .map(|endpoint| endpoint.robot.unacked_count())
+            .sum()
+    }
+
+    /// Tables des gisements tenues par la station d'après les rapports reçus
+    pub fn station_resources(
+        &self,
+    ) -> (
+        HashMap<Position, u32>,
+        HashMap<Position, u32>,
+        HashMap<Position, u32>,
+    ) {
+        self.station.get_resources_state()
+    }
+
+    /// Statistiques des liens montant (robots vers station) et descendant
+    pub fn transport_stats(&self) -> (TransportStats, TransportStats) {
+        (self.uplink.stats(), self.downlink.stats())
+    }
+
+    fn endpoint(&mut self, robot_id: u32) -> &mut Endpoint {
+        let station = &mut self.station;
+        let uplink_sender = &self.uplink_sender;
+        self.endpoints.entry(robot_id).or_insert_with(|| {
+            let (station_sender, station_outbox) = channel::unbounded();
+            let (robot_inbox, robot_receiver) = channel::unbounded();
+            station.register_robot(robot_id, station_sender.clone());
+            Endpoint {
+                robot: RobotCommunication::new(robot_id, uplink_sender.clone(), robot_receiver),
+                station_sender,
+                station_outbox,
+                robot_inbox,
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::map::{Mineral, Tile};
+    use crate::robot::communication::LinkStatus;
+    use crate::robot::resources::ResourceType;
+    use crate::robot::{Position, Robot};
+    use crate::simulation::events::{LinkDirection, SimEvent};
+    use crate::simulation::transport::FaultConfig;
+    use crate::simulation::Simulation;
+    use crate::station::communication::RobotMessage;
+    use crate::station::conflict::Observer;
+    use crate::station::fleet::{Liveness, HEARTBEAT_INTERVAL_TICKS};
+
+    /// Robot immobile (batterie vide) à côté de la station, donc toujours en liaison
+    fn stationary_robot(sim: &Simulation, id: u32) -> Robot {
+        let mut robot = Robot::new(
+            Position {
+                x: sim.station.position.x + 2,
+                y: sim.station.position.y,
+            },
+            vec![],
+        );
+        robot.id = id;
+        robot.energy = 0.0;
+        robot
+    }
+
+    #[test]
+    fn test_messages_wait_for_radio_contact() {
+        let mut sim = Simulation::new(100, 100, 42);
+        sim.network.radio.station_range = 10;
+        let station = sim.station.position;
+        let far = Position {
+            x: (station.x + 40).min(99),
+            y: station.y,
+        };
+        let discovery = Position { x: 1, y: 1 };
+
+        // Batterie vide : le robot reste hors de portée
+        let mut robot = Robot::new(far, vec![]);
+        robot.id = 1;
+        robot.energy = 0.0;
+        robot.outbox.push_back(RobotMessage::ResourceDiscovered {
+            resource_type: ResourceType::Minerals,
+            position: discovery,
+            amount: 100,
+            confidence: 1.0,
+        });
+        sim.robots.push(robot);
+
+        sim.step();
+        assert_eq!(sim.robots[0].link, LinkStatus::NoSignal);
+        assert_eq!(sim.robots[0].outbox.len(), 1);
+        assert_eq!(sim.station.get_discovered_resource_counts().1, 0);
+
+        sim.robots[0].position = Position {
+            x: station.x + 2,
+            y: station.y,
+        };
+        sim.step();
+        assert!(sim.robots[0].link.is_connected());
+        assert!(sim.robots[0].outbox.is_empty());
+        assert_eq!(sim.station.get_discovered_resource_counts().1, 1);
+        assert_eq!(sim.network.emitted(), 1);
+    }
+
+    #[test]
+    fn test_discoveries_survive_adversarial_network() {
+        let mut sim = Simulation::new(100, 100, 42);
+        sim.network.set_faults(FaultConfig::adversarial());
+        let robot = stationary_robot(&sim, 1);
+        sim.robots.push(robot);
+        let events = sim.events.subscribe();
+
+        let discoveries: Vec<Position> = (0..10).map(|i| Position { x: i, y: 3 }).collect();
+        for _ in 0..5 {
+            for position in &discoveries {
+                sim.robots[0].outbox.push_back(RobotMessage::ResourceDiscovered {
+                    resource_type: ResourceType::Minerals,
+                    position: *position,
+                    amount: 100,
+                    confidence: 1.0,
+                });
+            }
+            sim.step();
+        }
+        for _ in 0..20 {
+            sim.step();
+        }
+
+        let (uplink, downlink) = sim.network.transport_stats();
+        assert!(uplink.dropped > 0 && uplink.duplicated > 0 && uplink.reordered > 0);
+        assert!(downlink.delivered > 0);
+
+        // Chaque perte est journalisée
+        let drops: Vec<LinkDirection> = events
+            .try_iter()
+            .filter_map(|record| match record.event {
+                SimEvent::MessageDropped { direction, .. } => Some(direction),
+                _ => None,
+            })
+            .collect();
+        let dropped = |direction| drops.iter().filter(|&&d| d == direction).count() as u64;
+        assert_eq!(dropped(LinkDirection::Uplink), uplink.dropped);
+        assert_eq!(dropped(LinkDirection::Downlink), downlink.dropped);
+
+        // Les doublons et le désordre ne créent pas de découvertes fantômes
+        let mut known = sim.station.discovered_resources.mineral_locations.clone();
+        assert_eq!(known.len(), discoveries.len());
+        known.sort_by_key(|position| position.x);
+        assert_eq!(known, discoveries);
+    }
+
+    #[test]
+    fn test_map_sync_converges_under_adversarial_network() {
+        let mut sim = Simulation::new(100, 100, 42);
+        sim.network.set_faults(FaultConfig::adversarial());
+        let robot = stationary_robot(&sim, 1);
+        sim.robots.push(robot);
+
+        for tick in 0..60u32 {
+            let robot = &mut sim.robots[0];
+            if tick < 20 {
+                let observer = Observer {
+                    robot_id: 1,
+                    tick: tick as u64,
+                    sensor_efficiency: 0.5,
+                };
+                robot
+                    .knowledge
+                    .observe_tile(Position { x: tick, y: 50 }, &Tile::Empty, observer);
+            }
+
+            // Renvoi complet à chaque tick : les pertes sont compensées par la répétition
+            let version_before = robot.knowledge.version;
+            robot.outbox.push_back(RobotMessage::MapSync {
+                last_seen_version: robot.knowledge.version,
+                tiles: robot
+                    .knowledge
+                    .explored_tiles
+                    .iter()
+                    .map(|(key, tile)| (*key, tile.clone()))
+                    .collect(),
+            });
+            sim.step();
+
+            let robot = &sim.robots[0];
+            assert!(robot.knowledge.version >= version_before);
+            assert!(robot.knowledge.version <= sim.station.knowledge.version);
+        }
+
+        assert_eq!(sim.station.knowledge.explored_count(), 20);
+        assert_eq!(sim.robots[0].knowledge.explored_count(), 20);
+        assert_eq!(
+            sim.robots[0].knowledge.version,
+            sim.station.knowledge.version
+        );
+    }
+
+    #[test]
+    fn test_lost_consumption_reports_do_not_corrupt_accounting() {
+        let mut sim = Simulation::new(100, 100, 42);
+        sim.network.set_faults(FaultConfig::adversarial());
+        let robot = stationary_robot(&sim, 1);
+        sim.robots.push(robot);
+
+        let deposit = Position { x: 5, y: 50 };
+        *sim.map.get_tile_mut(deposit.x, deposit.y).unwrap() = Tile::Mineral(Mineral {
+            amount: 500,
+            is_base: false,
+        });
+        sim.robots[0].outbox.push_back(RobotMessage::ResourceDiscovered {
+            resource_type: ResourceType::Minerals,
+            position: deposit,
+            amount: 500,
+            confidence: 1.0,
+        });
+        while sim.network.unacked() > 0 || !sim.robots[0].outbox.is_empty() {
+            sim.step();
+        }
+
+        for _ in 0..10 {
+            sim.map.consume_mineral(deposit.x, deposit.y, 10);
+            sim.robots[0].outbox.push_back(RobotMessage::ResourceConsumed {
+                resource_type: ResourceType::Minerals,
+                position: deposit,
+                amount: 10,
+                robot_id: 1,
+            });
+            sim.step();
+        }
+        for _ in 0..150 {
+            sim.step();
+        }
+
+        let (uplink, _) = sim.network.transport_stats();
+        assert!(uplink.dropped > 0 && uplink.duplicated > 0);
+        assert!(sim.network.retransmitted() > 0);
+        assert_eq!(sim.network.unacked(), 0);
+
+        // Ni perte ni double décompte : la station retrouve la quantité restante
+        let (_, minerals, _) = sim.network.station_resources();
+        assert_eq!(minerals.get(&deposit), Some(&400));
+        assert_eq!(
+            sim.map.get_tile(deposit.x, deposit.y),
+            Some(&Tile::Mineral(Mineral {
+                amount: 400,
+                is_base: false,
+            }))
+        );
+    }
+
+    #[test]
+    fn test_silent_robot_is_lost_then_recovered() {
+        let mut sim = Simulation::new(100, 100, 42);
+        let robot = stationary_robot(&sim, 1);
+        sim.station.fleet.register(&robot);
+        sim.robots.push(robot);
+
+        // Sans énergie, le robot n'émet plus de signal de vie
+        for _ in 0..sim.station.fleet.config.lost_after_ticks {
+            sim.step();
+        }
+        assert_eq!(sim.station.fleet.liveness(1), Some(Liveness::Lost));
+        let recovery = &sim.station.fleet.recoveries()[0];
+        assert!(recovery.stranded);
+        assert_eq!(recovery.last_known_position, sim.robots[0].position);
+
+        sim.robots[0].energy = 50.0;
+        sim.robots[0].outbox.push_back(RobotMessage::ResourceDiscovered {
+            resource_type: ResourceType::Minerals,
+            position: Position { x: 3, y: 3 },
+            amount: 100,
+            confidence: 1.0,
+        });
+        for _ in 0..HEARTBEAT_INTERVAL_TICKS {
+            sim.step();
+        }
+
+        assert_eq!(sim.station.fleet.liveness(1), Some(Liveness::Alive));
+        assert!(sim.station.fleet.recoveries().is_empty());
+        assert_eq!(sim.network.unacked(), 0);
+        let entry = sim.station.fleet.get(1).unwrap();
+        assert!(entry.last_heartbeat.is_some());
+        assert_eq!(entry.position, sim.robots[0].position);
+    }
+}
@@ -0,0 +1,185 @@
+use std::collections::VecDeque;
+
+use serde::{Deserialize, Serialize};
+
+use crate::map::Map;
+use crate::robot::communication::LinkStatus;
+use crate::robot::{HardwareModule, Position, Robot};
+
+/// Paramètres de propagation radio
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct RadioConfig {
+    /// Portée de l'antenne de la station, en cases
+    pub station_range: u32,
+    /// Portée perdue pour chaque obstacle traversé par le signal
+    pub obstacle_attenuation: u32,
+}
+
+impl Default for RadioConfig {
+    fn default() -> Self {
+        Self {
+            station_range: 30,
+            obstacle_attenuation: 4,
+        }
+    }
+}
+
+impl RadioConfig {
+    /// Vrai si un émetteur de portée `range` atteint `to` depuis `from`,
+    /// chaque obstacle sur la ligne de visée réduisant la portée
+    pub fn signal_reaches(&self, map: &Map, from: Position, to: Position, range: u32) -> bool {
+        let distance = from.distance_to(&to);
+        if distance > range {
+            return false;
+        }
+
+        let attenuation = obstacles_between(map, from, to) * self.obstacle_attenuation;
+        distance <= range.saturating_sub(attenuation)
+    }
+
+    /// Calcule la liaison de chaque robot : directe avec la station, ou relayée
+    /// de proche en proche par des robots équipés d'un relais déjà connectés
+    pub fn compute_links(&self, map: &Map, station: Position, robots: &[Robot]) -> Vec<LinkStatus> {
+        let mut links = vec![LinkStatus::NoSignal; robots.len()];
+        let mut relays = VecDeque::new();
+
+        for (i, robot) in robots.iter().enumerate() {
+            if self.signal_reaches(map, station, robot.position, self.station_range) {
+                links[i] = LinkStatus::Direct;
+                if relay_range(robot).is_some() {
+                    relays.push_back(i);
+                }
+            }
+        }
+
+        while let Some(relay) = relays.pop_front() {
+            let Some(range) = relay_range(&robots[relay]) else {
+                continue;
+            };
+            for (i, robot) in robots.iter().enumerate() {
+                if links[i].is_connected() {
+                    continue;
+                }
+                if self.signal_reaches(map, robots[relay].position, robot.position, range) {
+                    links[i] = LinkStatus::Relayed {
+                        via: robots[relay].id,
+                    };
+                    if relay_range(robot).is_some() {
+                        relays.push_back(i);
+                    }
+                }
+            }
+        }
+
+        links
+    }
+}
+
+fn relay_range(robot: &Robot) -> Option<u32> {
+    robot
+        .modules
+        .iter()
+        .filter_map(|module| match module {
+            HardwareModule::SignalRelay { range } => Some(*range),
+            _ => None,
+        })
+        .max()
+}
+
+/// Nombre d'obstacles sur le segment entre deux positions (extrémités exclues)
+fn obstacles_between(map: &Map, from: Position, to: Position) -> u32 {
+    let (mut x, mut y) = (from.x as i64, from.y as i64);
+    let (x1, y1) = (to.x as i64, to.y as i64);
+    let dx = (x1 - x).abs();
+    let dy = -(y1 - y).abs();
+    let sx = if x < x1 { 1 } else { -1 };
+    let sy = if y < y1 { 1 } else { -1 };
+    let mut error = dx + dy;
+    let mut obstacles = 0;
+
+    loop {
+        if (x, y) == (x1, y1) {
+            break;
+        }
+        let doubled = 2 * error;
+        if doubled >= dy {
+            error += dy;
+            x += sx;
+        }
+        if doubled <= dx {
+            error += dx;
+            y += sy;
+        }
+        if (x, y) != (x1, y1) && map.is_obstacle(x as u32, y as u32) {
+            obstacles += 1;
+        }
+    }
+
+    obstacles
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::Tile;
+
+    fn open_map(width: u32, height: u32) -> Map {
+        let mut map = Map::new(width, height, 42);
+        for y in 0..height {
+            for x in 0..width {
+                *map.get_tile_mut(x, y).unwrap() = Tile::Empty;
+            }
+        }
+        map
+    }
+
+    fn robot_at(id: u32, x: u32, y: u32, modules: Vec<HardwareModule>) -> Robot {
+        let mut robot = Robot::new(Position { x, y }, modules);
+        robot.id = id;
+        robot
+    }
+
+    #[test]
+    fn test_obstacles_attenuate_signal() {
+        let mut map = open_map(40, 10);
+        let config = RadioConfig {
+            station_range: 20,
+            obstacle_attenuation: 5,
+        };
+        let station = Position { x: 0, y: 5 };
+        let robot = Position { x: 18, y: 5 };
+        assert!(config.signal_reaches(&map, station, robot, config.station_range));
+
+        *map.get_tile_mut(10, 5).unwrap() = Tile::Obstacle;
+        assert_eq!(obstacles_between(&map, station, robot), 1);
+        assert!(!config.signal_reaches(&map, station, robot, config.station_range));
+        assert!(config.signal_reaches(
+            &map,
+            station,
+            Position { x: 15, y: 5 },
+            config.station_range
+        ));
+    }
+
+    #[test]
+    fn test_relays_extend_coverage() {
+        let map = open_map(100, 10);
+        let config = RadioConfig {
+            station_range: 20,
+            obstacle_attenuation: 5,
+        };
+        let robots = vec![
+            robot_at(1, 15, 5, vec![HardwareModule::SignalRelay { range: 20 }]),
+            robot_at(2, 30, 5, vec![HardwareModule::SignalRelay { range: 20 }]),
+            robot_at(3, 45, 5, vec![]),
+            robot_at(4, 90, 5, vec![]),
+        ];
+
+        let links = config.compute_links(&map, Position { x: 0, y: 5 }, &robots);
+
+        assert_eq!(links[0], LinkStatus::Direct);
+        assert_eq!(links[1], LinkStatus::Relayed { via: 1 });
+        assert_eq!(links[2], LinkStatus::Relayed { via: 2 });
+        assert_eq!(links[3], LinkStatus::NoSignal);
+    }
+}
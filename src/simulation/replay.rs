@@ -0,0 +1,392 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs::File;
+use std::hash::{Hash, Hasher};
+use std::io::{self, BufRead, BufReader, LineWriter, Write};
+use std::path::Path;
+
+use serde::{Deserialize, Serialize};
+
+use crate::simulation::command::UserCommand;
+use crate::simulation::{SimConfig, Simulation};
+
+/// Ligne d'un fichier de rejeu, au format JSON lines. La configuration vient
+/// en premier ; les commandes sont datées du nombre de ticks écoulés quand
+/// elles ont été appliquées, les empreintes du tick qu'elles décrivent.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum ReplayEntry {
+    Config(SimConfig),
+    Command { tick: u64, command: UserCommand },
+    Hash { tick: u64, hash: u64 },
+}
+
+#[derive(Debug)]
+pub enum ReplayError {
+    Io(io::Error),
+    Parse { line: usize, error: serde_json::Error },
+    MissingConfig,
+}
+
+impl fmt::Display for ReplayError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ReplayError::Io(error) => write!(f, "replay file error: {error}"),
+            ReplayError::Parse { line, error } => write!(f, "invalid replay line {line}: {error}"),
+            ReplayError::MissingConfig => write!(f, "replay file does not start with a config"),
+        }
+    }
+}
+
+impl std::error::Error for ReplayError {}
+
+impl From<io::Error> for ReplayError {
+    fn from(error: io::Error) -> Self {
+        ReplayError::Io(error)
+    }
+}
+
+/// Premier tick dont l'empreinte diffère de l'enregistrement
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Divergence {
+    pub tick: u64,
+    pub expected: u64,
+    pub actual: u64,
+}
+
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "replay diverged at tick {} (expected {:016x}, got {:016x})",
+            self.tick, self.expected, self.actual
+        )
+    }
+}
+
+/// Écrit la configuration, les commandes et les empreintes d'une partie
+pub struct ReplayRecorder {
+    writer: Box<dyn Write + Send>,
+}
+
+impl ReplayRecorder {
+    pub fn create(path: &Path, config: &SimConfig) -> io::Result<Self> {
+        Self::new(LineWriter::new(File::create(path)?), config)
+    }
+
+    pub fn new(writer: impl Write + Send + 'static, config: &SimConfig) -> io::Result<Self> {
+        let mut recorder = Self {
+            writer: Box::new(writer),
+        };
+        recorder.write(&ReplayEntry::Config(*config))?;
+        Ok(recorder)
+    }
+
+    pub fn record_command(&mut self, tick: u64, command: &UserCommand) {
+        // Un enregistrement incomplet ne doit pas interrompre la simulation
+        let _ = self.write(&ReplayEntry::Command {
+            tick,
+            command: command.clone(),
+        });
+    }
+
+    pub fn record_hash(&mut self, tick: u64, hash: u64) {
+        let _ = self.write(&ReplayEntry::Hash { tick, hash });
+    }
+
+    fn write(&mut self, entry: &ReplayEntry) -> io::Result<()> {
+        serde_json::to_writer(&mut self.writer, entry)?;
+        self.writer.write_all(b"\n")
+    }
+}
+
+/// Partie enregistrée, prête à être rejouée
+#[derive(Debug, Clone, PartialEq)]
+pub struct Replay {
+    pub config: SimConfig,
+    commands: Vec<(u64, UserCommand)>,
+    hashes: BTreeMap<u64, u64>,
+}
+
+impl Replay {
+    pub fn load(path: &Path) -> Result<Self, ReplayError> {
+        Self::read(BufReader::new(File::open(path)?))
+    }
+
+    pub fn read(reader: impl BufRead) -> Result<Self, ReplayError> {
+        let mut config = None;
+        let mut commands = Vec::new();
+        let mut hashes = BTreeMap::new();
+
+        for (index, line) in reader.lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+            let entry = serde_json::from_str(&line).map_err(|error| ReplayError::Parse {
+                line: index + 1,
+                error,
+            })?;
+            match entry {
+                ReplayEntry::Config(recorded) if config.is_none() => config = Some(recorded),
+                ReplayEntry::Config(_) => {}
+                ReplayEntry::Command { tick, command } => commands.push((tick, command)),
+                ReplayEntry::Hash { tick, hash } => {
+                    hashes.insert(tick, hash);
+                }
+            }
+        }
+
+        Ok(Self {
+            config: config.ok_or(ReplayError::MissingConfig)?,
+            commands,
+            hashes,
+        })
+    }
+
+    /// Dernier tick enregistré
+    pub fn last_tick(&self) -> u64 {
+        let last_command = self.commands.last().map_or(0, |(tick, _)| *tick);
+        let last_hash = self.hashes.keys().next_back().copied().unwrap_or(0);
+        last_command.max(last_hash)
+    }
+
+    pub fn command_count(&self) -> usize {
+        self.commands.len()
+    }
+}
+
+/// Rejoue une partie tick par tick en vérifiant l'empreinte de chaque tick
+pub struct ReplayPlayer {
+    replay: Replay,
+    next_command: usize,
+    divergence: Option<Divergence>,
+}
+
+impl ReplayPlayer {
+    pub fn new(replay: Replay) -> Self {
+        Self {
+            replay,
+            next_command: 0,
+            divergence: None,
+        }
+    }
+
+    /// Simulation de départ de la partie enregistrée
+    pub fn simulation(&self) -> Simulation {
+        Simulation::with_config(&self.replay.config)
+    }
+
+    pub fn replay(&self) -> &Replay {
+        &self.replay
+    }
+
+    pub fn is_finished(&self, sim: &Simulation) -> bool {
+        sim.tick >= self.replay.last_tick()
+    }
+
+    /// Première divergence constatée
+    pub fn divergence(&self) -> Option<Divergence> {
+        self.divergence
+    }
+
+    /// Applique les commandes dues puis avance la simulation d'un tick
+    pub fn step(&mut self, sim: &mut Simulation) -> Result<(), Divergence> {
+        while let Some((tick, command)) = self.replay.commands.get(self.next_command) {
+            if *tick > sim.tick {
+                break;
+            }
+            // Les refus se reproduisent à l'identique : ils ne sont pas des divergences
+            let _ = sim.apply(command.clone());
+            self.next_command += 1;
+        }
+
+        sim.step();
+        let Some(&expected) = self.replay.hashes.get(&sim.tick) else {
+            return Ok(());
+        };
+        let actual = sim.state_hash();
+        if actual == expected {
+            return Ok(());
+        }
+
+        let divergence = Divergence {
+            tick: sim.tick,
+            expected,
+            actual,
+        };
+        self.divergence.get_or_insert(divergence);
+        Err(divergence)
+    }
+
+    /// Rejoue jusqu'au dernier tick enregistré ou jusqu'à la première divergence
+    pub fn run(&mut self, sim: &mut Simulation) -> Result<(), Divergence> {
+        while !self.is_finished(sim) {
+            self.step(sim)?;
+        }
+        Ok(())
+    }
+}
+
+/// FNV-1a 64 bits : contrairement au hacheur de la bibliothèque standard,
+/// son résultat est garanti identique d'une version de Rust à l'autre
+struct StateHasher(u64);
+
+impl Hasher for StateHasher {
+    fn finish(&self) -> u64 {
+        self.0
+    }
+
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0000_0100_0000_01b3);
+        }
+    }
+}
+
+impl Simulation {
+    /// Empreinte de l'état observable de la simulation, pour détecter une divergence
+    pub fn state_hash(&self) -> u64 {
+        let mut hasher = StateHasher(0xcbf2_9ce4_8422_2325);
+        self.tick.hash(&mut hasher);
+        self.map.seed.hash(&mut hasher);
+        self.map.calculate_total_resources().hash(&mut hasher);
+
+        let resources = &self.station.resources;
+        (resources.energy, resources.minerals, resources.scientific_data).hash(&mut hasher);
+        self.station.knowledge.version.hash(&mut hasher);
+        self.station.get_discovered_resource_counts().hash(&mut hasher);
+        self.station.production_queue.queue_size().hash(&mut hasher);
+
+        for robot in &self.robots {
+            robot.id.hash(&mut hasher);
+            robot.position.hash(&mut hasher);
+            robot.energy.to_bits().hash(&mut hasher);
+            robot.battery_capacity.to_bits().hash(&mut hasher);
+            robot.state.name().hash(&mut hasher);
+            robot.order.hash(&mut hasher);
+            let inventory = &robot.inventory;
+            (inventory.energy, inventory.minerals, inventory.scientific_data).hash(&mut hasher);
+            robot.modules.len().hash(&mut hasher);
+            robot.outbox.len().hash(&mut hasher);
+        }
+        hasher.finish()
+    }
+
+    /// Enregistre la suite de la partie. À démarrer avant le premier tick pour
+    /// que le rejeu parte du même état.
+    pub fn start_recording(&mut self, recorder: ReplayRecorder) {
+        self.recorder = Some(recorder);
+        let hash = self.state_hash();
+        if let Some(recorder) = self.recorder.as_mut() {
+            recorder.record_hash(self.tick, hash);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::station::RobotType;
+    use std::sync::{Arc, Mutex};
+
+    /// Tampon partagé entre l'enregistreur et le test
+    #[derive(Clone, Default)]
+    struct SharedBuffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for SharedBuffer {
+        fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().write(buf)
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    fn record_session(config: &SimConfig) -> (Replay, Vec<u64>) {
+        let buffer = SharedBuffer::default();
+        let mut sim = Simulation::with_config(config);
+        sim.start_recording(ReplayRecorder::new(buffer.clone(), config).unwrap());
+
+        let mut hashes = Vec::new();
+        for tick in 0..120 {
+            match tick {
+                0 | 10 => {
+                    let _ = sim.apply(UserCommand::CreateRobot(RobotType::Explorer));
+                }
+                30 => {
+                    let _ = sim.apply(UserCommand::CreateRobot(RobotType::Miner));
+                }
+                60 => {
+                    let _ = sim.apply(UserCommand::ToggleService { robot_id: 1 });
+                }
+                _ => {}
+            }
+            sim.step();
+            hashes.push(sim.state_hash());
+        }
+
+        let bytes = buffer.0.lock().unwrap().clone();
+        (Replay::read(bytes.as_slice()).unwrap(), hashes)
+    }
+
+    #[test]
+    fn test_recorded_session_replays_identically() {
+        let config = SimConfig {
+            width: 80,
+            height: 60,
+            seed: 7,
+            faults: crate::simulation::transport::FaultConfig::adversarial(),
+            ..SimConfig::default()
+        };
+        let (replay, hashes) = record_session(&config);
+        assert_eq!(replay.config, config);
+        assert_eq!(replay.command_count(), 4);
+        assert_eq!(replay.last_tick(), 120);
+
+        let mut player = ReplayPlayer::new(replay);
+        let mut sim = player.simulation();
+        let mut replayed = Vec::new();
+        while !player.is_finished(&sim) {
+            player.step(&mut sim).unwrap();
+            replayed.push(sim.state_hash());
+        }
+        assert_eq!(replayed, hashes);
+        assert_eq!(sim.robots.len(), 3);
+    }
+
+    #[test]
+    fn test_divergence_is_reported_at_first_differing_tick() {
+        let (replay, _) = record_session(&SimConfig {
+            width: 80,
+            height: 60,
+            seed: 3,
+            ..SimConfig::default()
+        });
+
+        let mut player = ReplayPlayer::new(replay);
+        let mut sim = player.simulation();
+        for _ in 0..20 {
+            player.step(&mut sim).unwrap();
+        }
+        sim.station.resources.minerals += 1;
+
+        let divergence = player.run(&mut sim).unwrap_err();
+        assert_eq!(divergence.tick, 21);
+        assert_eq!(player.divergence(), Some(divergence));
+    }
+
+    #[test]
+    fn test_replay_file_requires_config() {
+        let line = serde_json::to_string(&ReplayEntry::Hash { tick: 1, hash: 2 }).unwrap();
+        assert!(matches!(
+            Replay::read(line.as_bytes()),
+            Err(ReplayError::MissingConfig)
+        ));
+        assert!(matches!(
+            Replay::read("{not json".as_bytes()),
+            Err(ReplayError::Parse { line: 1, .. })
+        ));
+    }
+}
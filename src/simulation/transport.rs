@@ -0,0 +1,197 @@
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use serde::{Deserialize, Serialize};
+
+/// Retard supplémentaire maximal d'un message retenu pour être doublé par les suivants
+const REORDER_WINDOW_TICKS: u64 = 5;
+
+/// Défaillances injectées sur un lien. Les probabilités sont entre 0 et 1.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct FaultConfig {
+    /// Délai fixe d'acheminement, en ticks
+    pub latency_ticks: u64,
+    /// Délai aléatoire supplémentaire maximal, en ticks
+    pub jitter_ticks: u64,
+    pub drop_rate: f64,
+    pub duplicate_rate: f64,
+    pub reorder_rate: f64,
+}
+
+impl FaultConfig {
+    /// Réseau parfait : livraison immédiate, sans perte ni doublon
+    pub fn perfect() -> Self {
+        Self::default()
+    }
+
+    /// Conditions très dégradées, utilisées pour éprouver les protocoles
+    pub fn adversarial() -> Self {
+        Self {
+            latency_ticks: 2,
+            jitter_ticks: 4,
+            drop_rate: 0.3,
+            duplicate_rate: 0.3,
+            reorder_rate: 0.3,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TransportStats {
+    pub sent: u64,
+    pub dropped: u64,
+    pub duplicated: u64,
+    pub reordered: u64,
+    pub delivered: u64,
+}
+
+struct InFlight<T> {
+    deliver_at: u64,
+    sequence: u64,
+    message: T,
+}
+
+/// Lien de transport à défaillances simulées, déterministe pour une graine donnée
+pub struct FaultyLink<T> {
+    pub config: FaultConfig,
+    rng: StdRng,
+    in_flight: Vec<InFlight<T>>,
+    next_sequence: u64,
+    stats: TransportStats,
+}
+
+impl<T: Clone> FaultyLink<T> {
+    pub fn new(config: FaultConfig, seed: u64) -> Self {
+        Self {
+            config,
+            rng: StdRng::seed_from_u64(seed),
+            in_flight: Vec::new(),
+            next_sequence: 0,
+            stats: TransportStats::default(),
+        }
+    }
+
+    /// Confie un message au lien au tick `now`. Retourne faux si le message est perdu.
+    pub fn send(&mut self, now: u64, message: T) -> bool {
+        self.stats.sent += 1;
+        if self.roll(self.config.drop_rate) {
+            self.stats.dropped += 1;
+            return false;
+        }
+
+        let copies = if self.roll(self.config.duplicate_rate) {
+            self.stats.duplicated += 1;
+            2
+        } else {
+            1
+        };
+
+        for _ in 0..copies {
+            let mut delay = self.config.latency_ticks;
+            if self.config.jitter_ticks > 0 {
+                delay += self.rng.gen_range(0..=self.config.jitter_ticks);
+            }
+            if self.roll(self.config.reorder_rate) {
+                self.stats.reordered += 1;
+                delay += self.rng.gen_range(1..=REORDER_WINDOW_TICKS);
+            }
+
+            self.in_flight.push(InFlight {
+                deliver_at: now + delay,
+                sequence: self.next_sequence,
+                message: message.clone(),
+            });
+            self.next_sequence += 1;
+        }
+        true
+    }
+
+    /// Retire les messages arrivés à destination au tick `now`, dans leur ordre d'arrivée
+    pub fn deliver(&mut self, now: u64) -> Vec<T> {
+        let (mut due, pending): (Vec<_>, Vec<_>) = std::mem::take(&mut self.in_flight)
+            .into_iter()
+            .partition(|message| message.deliver_at <= now);
+        self.in_flight = pending;
+
+        due.sort_by_key(|message| (message.deliver_at, message.sequence));
+        self.stats.delivered += due.len() as u64;
+        due.into_iter().map(|message| message.message).collect()
+    }
+
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.len()
+    }
+
+    pub fn stats(&self) -> TransportStats {
+        self.stats
+    }
+
+    fn roll(&mut self, probability: f64) -> bool {
+        probability > 0.0 && self.rng.gen_bool(probability.min(1.0))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run(config: FaultConfig, seed: u64) -> Vec<(u64, u32)> {
+        let mut link = FaultyLink::new(config, seed);
+        let mut received = Vec::new();
+        for tick in 0..50 {
+            if tick < 20 {
+                link.send(tick, tick as u32);
+            }
+            received.extend(link.deliver(tick).into_iter().map(|m| (tick, m)));
+        }
+        received
+    }
+
+    #[test]
+    fn test_perfect_link_delivers_immediately_in_order() {
+        let received = run(FaultConfig::perfect(), 1);
+        assert_eq!(received.len(), 20);
+        assert!(received.iter().all(|(tick, message)| *tick == *message as u64));
+    }
+
+    #[test]
+    fn test_latency_drop_and_duplication() {
+        let delayed = run(
+            FaultConfig {
+                latency_ticks: 3,
+                ..FaultConfig::perfect()
+            },
+            1,
+        );
+        assert!(delayed.iter().all(|(tick, message)| *tick == *message as u64 + 3));
+
+        let dropped = run(
+            FaultConfig {
+                drop_rate: 1.0,
+                ..FaultConfig::perfect()
+            },
+            1,
+        );
+        assert!(dropped.is_empty());
+
+        let duplicated = run(
+            FaultConfig {
+                duplicate_rate: 1.0,
+                ..FaultConfig::perfect()
+            },
+            1,
+        );
+        assert_eq!(duplicated.len(), 40);
+    }
+
+    #[test]
+    fn test_adversarial_link_is_deterministic_per_seed() {
+        let first = run(FaultConfig::adversarial(), 7);
+        assert_eq!(first, run(FaultConfig::adversarial(), 7));
+        assert_ne!(first, run(FaultConfig::adversarial(), 8));
+
+        let messages: Vec<u32> = first.iter().map(|(_, message)| *message).collect();
+        let mut sorted = messages.clone();
+        sorted.sort();
+        assert_ne!(messages, sorted, "some messages should arrive out of order");
+    }
+}
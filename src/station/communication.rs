@@ -1,15 +1,19 @@
-use crate::map::Tile;
 use crate::robot::Position;
 use crate::robot::resources::ResourceType;
+use crate::station::fleet::Heartbeat;
+use crate::station::sync::{ExploredTile, KnowledgeSync, MapKnowledge};
 use crossbeam::channel::{Receiver, Sender};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum RobotMessage {
+    /// Quantité mesurée par le robot et confiance de la mesure, entre 0 et 1
     ResourceDiscovered {
         resource_type: ResourceType,
         position: Position,
+        amount: u32,
+        confidence: f32,
     },
     ResourceConsumed {
         resource_type: ResourceType,
@@ -18,6 +22,33 @@ pub enum RobotMessage {
         robot_id: u32,
     },
     RequestResourcesState,
+    /// Envoie les cases modifiées par le robot et demande les changements
+    /// de la carte de la station depuis la version qu'il connaît
+    MapSync {
+        last_seen_version: u64,
+        tiles: Vec<((u32, u32), ExploredTile)>,
+    },
+    Heartbeat(Heartbeat),
+}
+
+impl RobotMessage {
+    /// Les rapports modifient la comptabilité de la station : ils sont
+    /// acquittés et réémis tant que l'acquittement n'est pas reçu
+    pub fn requires_ack(&self) -> bool {
+        matches!(
+            self,
+            RobotMessage::ResourceDiscovered { .. } | RobotMessage::ResourceConsumed { .. }
+        )
+    }
+}
+
+/// Message d'un robot et son identifiant, croissant et unique pour ce robot.
+/// Une réémission conserve l'identifiant d'origine.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RobotPacket {
+    pub robot_id: u32,
+    pub message_id: u64,
+    pub message: RobotMessage,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -32,28 +63,57 @@ pub enum StationMessage {
         position: Position,
         remaining: u32,
     },
+    /// Confirme la prise en compte du message `message_id` du robot destinataire
     Acknowledgement {
-        message: String,
+        message_id: u64,
     },
+    MapDelta {
+        sync: KnowledgeSync,
+    },
+}
+
+/// Identifiants déjà traités pour un robot : tous ceux inférieurs à `below`,
+/// plus ceux arrivés en avance
+#[derive(Debug, Default)]
+struct ReceivedIds {
+    below: u64,
+    above: BTreeSet<u64>,
+}
+
+impl ReceivedIds {
+    /// Retourne faux si le message a déjà été traité
+    fn insert(&mut self, message_id: u64) -> bool {
+        if message_id < self.below || !self.above.insert(message_id) {
+            return false;
+        }
+        while self.above.remove(&self.below) {
+            self.below += 1;
+        }
+        true
+    }
 }
 
 pub struct StationCommunication {
-    pub robot_receiver: Receiver<(u32, RobotMessage)>,
+    pub robot_receiver: Receiver<RobotPacket>,
     pub robot_senders: HashMap<u32, Sender<StationMessage>>,
     energy_resources: HashMap<Position, u32>,
     mineral_resources: HashMap<Position, u32>,
     scientific_resources: HashMap<Position, u32>,
+    confidence: HashMap<Position, f32>,
+    received: HashMap<u32, ReceivedIds>,
 }
 
 // / Implémentation de la communication entre la station et les robots
 impl StationCommunication {
-    pub fn new(robot_receiver: Receiver<(u32, RobotMessage)>) -> Self {
+    pub fn new(robot_receiver: Receiver<RobotPacket>) -> Self {
         Self {
             robot_receiver,
             robot_senders: HashMap::new(),
             energy_resources: HashMap::new(),
             mineral_resources: HashMap::new(),
             scientific_resources: HashMap::new(),
+            confidence: HashMap::new(),
+            received: HashMap::new(),
         }
     }
 
@@ -61,35 +121,74 @@ impl StationCommunication {
         self.robot_senders.insert(robot_id, sender);
     }
 
-    pub fn process_messages(&mut self, map: &mut crate::map::Map) {
-        while let Ok((robot_id, message)) = self.robot_receiver.try_recv() {
+    /// Ferme le canal d'un robot déconnecté ou perdu. Ses identifiants de
+    /// messages restent suivis : un doublon retardé reste ignoré s'il revient.
+    pub fn unregister_robot(&mut self, robot_id: u32) {
+        self.robot_senders.remove(&robot_id);
+    }
+
+    /// Traite les messages reçus et retourne ceux qui n'avaient pas encore été
+    /// traités, pour que la simulation puisse les répercuter sur la station.
+    /// Un doublon est acquitté de nouveau mais n'est pas appliqué.
+    pub fn process_messages(&mut self, knowledge: &mut MapKnowledge) -> Vec<(u32, RobotMessage)> {
+        let mut handled = Vec::new();
+
+        while let Ok(packet) = self.robot_receiver.try_recv() {
+            let RobotPacket {
+                robot_id,
+                message_id,
+                message,
+            } = packet;
+
+            let is_new = self.received.entry(robot_id).or_default().insert(message_id);
+            if message.requires_ack() {
+                if let Some(sender) = self.robot_senders.get(&robot_id) {
+                    let _ = sender.send(StationMessage::Acknowledgement { message_id });
+                }
+            }
+            if !is_new {
+                continue;
+            }
+
+            handled.push((robot_id, message.clone()));
             match message {
+                // La station ne connaît les gisements que par les mesures des robots :
+                // une mesure au moins aussi fiable remplace l'estimation, une mesure
+                // moins fiable est moyennée avec elle selon leur confiance
                 RobotMessage::ResourceDiscovered {
                     resource_type,
                     position,
+                    amount,
+                    confidence,
                 } => {
-                    if let Some(tile) = map.get_tile(position.x, position.y) {
-                        match (resource_type, tile) {
-                            (ResourceType::Energy, Tile::Energy(energy)) => {
-                                self.energy_resources
-                                    .insert(position, energy.amount);
-                            }
-                            (ResourceType::Minerals, Tile::Mineral(mineral)) => {
-                                self.mineral_resources
-                                    .insert(position, mineral.amount);
-                            }
-                            (ResourceType::ScientificData, Tile::ScientificPoint(point)) => {
-                                self.scientific_resources
-                                    .insert(position, point.value);
-                            }
-                            _ => {}
+                    let table = match resource_type {
+                        ResourceType::Energy => &mut self.energy_resources,
+                        ResourceType::Minerals => &mut self.mineral_resources,
+                        ResourceType::ScientificData => &mut self.scientific_resources,
+                    };
+                    let confidence = confidence.clamp(0.0, 1.0);
+                    let (amount, confidence) = match (
+                        table.get(&position),
+                        self.confidence.get(&position),
+                    ) {
+                        (Some(&current), Some(&known)) if known > confidence => {
+                            let weight = known + confidence;
+                            let blended =
+                                (current as f32 * known + amount as f32 * confidence) / weight;
+                            (
+                                blended.round() as u32,
+                                (known * known + confidence * confidence) / weight,
+                            )
                         }
-                    }
-    
-                    if let Some(sender) = self.robot_senders.get(&robot_id) {
-                        let _ = sender.send(StationMessage::Acknowledgement {
-                            message: format!("Resource discovered at {:?} registered", position),
-                        });
+                        _ => (amount, confidence),
+                    };
+
+                    if amount == 0 {
+                        table.remove(&position);
+                        self.confidence.remove(&position);
+                    } else {
+                        table.insert(position, amount);
+                        self.confidence.insert(position, confidence);
                     }
                 }
                 RobotMessage::ResourceConsumed {
@@ -98,65 +197,28 @@ impl StationCommunication {
                     amount,
                     robot_id: _,
                 } => {
-                    // Mettre à jour la carte et l'état des ressources
-                    match resource_type {
-                        ResourceType::Energy => {
-                            let mut should_remove = false;
-                            let mut new_amount = 0;
-                            
-                            if let Some(current) = self.energy_resources.get(&position) {
-                                new_amount = current.saturating_sub(amount);
-                                should_remove = new_amount == 0;
-                            }
-                            
-                            if should_remove {
-                                self.energy_resources.remove(&position);
-                                new_amount = 0;
-                            } else if new_amount > 0 {
-                                self.energy_resources.insert(position, new_amount);
-                            }
-                            
-                            self.broadcast_resource_update(resource_type, position, new_amount);
-                            
-                            map.consume_energy(position.x, position.y, amount);
-                        }
-                        ResourceType::Minerals => {
-                            let mut should_remove = false;
-                            let mut new_amount = 0;
-                            
-                            if let Some(current) = self.mineral_resources.get(&position) {
-                                new_amount = current.saturating_sub(amount);
-                                should_remove = new_amount == 0;
-                            }
-                            
-                            if should_remove {
-                                self.mineral_resources.remove(&position);
-                                new_amount = 0;
-                            } else if new_amount > 0 {
-                                self.mineral_resources.insert(position, new_amount);
-                            }
-                            
-                            self.broadcast_resource_update(resource_type, position, new_amount);
-                            
-                            map.consume_mineral(position.x, position.y, amount);
-                        }
-                        ResourceType::ScientificData => {
-                            let exists = self.scientific_resources.contains_key(&position);
-                            
-                            if exists {
-                                self.scientific_resources.remove(&position);
-                                self.broadcast_resource_update(resource_type, position, 0);
-                            }
-                            
-                            map.extract_scientific_data(position.x, position.y);
-                        }
-                    }
-    
-                    if let Some(sender) = self.robot_senders.get(&robot_id) {
-                        let _ = sender.send(StationMessage::Acknowledgement {
-                            message: format!("Resource consumption at {:?} registered", position),
-                        });
+                    // La carte est modifiée par le robot lors de l'extraction :
+                    // la station ne tient que la comptabilité des gisements connus.
+                    // Une consommation arrivée avant la découverte est ignorée,
+                    // la mesure suivante du gisement corrigera l'estimation.
+                    let table = match resource_type {
+                        ResourceType::Energy => &mut self.energy_resources,
+                        ResourceType::Minerals => &mut self.mineral_resources,
+                        ResourceType::ScientificData => &mut self.scientific_resources,
+                    };
+                    let Some(current) = table.get(&position).copied() else {
+                        continue;
+                    };
+
+                    let remaining = current.saturating_sub(amount);
+                    if remaining == 0 {
+                        table.remove(&position);
+                        self.confidence.remove(&position);
+                    } else {
+                        table.insert(position, remaining);
                     }
+
+                    self.broadcast_resource_update(resource_type, position, remaining);
                 }
                 // Envoyer l'état complet des ressources au robot qui le demande
                 RobotMessage::RequestResourcesState => {
@@ -168,8 +230,24 @@ impl StationCommunication {
                         });
                     }
                 }
+                // Suivi par la table de la flotte de la station
+                RobotMessage::Heartbeat(_) => {}
+                RobotMessage::MapSync {
+                    last_seen_version,
+                    tiles,
+                } => {
+                    knowledge.merge_robot_knowledge(tiles.into_iter().collect(), robot_id);
+
+                    if let Some(sender) = self.robot_senders.get(&robot_id) {
+                        let _ = sender.send(StationMessage::MapDelta {
+                            sync: knowledge.delta_since(last_seen_version),
+                        });
+                    }
+                }
             }
         }
+
+        handled
     }
 
     fn broadcast_resource_update(&self, resource_type: ResourceType, position: Position, remaining: u32) {
@@ -203,39 +281,33 @@ impl StationCommunication {
             self.scientific_resources.clone(),
         )
     }
+
+    /// Confiance de l'estimation tenue pour un gisement connu
+    pub fn estimate_confidence(&self, position: Position) -> Option<f32> {
+        self.confidence.get(&position).copied()
+    }
 }
 
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::map::{Map, Energy, Mineral, ScientificPoint};
+    use crate::map::Tile;
     use crate::robot::resources::ResourceType;
     use crossbeam::channel;
-    use std::thread;
     use std::time::Duration;
 
-    fn create_test_map() -> Map {
-        let mut map = Map::new(100, 100, 12345);
-        
-        if let Some(tile) = map.get_tile_mut(10, 10) {
-            *tile = crate::map::Tile::Energy(Energy { amount: 1000, is_base: true });
-        }
-        
-        if let Some(tile) = map.get_tile_mut(20, 20) {
-            *tile = crate::map::Tile::Mineral(Mineral { amount: 500, is_base: true });
+    fn packet(robot_id: u32, message_id: u64, message: RobotMessage) -> RobotPacket {
+        RobotPacket {
+            robot_id,
+            message_id,
+            message,
         }
-        
-        if let Some(tile) = map.get_tile_mut(30, 30) {
-            *tile = crate::map::Tile::ScientificPoint(ScientificPoint { value: 300, is_base: true });
-        }
-        
-        map
     }
 
     #[test]
     fn test_register_robot() {
-        let (sender, receiver) = channel::unbounded();
+        let (_sender, receiver) = channel::unbounded();
         let mut comm = StationCommunication::new(receiver);
         
         let (robot_sender, _) = channel::unbounded();
@@ -255,18 +327,20 @@ mod tests {
     fn test_resource_discovery() {
         let (sender, receiver) = channel::unbounded();
         let mut comm = StationCommunication::new(receiver);
-        let mut map = create_test_map();
+        let mut knowledge = MapKnowledge::new();
         
         let (robot_sender, robot_receiver) = channel::unbounded();
         comm.register_robot(1, robot_sender);
         
         let position = Position { x: 10, y: 10 };
-        let _ = sender.send((1, RobotMessage::ResourceDiscovered { 
+        let _ = sender.send(packet(1, 0, RobotMessage::ResourceDiscovered { 
             resource_type: ResourceType::Energy, 
-            position 
+            position,
+            amount: 1000,
+            confidence: 1.0,
         }));
         
-        comm.process_messages(&mut map);
+        comm.process_messages(&mut knowledge);
         
         let (energy, _, _) = comm.get_resources_state();
         assert!(energy.contains_key(&position));
@@ -274,7 +348,7 @@ mod tests {
         
         if let Ok(message) = robot_receiver.recv_timeout(Duration::from_millis(100)) {
             match message {
-                StationMessage::Acknowledgement { message: _ } => (),
+                StationMessage::Acknowledgement { message_id } => assert_eq!(message_id, 0),
                 _ => panic!("Type de message incorrect reçu"),
             }
         } else {
@@ -286,7 +360,7 @@ mod tests {
     fn test_resource_consumption() {
         let (sender, receiver) = channel::unbounded();
         let mut comm = StationCommunication::new(receiver);
-        let mut map = create_test_map();
+        let mut knowledge = MapKnowledge::new();
         
         let (robot1_sender, _) = channel::unbounded();
         let (robot2_sender, robot2_receiver) = channel::unbounded();
@@ -294,21 +368,23 @@ mod tests {
         comm.register_robot(2, robot2_sender);
         
         let position = Position { x: 10, y: 10 };
-        let _ = sender.send((1, RobotMessage::ResourceDiscovered { 
+        let _ = sender.send(packet(1, 0, RobotMessage::ResourceDiscovered { 
             resource_type: ResourceType::Energy, 
-            position 
+            position,
+            amount: 1000,
+            confidence: 1.0,
         }));
         
-        comm.process_messages(&mut map);
+        comm.process_messages(&mut knowledge);
         
-        let _ = sender.send((1, RobotMessage::ResourceConsumed { 
+        let _ = sender.send(packet(1, 1, RobotMessage::ResourceConsumed { 
             resource_type: ResourceType::Energy, 
             position,
             amount: 200,
             robot_id: 1 
         }));
         
-        comm.process_messages(&mut map);
+        comm.process_messages(&mut knowledge);
         
         let (energy, _, _) = comm.get_resources_state();
         assert_eq!(energy.get(&position), Some(&800)); 
@@ -331,11 +407,47 @@ mod tests {
         }
     }
     
+    #[test]
+    fn test_partially_analysed_point_stays_known() {
+        let (sender, receiver) = channel::unbounded();
+        let mut comm = StationCommunication::new(receiver);
+        let mut knowledge = MapKnowledge::new();
+
+        let position = Position { x: 4, y: 7 };
+        let _ = sender.send(packet(1, 0, RobotMessage::ResourceDiscovered {
+            resource_type: ResourceType::ScientificData,
+            position,
+            amount: 300,
+            confidence: 1.0,
+        }));
+        let _ = sender.send(packet(1, 1, RobotMessage::ResourceConsumed {
+            resource_type: ResourceType::ScientificData,
+            position,
+            amount: 100,
+            robot_id: 1,
+        }));
+        comm.process_messages(&mut knowledge);
+
+        let (_, _, scientific) = comm.get_resources_state();
+        assert_eq!(scientific.get(&position), Some(&200));
+
+        let _ = sender.send(packet(1, 2, RobotMessage::ResourceConsumed {
+            resource_type: ResourceType::ScientificData,
+            position,
+            amount: 200,
+            robot_id: 1,
+        }));
+        comm.process_messages(&mut knowledge);
+
+        let (_, _, scientific) = comm.get_resources_state();
+        assert_eq!(scientific.get(&position), None);
+    }
+
     #[test]
     fn test_request_resources_state() {
         let (sender, receiver) = channel::unbounded();
         let mut comm = StationCommunication::new(receiver);
-        let mut map = create_test_map();
+        let mut knowledge = MapKnowledge::new();
         
         let (robot_sender, robot_receiver) = channel::unbounded();
         comm.register_robot(1, robot_sender);
@@ -343,16 +455,20 @@ mod tests {
         let energy_pos = Position { x: 10, y: 10 };
         let mineral_pos = Position { x: 20, y: 20 };
         
-        let _ = sender.send((1, RobotMessage::ResourceDiscovered { 
+        let _ = sender.send(packet(1, 0, RobotMessage::ResourceDiscovered { 
             resource_type: ResourceType::Energy, 
-            position: energy_pos
+            position: energy_pos,
+            amount: 1000,
+            confidence: 1.0,
         }));
-        let _ = sender.send((1, RobotMessage::ResourceDiscovered { 
+        let _ = sender.send(packet(1, 1, RobotMessage::ResourceDiscovered { 
             resource_type: ResourceType::Minerals, 
-            position: mineral_pos
+            position: mineral_pos,
+            amount: 500,
+            confidence: 1.0,
         }));
         
-        comm.process_messages(&mut map);
+        comm.process_messages(&mut knowledge);
         
         while let Ok(message) = robot_receiver.try_recv() {
             match message {
@@ -365,8 +481,8 @@ mod tests {
             }
         }
         
-        let _ = sender.send((1, RobotMessage::RequestResourcesState));
-        comm.process_messages(&mut map);
+        let _ = sender.send(packet(1, 2, RobotMessage::RequestResourcesState));
+        comm.process_messages(&mut knowledge);
         
         if let Ok(message) = robot_receiver.recv_timeout(Duration::from_millis(100)) {
             match message {
@@ -385,4 +501,148 @@ mod tests {
             panic!("Aucun message d'état des ressources reçu");
         }
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_map_sync_replies_with_delta() {
+        let (robot_sender, robot_receiver) = channel::unbounded();
+        let (station_sender, station_receiver) = channel::unbounded();
+        let mut comm = StationCommunication::new(robot_receiver);
+        comm.register_robot(1, station_sender);
+
+        let mut knowledge = MapKnowledge::new();
+        let mut robot_knowledge = MapKnowledge::new();
+        robot_knowledge.observe_tile(
+            Position { x: 4, y: 4 },
+            &Tile::Empty,
+            crate::station::conflict::Observer {
+                robot_id: 1,
+                tick: 0,
+                sensor_efficiency: 0.5,
+            },
+        );
+
+        robot_sender
+            .send(packet(
+                1,
+                0,
+                RobotMessage::MapSync {
+                    last_seen_version: robot_knowledge.version,
+                    tiles: robot_knowledge.take_local_changes().into_iter().collect(),
+                },
+            ))
+            .unwrap();
+        comm.process_messages(&mut knowledge);
+
+        assert_eq!(knowledge.explored_count(), 1);
+        match station_receiver.recv_timeout(Duration::from_millis(100)).unwrap() {
+            StationMessage::MapDelta { sync } => {
+                assert!(!sync.is_snapshot());
+                assert_eq!(sync.version(), 1);
+                assert_eq!(sync.tile_count(), 1);
+            }
+            _ => panic!("Expected MapDelta message"),
+        }
+    }
+
+    #[test]
+    fn test_duplicate_reports_are_acknowledged_but_applied_once() {
+        let (sender, receiver) = channel::unbounded();
+        let mut comm = StationCommunication::new(receiver);
+        let mut knowledge = MapKnowledge::new();
+        let (robot_sender, robot_receiver) = channel::unbounded();
+        comm.register_robot(1, robot_sender);
+
+        let position = Position { x: 20, y: 20 };
+        let consumed = RobotMessage::ResourceConsumed {
+            resource_type: ResourceType::Minerals,
+            position,
+            amount: 100,
+            robot_id: 1,
+        };
+        // Consommation arrivée avant la découverte, puis doublons et désordre
+        let _ = sender.send(packet(1, 1, consumed.clone()));
+        let _ = sender.send(packet(
+            1,
+            0,
+            RobotMessage::ResourceDiscovered {
+                resource_type: ResourceType::Minerals,
+                position,
+                amount: 500,
+                confidence: 1.0,
+            },
+        ));
+        let _ = sender.send(packet(1, 1, consumed.clone()));
+        let _ = sender.send(packet(1, 0, consumed.clone()));
+        let handled = comm.process_messages(&mut knowledge);
+
+        assert_eq!(handled.len(), 2);
+        let (_, minerals, _) = comm.get_resources_state();
+        assert_eq!(minerals.get(&position), Some(&500));
+
+        let acks: Vec<u64> = robot_receiver
+            .try_iter()
+            .filter_map(|message| match message {
+                StationMessage::Acknowledgement { message_id } => Some(message_id),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(acks, vec![1, 0, 1, 0]);
+
+        let _ = sender.send(packet(1, 2, consumed));
+        comm.process_messages(&mut knowledge);
+        let (_, minerals, _) = comm.get_resources_state();
+        assert_eq!(minerals.get(&position), Some(&400));
+    }
+
+    #[test]
+    fn test_measurements_are_replaced_or_blended_by_confidence() {
+        let (sender, receiver) = channel::unbounded();
+        let mut comm = StationCommunication::new(receiver);
+        let mut knowledge = MapKnowledge::new();
+        let position = Position { x: 20, y: 20 };
+        let discovered = |amount, confidence| RobotMessage::ResourceDiscovered {
+            resource_type: ResourceType::Minerals,
+            position,
+            amount,
+            confidence,
+        };
+
+        // Aucune carte n'est consultée : seule la mesure du robot compte
+        let _ = sender.send(packet(1, 0, discovered(600, 0.5)));
+        comm.process_messages(&mut knowledge);
+        let (_, minerals, _) = comm.get_resources_state();
+        assert_eq!(minerals.get(&position), Some(&600));
+        assert_eq!(comm.estimate_confidence(position), Some(0.5));
+
+        // Une mesure plus fiable remplace l'estimation
+        let _ = sender.send(packet(2, 0, discovered(400, 1.0)));
+        comm.process_messages(&mut knowledge);
+        let (_, minerals, _) = comm.get_resources_state();
+        assert_eq!(minerals.get(&position), Some(&400));
+        assert_eq!(comm.estimate_confidence(position), Some(1.0));
+
+        // Une mesure moins fiable est moyennée, sans rendre l'estimation certaine
+        let _ = sender.send(packet(1, 1, discovered(700, 0.5)));
+        comm.process_messages(&mut knowledge);
+        let (_, minerals, _) = comm.get_resources_state();
+        assert_eq!(minerals.get(&position), Some(&500));
+        let confidence = comm.estimate_confidence(position).unwrap();
+        assert!(confidence > 0.5 && confidence < 1.0);
+
+        // Un gisement épuisé est oublié avec son estimation
+        let _ = sender.send(packet(
+            2,
+            1,
+            RobotMessage::ResourceConsumed {
+                resource_type: ResourceType::Minerals,
+                position,
+                amount: 500,
+                robot_id: 2,
+            },
+        ));
+        comm.process_messages(&mut knowledge);
+        let (_, minerals, _) = comm.get_resources_state();
+        assert!(minerals.is_empty());
+        assert_eq!(comm.estimate_confidence(position), None);
+    }
+}
@@ -0,0 +1,223 @@
+use crate::map::Tile;
+use crate::robot::Position;
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::collections::BTreeMap;
+
+/// Nombre maximal de conflits conservés dans le journal
+pub const MAX_CONFLICT_LOG: usize = 100;
+
+/// Contexte d'une observation : quel robot, à quel tick et avec quel capteur
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Observer {
+    pub robot_id: u32,
+    pub tick: u64,
+    pub sensor_efficiency: f32,
+}
+
+/// Dernière observation d'une case par un robot. `count` est l'entrée du robot
+/// dans l'horloge vectorielle de la case.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Observation {
+    pub tile: Tile,
+    pub tick: u64,
+    pub count: u64,
+    pub sensor_efficiency: f32,
+}
+
+/// Relation causale entre deux historiques d'observation d'une même case
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Causality {
+    Equal,
+    Before,
+    After,
+    Concurrent,
+}
+
+/// Compare les horloges vectorielles de deux ensembles d'observations
+pub fn compare_clocks(
+    local: &BTreeMap<u32, Observation>,
+    remote: &BTreeMap<u32, Observation>,
+) -> Causality {
+    let mut local_ahead = false;
+    let mut remote_ahead = false;
+
+    for robot_id in local.keys().chain(remote.keys()) {
+        let local_count = local.get(robot_id).map_or(0, |o| o.count);
+        let remote_count = remote.get(robot_id).map_or(0, |o| o.count);
+        match local_count.cmp(&remote_count) {
+            Ordering::Greater => local_ahead = true,
+            Ordering::Less => remote_ahead = true,
+            Ordering::Equal => {}
+        }
+    }
+
+    match (local_ahead, remote_ahead) {
+        (false, false) => Causality::Equal,
+        (true, false) => Causality::After,
+        (false, true) => Causality::Before,
+        (true, true) => Causality::Concurrent,
+    }
+}
+
+/// Fusionne deux ensembles d'observations en gardant la plus récente de chaque robot
+pub fn merge_observations(
+    local: &BTreeMap<u32, Observation>,
+    remote: &BTreeMap<u32, Observation>,
+) -> BTreeMap<u32, Observation> {
+    let mut merged = local.clone();
+    for (robot_id, observation) in remote {
+        match merged.get(robot_id) {
+            Some(known) if known.count >= observation.count => {}
+            _ => {
+                merged.insert(*robot_id, observation.clone());
+            }
+        }
+    }
+    merged
+}
+
+/// Politique de résolution des observations concurrentes et contradictoires
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub enum ConflictPolicy {
+    /// L'observation la plus récente (en ticks de simulation) l'emporte
+    #[default]
+    LatestObservation,
+    /// L'observation faite avec le capteur le plus efficace l'emporte
+    TrustSensorEfficiency,
+    /// Le contenu vu par au moins `quorum` robots l'emporte, sinon la plus récente
+    Consensus { quorum: usize },
+}
+
+impl ConflictPolicy {
+    pub fn name(&self) -> &'static str {
+        match self {
+            ConflictPolicy::LatestObservation => "Latest observation",
+            ConflictPolicy::TrustSensorEfficiency => "Best sensor",
+            ConflictPolicy::Consensus { .. } => "Consensus",
+        }
+    }
+
+    /// Choisit l'observation retenue et retourne l'identifiant de son robot
+    pub fn resolve<'a>(
+        &self,
+        observations: &'a BTreeMap<u32, Observation>,
+    ) -> Option<(u32, &'a Observation)> {
+        let latest = |(id_a, a): &(&u32, &Observation), (id_b, b): &(&u32, &Observation)| {
+            a.tick.cmp(&b.tick).then(id_a.cmp(id_b))
+        };
+
+        let chosen = match self {
+            ConflictPolicy::LatestObservation => observations.iter().max_by(latest),
+            ConflictPolicy::TrustSensorEfficiency => observations.iter().max_by(|a, b| {
+                a.1.sensor_efficiency
+                    .total_cmp(&b.1.sensor_efficiency)
+                    .then_with(|| latest(a, b))
+            }),
+            ConflictPolicy::Consensus { quorum } => {
+                let mut votes: Vec<(&Tile, usize)> = Vec::new();
+                for observation in observations.values() {
+                    match votes.iter_mut().find(|(tile, _)| **tile == observation.tile) {
+                        Some((_, count)) => *count += 1,
+                        None => votes.push((&observation.tile, 1)),
+                    }
+                }
+
+                let winner = votes
+                    .iter()
+                    .filter(|(_, count)| *count >= *quorum)
+                    .max_by_key(|(_, count)| *count)
+                    .map(|(tile, _)| *tile);
+
+                match winner {
+                    Some(tile) => observations
+                        .iter()
+                        .filter(|(_, observation)| observation.tile == *tile)
+                        .max_by(latest),
+                    None => observations.iter().max_by(latest),
+                }
+            }
+        };
+
+        chosen.map(|(robot_id, observation)| (*robot_id, observation))
+    }
+}
+
+/// Désaccord entre deux sources sur le contenu d'une case
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TileConflict {
+    pub position: Position,
+    pub tick: u64,
+    pub local_robot: Option<u32>,
+    pub local_tile: Tile,
+    pub remote_robot: Option<u32>,
+    pub remote_tile: Tile,
+    pub resolved: Tile,
+    pub policy: ConflictPolicy,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::Mineral;
+
+    fn observation(tile: Tile, tick: u64, count: u64, sensor_efficiency: f32) -> Observation {
+        Observation {
+            tile,
+            tick,
+            count,
+            sensor_efficiency,
+        }
+    }
+
+    fn mineral(amount: u32) -> Tile {
+        Tile::Mineral(Mineral {
+            amount,
+            is_base: false,
+        })
+    }
+
+    #[test]
+    fn test_clock_comparison() {
+        let mut a = BTreeMap::new();
+        a.insert(1, observation(Tile::Empty, 1, 1, 0.5));
+        let mut b = a.clone();
+        assert_eq!(compare_clocks(&a, &b), Causality::Equal);
+
+        b.insert(2, observation(Tile::Obstacle, 2, 1, 0.5));
+        assert_eq!(compare_clocks(&a, &b), Causality::Before);
+        assert_eq!(compare_clocks(&b, &a), Causality::After);
+
+        a.insert(3, observation(Tile::Empty, 3, 1, 0.5));
+        assert_eq!(compare_clocks(&a, &b), Causality::Concurrent);
+        assert_eq!(merge_observations(&a, &b).len(), 3);
+    }
+
+    #[test]
+    fn test_resolution_policies() {
+        let mut observations = BTreeMap::new();
+        observations.insert(1, observation(mineral(100), 10, 1, 0.9));
+        observations.insert(2, observation(mineral(80), 20, 1, 0.6));
+        observations.insert(3, observation(mineral(80), 5, 1, 0.5));
+        observations.insert(4, observation(mineral(50), 30, 1, 0.4));
+
+        let latest = ConflictPolicy::LatestObservation.resolve(&observations).unwrap();
+        assert_eq!(latest.0, 4);
+
+        let trusted = ConflictPolicy::TrustSensorEfficiency
+            .resolve(&observations)
+            .unwrap();
+        assert_eq!(trusted.0, 1);
+
+        let consensus = ConflictPolicy::Consensus { quorum: 2 }
+            .resolve(&observations)
+            .unwrap();
+        assert_eq!(consensus.0, 2);
+        assert_eq!(consensus.1.tile, mineral(80));
+
+        let no_quorum = ConflictPolicy::Consensus { quorum: 3 }
+            .resolve(&observations)
+            .unwrap();
+        assert_eq!(no_quorum.0, 4);
+    }
+}
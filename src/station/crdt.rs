@@ -0,0 +1,279 @@
+use crate::map::Tile;
+use crate::robot::Position;
+use crate::station::conflict::{ConflictPolicy, Observation, Observer};
+use crate::station::sync::ExploredTile;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap};
+
+/// Compteur croissant répliqué (G-Counter) : une entrée par robot, fusion par maximum
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct GCounter {
+    counts: BTreeMap<u32, u64>,
+}
+
+impl GCounter {
+    pub fn increment(&mut self, robot_id: u32, amount: u64) {
+        *self.counts.entry(robot_id).or_insert(0) += amount;
+    }
+
+    pub fn value(&self) -> u64 {
+        self.counts.values().sum()
+    }
+
+    /// Retourne vrai si la fusion a modifié le compteur
+    pub fn merge(&mut self, other: &GCounter) -> bool {
+        let mut changed = false;
+        for (robot_id, count) in &other.counts {
+            let entry = self.counts.entry(*robot_id).or_insert(0);
+            if *count > *entry {
+                *entry = *count;
+                changed = true;
+            }
+        }
+        changed
+    }
+}
+
+/// État répliqué d'un gisement : capacité estimée (registre maximum) et
+/// quantités extraites par chaque robot (G-Counter)
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DepositState {
+    pub capacity: u64,
+    pub consumed: GCounter,
+}
+
+impl DepositState {
+    pub fn remaining(&self) -> u64 {
+        self.capacity.saturating_sub(self.consumed.value())
+    }
+
+    fn merge(&mut self, other: &DepositState) -> bool {
+        let mut changed = self.consumed.merge(&other.consumed);
+        if other.capacity > self.capacity {
+            self.capacity = other.capacity;
+            changed = true;
+        }
+        changed
+    }
+}
+
+/// Carte partagée sans conflit (CRDT par état) échangée directement entre robots.
+/// La fusion est commutative, associative et idempotente : deux robots ayant
+/// échangé leurs états convergent vers la même carte sans passer par la station.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SharedMap {
+    tiles: HashMap<(u32, u32), BTreeMap<u32, Observation>>,
+    deposits: HashMap<(u32, u32), DepositState>,
+}
+
+impl SharedMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Enregistre l'observation d'une case par un robot
+    pub fn observe(&mut self, position: Position, tile: &Tile, observer: Observer) {
+        let key = (position.x, position.y);
+        let observations = self.tiles.entry(key).or_default();
+
+        let previous = observations.get(&observer.robot_id);
+        if previous.is_some_and(|observation| observation.tile == *tile) {
+            return;
+        }
+        let count = previous.map_or(0, |observation| observation.count) + 1;
+        observations.insert(
+            observer.robot_id,
+            Observation {
+                tile: tile.clone(),
+                tick: observer.tick,
+                count,
+                sensor_efficiency: observer.sensor_efficiency,
+            },
+        );
+
+        // La quantité vue plus ce qui a déjà été extrait donne une borne basse de la capacité
+        if let Some(amount) = resource_amount(tile) {
+            let deposit = self.deposits.entry(key).or_default();
+            deposit.capacity = deposit
+                .capacity
+                .max(amount as u64 + deposit.consumed.value());
+        }
+    }
+
+    /// Enregistre une extraction par un robot ; les compteurs ne font que croître
+    pub fn record_consumption(&mut self, position: Position, robot_id: u32, amount: u32) {
+        self.deposits
+            .entry((position.x, position.y))
+            .or_default()
+            .consumed
+            .increment(robot_id, amount as u64);
+    }
+
+    /// Fusionne l'état d'un autre robot. Retourne les positions modifiées.
+    pub fn merge(&mut self, other: &SharedMap) -> Vec<Position> {
+        let mut changed = Vec::new();
+
+        for (key, observations) in &other.tiles {
+            let local = self.tiles.entry(*key).or_default();
+            let mut tile_changed = false;
+            for (robot_id, observation) in observations {
+                match local.get(robot_id) {
+                    Some(known) if known.count >= observation.count => {}
+                    _ => {
+                        local.insert(*robot_id, observation.clone());
+                        tile_changed = true;
+                    }
+                }
+            }
+            if tile_changed {
+                changed.push(*key);
+            }
+        }
+
+        for (key, deposit) in &other.deposits {
+            if self.deposits.entry(*key).or_default().merge(deposit) && !changed.contains(key) {
+                changed.push(*key);
+            }
+        }
+
+        changed
+            .into_iter()
+            .map(|(x, y)| Position { x, y })
+            .collect()
+    }
+
+    pub fn remaining(&self, position: Position) -> Option<u64> {
+        self.deposits
+            .get(&(position.x, position.y))
+            .map(DepositState::remaining)
+    }
+
+    /// Contenu d'une case selon la politique de résolution, la quantité des
+    /// gisements étant dérivée des compteurs d'extraction
+    pub fn tile(&self, position: Position, policy: ConflictPolicy) -> Option<ExploredTile> {
+        let observations = self.tiles.get(&(position.x, position.y))?;
+        let (robot_id, chosen) = policy.resolve(observations)?;
+
+        let tile = match self.remaining(position) {
+            Some(remaining) if resource_amount(&chosen.tile).is_some() => {
+                with_resource_amount(&chosen.tile, remaining as u32)
+            }
+            _ => chosen.tile.clone(),
+        };
+
+        Some(ExploredTile {
+            tile,
+            version: observations.values().map(|o| o.count).sum(),
+            explorer_id: Some(robot_id),
+            observations: observations.clone(),
+        })
+    }
+
+    pub fn len(&self) -> usize {
+        self.tiles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tiles.is_empty()
+    }
+}
+
+fn resource_amount(tile: &Tile) -> Option<u32> {
+    match tile {
+        Tile::Energy(energy) => Some(energy.amount),
+        Tile::Mineral(mineral) => Some(mineral.amount),
+        Tile::ScientificPoint(point) => Some(point.value),
+        _ => None,
+    }
+}
+
+fn with_resource_amount(tile: &Tile, amount: u32) -> Tile {
+    if amount == 0 {
+        return Tile::Empty;
+    }
+
+    let mut tile = tile.clone();
+    match &mut tile {
+        Tile::Energy(energy) => energy.amount = amount,
+        Tile::Mineral(mineral) => mineral.amount = amount,
+        Tile::ScientificPoint(point) => point.value = amount,
+        _ => {}
+    }
+    tile
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::Mineral;
+
+    fn observer(robot_id: u32, tick: u64) -> Observer {
+        Observer {
+            robot_id,
+            tick,
+            sensor_efficiency: 0.5,
+        }
+    }
+
+    fn mineral(amount: u32) -> Tile {
+        Tile::Mineral(Mineral {
+            amount,
+            is_base: false,
+        })
+    }
+
+    #[test]
+    fn test_merge_is_commutative_and_idempotent() {
+        let position = Position { x: 2, y: 3 };
+        let mut a = SharedMap::new();
+        let mut b = SharedMap::new();
+        a.observe(position, &Tile::Empty, observer(1, 1));
+        b.observe(position, &Tile::Obstacle, observer(2, 4));
+        b.observe(Position { x: 5, y: 5 }, &Tile::Empty, observer(2, 4));
+
+        let mut ab = a.clone();
+        ab.merge(&b);
+        let mut ba = b.clone();
+        ba.merge(&a);
+        assert_eq!(ab, ba);
+        assert!(ab.merge(&b).is_empty());
+        assert_eq!(ab.len(), 2);
+
+        let policy = ConflictPolicy::LatestObservation;
+        assert_eq!(ab.tile(position, policy).unwrap().tile, Tile::Obstacle);
+    }
+
+    #[test]
+    fn test_concurrent_consumption_converges() {
+        let position = Position { x: 1, y: 1 };
+        let mut shared = SharedMap::new();
+        shared.observe(position, &mineral(100), observer(1, 1));
+
+        let mut first = shared.clone();
+        let mut second = shared.clone();
+        first.record_consumption(position, 1, 30);
+        first.record_consumption(position, 1, 10);
+        second.record_consumption(position, 2, 25);
+
+        let mut merged_first = first.clone();
+        merged_first.merge(&second);
+        let mut merged_second = second.clone();
+        merged_second.merge(&first);
+        merged_second.merge(&first);
+
+        assert_eq!(merged_first.remaining(position), Some(35));
+        assert_eq!(merged_second.remaining(position), Some(35));
+        let policy = ConflictPolicy::LatestObservation;
+        assert_eq!(merged_first.tile(position, policy).unwrap().tile, mineral(35));
+    }
+
+    #[test]
+    fn test_late_observation_accounts_for_known_consumption() {
+        let position = Position { x: 1, y: 1 };
+        let mut shared = SharedMap::new();
+        shared.record_consumption(position, 1, 20);
+        shared.observe(position, &mineral(80), observer(2, 5));
+
+        assert_eq!(shared.remaining(position), Some(80));
+    }
+}
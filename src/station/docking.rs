@@ -0,0 +1,356 @@
+use crate::map::{Map, Tile};
+use crate::robot::{Position, Robot};
+use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Distance à la station à partir de laquelle un robot demande une baie
+pub const APPROACH_RADIUS: u32 = 4;
+/// Ticks sans se rapprocher de sa baie après lesquels un robot perd sa réservation
+pub const STALLED_RESERVATION_TICKS: u32 = 50;
+
+const BAY_OFFSETS: [(i32, i32); 8] = [
+    (0, -1),
+    (1, 0),
+    (0, 1),
+    (-1, 0),
+    (1, -1),
+    (1, 1),
+    (-1, 1),
+    (-1, -1),
+];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DockingConfig {
+    /// Nombre de baies autour de la station (entre 1 et 8)
+    pub bay_count: usize,
+    /// Unités de chargement déchargées par tick
+    pub unload_rate: u32,
+}
+
+impl Default for DockingConfig {
+    fn default() -> Self {
+        Self {
+            bay_count: 2,
+            unload_rate: 20,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DockingBay {
+    pub position: Position,
+    pub occupant: Option<u32>,
+    pub docked_ticks: u32,
+    /// Plus petite distance entre l'occupant et la baie depuis sa réservation
+    pub closest_distance: u32,
+    /// Ticks écoulés depuis que l'occupant s'est rapproché de la baie
+    pub stalled_ticks: u32,
+}
+
+impl DockingBay {
+    fn assign(&mut self, occupant: Option<u32>) {
+        self.occupant = occupant;
+        self.docked_ticks = 0;
+        self.closest_distance = u32::MAX;
+        self.stalled_ticks = 0;
+    }
+}
+
+/// Gestion des baies d'amarrage et de la file d'attente des robots
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DockingManager {
+    pub config: DockingConfig,
+    bays: Vec<DockingBay>,
+    queue: VecDeque<u32>,
+}
+
+impl DockingManager {
+    /// Place les baies sur les cases praticables de la carte autour de la station
+    pub fn new(station_position: Position, map: &Map, config: DockingConfig) -> Self {
+        let bays = BAY_OFFSETS
+            .iter()
+            .filter_map(|(dx, dy)| {
+                let x = u32::try_from(station_position.x as i32 + dx).ok()?;
+                let y = u32::try_from(station_position.y as i32 + dy).ok()?;
+                match map.get_tile(x, y) {
+                    None | Some(Tile::Obstacle | Tile::Station) => None,
+                    Some(_) => Some(Position { x, y }),
+                }
+            })
+            .take(config.bay_count.clamp(1, BAY_OFFSETS.len()))
+            .map(|position| DockingBay {
+                position,
+                occupant: None,
+                docked_ticks: 0,
+                closest_distance: u32::MAX,
+                stalled_ticks: 0,
+            })
+            .collect();
+
+        Self {
+            config,
+            bays,
+            queue: VecDeque::new(),
+        }
+    }
+
+    /// Demande une baie pour un robot : retourne sa position si une baie lui est
+    /// attribuée, sinon le robot est placé (ou reste) dans la file d'attente
+    pub fn request_bay(&mut self, robot_id: u32) -> Option<Position> {
+        if let Some(position) = self.assigned_bay(robot_id) {
+            return Some(position);
+        }
+
+        if !self.queue.contains(&robot_id) {
+            self.queue.push_back(robot_id);
+        }
+        self.assign_free_bays();
+        self.assigned_bay(robot_id)
+    }
+
+    pub fn assigned_bay(&self, robot_id: u32) -> Option<Position> {
+        self.bays
+            .iter()
+            .find(|bay| bay.occupant == Some(robot_id))
+            .map(|bay| bay.position)
+    }
+
+    pub fn is_docked(&self, robot_id: u32, position: Position) -> bool {
+        self.assigned_bay(robot_id) == Some(position)
+    }
+
+    /// Compte un tick passé à quai et retourne la durée totale d'amarrage
+    pub fn service_tick(&mut self, robot_id: u32) -> u32 {
+        match self
+            .bays
+            .iter_mut()
+            .find(|bay| bay.occupant == Some(robot_id))
+        {
+            Some(bay) => {
+                bay.docked_ticks += 1;
+                bay.docked_ticks
+            }
+            None => 0,
+        }
+    }
+
+    /// Libère la baie (ou la place dans la file) d'un robot qui repart
+    pub fn release(&mut self, robot_id: u32) {
+        self.queue.retain(|id| *id != robot_id);
+        for bay in self.bays.iter_mut() {
+            if bay.occupant == Some(robot_id) {
+                bay.assign(None);
+            }
+        }
+        self.assign_free_bays();
+    }
+
+    /// Retire les baies et places dans la file des robots qui ne les rejoindront
+    /// plus : robot disparu, batterie vide hors d'une baie, ou occupant qui ne se
+    /// rapproche plus de sa baie depuis `STALLED_RESERVATION_TICKS`.
+    /// Retourne les robots dont la réservation a été retirée.
+    pub fn drop_stalled_reservations(&mut self, robots: &[Robot]) -> Vec<u32> {
+        let find = |robot_id: u32| robots.iter().find(|robot| robot.id == robot_id);
+        let mut dropped = Vec::new();
+
+        self.queue.retain(|robot_id| match find(*robot_id) {
+            Some(robot) if robot.energy > 0.0 => true,
+            _ => {
+                dropped.push(*robot_id);
+                false
+            }
+        });
+
+        for bay in self.bays.iter_mut() {
+            let Some(robot_id) = bay.occupant else {
+                continue;
+            };
+            let stalled = match find(robot_id) {
+                None => true,
+                Some(robot) => {
+                    let distance = robot.position.distance_to(&bay.position);
+                    if distance < bay.closest_distance || distance == 0 {
+                        bay.closest_distance = distance;
+                        bay.stalled_ticks = 0;
+                    } else {
+                        bay.stalled_ticks += 1;
+                    }
+                    (distance > 0 && robot.energy <= 0.0)
+                        || bay.stalled_ticks >= STALLED_RESERVATION_TICKS
+                }
+            };
+            if stalled {
+                bay.assign(None);
+                dropped.push(robot_id);
+            }
+        }
+
+        self.assign_free_bays();
+        dropped
+    }
+
+    pub fn reset(&mut self) {
+        self.queue.clear();
+        for bay in self.bays.iter_mut() {
+            bay.assign(None);
+        }
+    }
+
+    pub fn bays(&self) -> &[DockingBay] {
+        &self.bays
+    }
+
+    pub fn queue_len(&self) -> usize {
+        self.queue.len()
+    }
+
+    pub fn occupied_bays(&self) -> usize {
+        self.bays.iter().filter(|bay| bay.occupant.is_some()).count()
+    }
+
+    fn assign_free_bays(&mut self) {
+        for bay in self.bays.iter_mut() {
+            if bay.occupant.is_some() {
+                continue;
+            }
+            match self.queue.pop_front() {
+                Some(robot_id) => bay.assign(Some(robot_id)),
+                None => break,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::station::Station;
+
+    fn open_map(width: u32, height: u32) -> Map {
+        let mut map = Map::new(width, height, 42);
+        for y in 0..height {
+            for x in 0..width {
+                *map.get_tile_mut(x, y).unwrap() = Tile::Empty;
+            }
+        }
+        map
+    }
+
+    fn robot_at(id: u32, position: Position) -> Robot {
+        let mut robot = Robot::new(position, Vec::new());
+        robot.id = id;
+        robot
+    }
+
+    #[test]
+    fn test_bays_are_assigned_then_queued() {
+        let map = open_map(20, 20);
+        let mut docking =
+            DockingManager::new(Position { x: 10, y: 10 }, &map, DockingConfig::default());
+
+        let bay1 = docking.request_bay(1).unwrap();
+        let bay2 = docking.request_bay(2).unwrap();
+        assert_ne!(bay1, bay2);
+        assert_eq!(bay1.distance_to(&Position { x: 10, y: 10 }), 1);
+
+        assert_eq!(docking.request_bay(3), None);
+        assert_eq!(docking.request_bay(4), None);
+        assert_eq!(docking.request_bay(3), None);
+        assert_eq!(docking.queue_len(), 2);
+
+        docking.release(1);
+        assert_eq!(docking.assigned_bay(3), Some(bay1));
+        assert_eq!(docking.queue_len(), 1);
+        assert_eq!(docking.request_bay(4), None);
+    }
+
+    #[test]
+    fn test_service_ticks_reset_on_release() {
+        let map = open_map(20, 20);
+        let mut docking = DockingManager::new(
+            Position { x: 10, y: 10 },
+            &map,
+            DockingConfig {
+                bay_count: 1,
+                unload_rate: 20,
+            },
+        );
+
+        docking.request_bay(1);
+        assert_eq!(docking.service_tick(1), 1);
+        assert_eq!(docking.service_tick(1), 2);
+
+        docking.release(1);
+        docking.request_bay(2);
+        assert_eq!(docking.service_tick(2), 1);
+        assert_eq!(docking.service_tick(1), 0);
+    }
+
+    #[test]
+    fn test_bays_stay_inside_the_map_and_off_obstacles() {
+        let mut map = open_map(11, 11);
+        *map.get_tile_mut(9, 10).unwrap() = Tile::Obstacle;
+        let config = DockingConfig {
+            bay_count: 8,
+            unload_rate: 20,
+        };
+        let docking = DockingManager::new(Position { x: 10, y: 10 }, &map, config.clone());
+        let positions: Vec<Position> = docking.bays().iter().map(|bay| bay.position).collect();
+        assert_eq!(
+            positions,
+            vec![Position { x: 10, y: 9 }, Position { x: 9, y: 9 }]
+        );
+
+        let mut map = Map::new(11, 11, 1);
+        let station = Station::new(&mut map);
+        let docking = DockingManager::new(station.position, &map, config);
+        assert!(!docking.bays().is_empty());
+        for bay in docking.bays() {
+            assert!(!matches!(
+                map.get_tile(bay.position.x, bay.position.y),
+                None | Some(Tile::Obstacle | Tile::Station)
+            ));
+        }
+    }
+
+    #[test]
+    fn test_stalled_reservations_are_dropped() {
+        let map = open_map(20, 20);
+        let config = DockingConfig {
+            bay_count: 1,
+            unload_rate: 20,
+        };
+        let mut docking = DockingManager::new(Position { x: 10, y: 10 }, &map, config);
+        let mut robots = vec![
+            robot_at(1, Position { x: 13, y: 10 }),
+            robot_at(2, Position { x: 10, y: 13 }),
+        ];
+        let bay = docking.request_bay(1).unwrap();
+        assert_eq!(docking.request_bay(2), None);
+
+        // Un robot à quai garde sa baie, même sans énergie
+        robots[0].position = bay;
+        robots[0].energy = 0.0;
+        for _ in 0..STALLED_RESERVATION_TICKS {
+            assert!(docking.drop_stalled_reservations(&robots).is_empty());
+        }
+
+        // Hors de sa baie, un robot à court d'énergie la libère aussitôt
+        robots[0].position = Position { x: 12, y: 10 };
+        assert_eq!(docking.drop_stalled_reservations(&robots), vec![1]);
+        assert_eq!(docking.assigned_bay(2), Some(bay));
+
+        // Un robot qui ne se rapproche plus finit par perdre sa baie
+        for _ in 0..STALLED_RESERVATION_TICKS {
+            assert!(docking.drop_stalled_reservations(&robots).is_empty());
+        }
+        assert_eq!(docking.drop_stalled_reservations(&robots), vec![2]);
+        assert_eq!(docking.occupied_bays(), 0);
+
+        // Un robot disparu est retiré de la file d'attente
+        docking.request_bay(1);
+        docking.request_bay(3);
+        assert_eq!(docking.drop_stalled_reservations(&robots), vec![3, 1]);
+        assert_eq!(docking.queue_len(), 0);
+    }
+}
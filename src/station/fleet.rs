@@ -0,0 +1,406 @@
+use crate::robot::{Position, Robot, State};
+use crate::station::communication::RobotMessage;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+/// Nombre de ticks entre deux signaux de vie d'un robot
+pub const HEARTBEAT_INTERVAL_TICKS: u64 = 10;
+/// Distance à laquelle un sauveteur repère un robot perdu
+pub const RESCUE_SEARCH_RADIUS: u32 = 5;
+/// Nombre d'anneaux de points de fouille autour de la dernière position connue
+const SEARCH_RINGS: u32 = 2;
+const SEARCH_DIRECTIONS: [(i32, i32); 8] = [
+    (0, -1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+    (0, 1),
+    (-1, 1),
+    (-1, 0),
+    (-1, -1),
+];
+
+/// Délais de silence avant de douter d'un robot, en ticks
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct LivenessConfig {
+    pub suspect_after_ticks: u64,
+    pub lost_after_ticks: u64,
+}
+
+impl Default for LivenessConfig {
+    fn default() -> Self {
+        Self {
+            suspect_after_ticks: 5 * HEARTBEAT_INTERVAL_TICKS,
+            lost_after_ticks: 15 * HEARTBEAT_INTERVAL_TICKS,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Liveness {
+    #[default]
+    Alive,
+    Suspected,
+    Lost,
+}
+
+impl Liveness {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Liveness::Alive => "alive",
+            Liveness::Suspected => "suspected",
+            Liveness::Lost => "lost",
+        }
+    }
+}
+
+/// Signal de vie périodique d'un robot. Il n'est pas acquitté : seul le plus
+/// récent compte.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Heartbeat {
+    pub tick: u64,
+    pub position: Position,
+    pub energy: f32,
+    pub state: State,
+}
+
+/// Dernier état connu d'un robot
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FleetEntry {
+    pub robot_id: u32,
+    /// Tick de la station au dernier message reçu
+    pub last_seen: u64,
+    pub last_heartbeat: Option<Heartbeat>,
+    pub position: Position,
+    pub energy: f32,
+    pub liveness: Liveness,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum FleetEvent {
+    Suspected(u32),
+    Lost(u32),
+    Recovered(u32),
+}
+
+/// Recherche à organiser pour un robot perdu, à sa dernière position connue
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct RecoveryTask {
+    pub robot_id: u32,
+    pub last_known_position: Position,
+    pub lost_at: u64,
+    /// Vrai si le robot était à court d'énergie : il ne reviendra pas seul
+    pub stranded: bool,
+    /// Robot envoyé à sa recherche
+    #[serde(default)]
+    pub rescuer: Option<u32>,
+    /// Nombre de points de fouille déjà visités
+    #[serde(default)]
+    pub search_step: u32,
+}
+
+impl RecoveryTask {
+    /// Point à fouiller : la dernière position connue, puis des anneaux de plus
+    /// en plus larges autour d'elle. `None` une fois la zone entièrement fouillée.
+    pub fn search_waypoint(&self, width: u32, height: u32) -> Option<Position> {
+        let Some(index) = self.search_step.checked_sub(1) else {
+            return Some(self.last_known_position);
+        };
+        let directions = SEARCH_DIRECTIONS.len() as u32;
+        let ring = index / directions + 1;
+        if ring > SEARCH_RINGS {
+            return None;
+        }
+
+        let (dx, dy) = SEARCH_DIRECTIONS[(index % directions) as usize];
+        let offset = (ring * RESCUE_SEARCH_RADIUS) as i32;
+        let shift = |value: u32, delta: i32, size: u32| {
+            (value as i32 + delta * offset).clamp(0, size as i32 - 1) as u32
+        };
+        Some(Position {
+            x: shift(self.last_known_position.x, dx, width),
+            y: shift(self.last_known_position.y, dy, height),
+        })
+    }
+}
+
+/// Table de la flotte tenue par la station : dernier contact et état de
+/// chaque robot, passant de vivant à suspect puis perdu faute de nouvelles
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FleetTable {
+    pub config: LivenessConfig,
+    entries: BTreeMap<u32, FleetEntry>,
+    recoveries: Vec<RecoveryTask>,
+    now: u64,
+}
+
+impl FleetTable {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Suit un robot dès sa construction, comme vu au dernier contrôle
+    pub fn register(&mut self, robot: &Robot) {
+        self.entries.insert(
+            robot.id,
+            FleetEntry {
+                robot_id: robot.id,
+                last_seen: self.now,
+                last_heartbeat: None,
+                position: robot.position,
+                energy: robot.energy,
+                liveness: Liveness::Alive,
+            },
+        );
+    }
+
+    /// Retire un robot de la table et abandonne sa recherche
+    pub fn unregister(&mut self, robot_id: u32) -> Option<FleetEntry> {
+        self.recoveries.retain(|task| task.robot_id != robot_id);
+        self.entries.remove(&robot_id)
+    }
+
+    /// Tout message reçu prouve que le robot est en vie. Un signal de vie
+    /// inscrit aussi un robot inconnu et met à jour sa position.
+    pub fn observe_message(
+        &mut self,
+        robot_id: u32,
+        now: u64,
+        message: &RobotMessage,
+    ) -> Option<FleetEvent> {
+        if let RobotMessage::Heartbeat(heartbeat) = message {
+            let entry = self.entries.entry(robot_id).or_insert_with(|| FleetEntry {
+                robot_id,
+                last_seen: now,
+                last_heartbeat: None,
+                position: heartbeat.position,
+                energy: heartbeat.energy,
+                liveness: Liveness::Alive,
+            });
+            // Un signal retardé ne remplace pas un état plus récent
+            if entry
+                .last_heartbeat
+                .as_ref()
+                .is_none_or(|last| last.tick < heartbeat.tick)
+            {
+                entry.position = heartbeat.position;
+                entry.energy = heartbeat.energy;
+                entry.last_heartbeat = Some(heartbeat.clone());
+            }
+        }
+
+        let entry = self.entries.get_mut(&robot_id)?;
+        entry.last_seen = entry.last_seen.max(now);
+        if entry.liveness == Liveness::Alive {
+            return None;
+        }
+
+        entry.liveness = Liveness::Alive;
+        self.recoveries.retain(|task| task.robot_id != robot_id);
+        Some(FleetEvent::Recovered(robot_id))
+    }
+
+    /// Déclasse les robots silencieux depuis trop longtemps. Une recherche est
+    /// programmée pour chaque robot perdu.
+    pub fn check(&mut self, now: u64) -> Vec<FleetEvent> {
+        self.now = now;
+        let mut events = Vec::new();
+
+        for entry in self.entries.values_mut() {
+            let silence = now.saturating_sub(entry.last_seen);
+            let liveness = if silence >= self.config.lost_after_ticks {
+                Liveness::Lost
+            } else if silence >= self.config.suspect_after_ticks {
+                Liveness::Suspected
+            } else {
+                Liveness::Alive
+            };
+            if liveness == entry.liveness {
+                continue;
+            }
+
+            entry.liveness = liveness;
+            match liveness {
+                Liveness::Suspected => events.push(FleetEvent::Suspected(entry.robot_id)),
+                Liveness::Lost => {
+                    events.push(FleetEvent::Lost(entry.robot_id));
+                    self.recoveries.push(RecoveryTask {
+                        robot_id: entry.robot_id,
+                        last_known_position: entry.position,
+                        lost_at: now,
+                        stranded: entry.energy <= 0.0,
+                        rescuer: None,
+                        search_step: 0,
+                    });
+                }
+                Liveness::Alive => {}
+            }
+        }
+
+        events
+    }
+
+    pub fn get(&self, robot_id: u32) -> Option<&FleetEntry> {
+        self.entries.get(&robot_id)
+    }
+
+    pub fn liveness(&self, robot_id: u32) -> Option<Liveness> {
+        self.get(robot_id).map(|entry| entry.liveness)
+    }
+
+    pub fn entries(&self) -> impl Iterator<Item = &FleetEntry> {
+        self.entries.values()
+    }
+
+    pub fn recoveries(&self) -> &[RecoveryTask] {
+        &self.recoveries
+    }
+
+    /// Confie la recherche d'un robot perdu à un autre robot, ou la libère
+    pub fn assign_rescuer(&mut self, robot_id: u32, rescuer: Option<u32>) {
+        for task in self.recoveries.iter_mut() {
+            if task.robot_id == robot_id {
+                task.rescuer = rescuer;
+            }
+        }
+    }
+
+    /// Passe au point de fouille suivant de la recherche d'un robot perdu
+    pub fn advance_search(&mut self, robot_id: u32) {
+        for task in self.recoveries.iter_mut() {
+            if task.robot_id == robot_id {
+                task.search_step += 1;
+            }
+        }
+    }
+
+    /// Recentre la recherche sur la position où le robot perdu a été aperçu
+    pub fn sight_lost_robot(&mut self, robot_id: u32, position: Position) {
+        for task in self.recoveries.iter_mut() {
+            if task.robot_id == robot_id {
+                task.last_known_position = position;
+                task.search_step = 0;
+            }
+        }
+    }
+
+    /// Clôt la recherche d'un robot retrouvé, qui reste perdu jusqu'à son
+    /// prochain message
+    pub fn close_recovery(&mut self, robot_id: u32) {
+        self.recoveries.retain(|task| task.robot_id != robot_id);
+    }
+
+    /// Nombre de robots vivants, suspects et perdus
+    pub fn counts(&self) -> (usize, usize, usize) {
+        let count = |liveness| {
+            self.entries
+                .values()
+                .filter(|entry| entry.liveness == liveness)
+                .count()
+        };
+        (
+            count(Liveness::Alive),
+            count(Liveness::Suspected),
+            count(Liveness::Lost),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn heartbeat(tick: u64, x: u32, energy: f32) -> RobotMessage {
+        RobotMessage::Heartbeat(Heartbeat {
+            tick,
+            position: Position { x, y: 0 },
+            energy,
+            state: State::Idle,
+        })
+    }
+
+    #[test]
+    fn test_silent_robots_become_suspected_then_lost() {
+        let mut fleet = FleetTable::new();
+        let config = fleet.config;
+        assert_eq!(fleet.observe_message(1, 0, &heartbeat(0, 4, 0.0)), None);
+        assert_eq!(fleet.observe_message(2, 0, &heartbeat(0, 9, 50.0)), None);
+
+        assert!(fleet.check(config.suspect_after_ticks - 1).is_empty());
+        fleet.observe_message(2, config.suspect_after_ticks - 1, &heartbeat(1, 9, 50.0));
+        assert_eq!(
+            fleet.check(config.suspect_after_ticks),
+            vec![FleetEvent::Suspected(1)]
+        );
+        assert_eq!(
+            fleet.check(config.lost_after_ticks),
+            vec![FleetEvent::Lost(1), FleetEvent::Suspected(2)]
+        );
+        assert_eq!(fleet.counts(), (0, 1, 1));
+
+        let recovery = &fleet.recoveries()[0];
+        assert_eq!(recovery.robot_id, 1);
+        assert_eq!(recovery.last_known_position, Position { x: 4, y: 0 });
+        assert!(recovery.stranded);
+    }
+
+    #[test]
+    fn test_contact_recovers_a_lost_robot() {
+        let mut fleet = FleetTable::new();
+        let lost_after = fleet.config.lost_after_ticks;
+        fleet.observe_message(1, 0, &heartbeat(5, 4, 30.0));
+        fleet.check(lost_after);
+        assert_eq!(fleet.liveness(1), Some(Liveness::Lost));
+
+        // Un signal plus ancien que le dernier connu prouve la vie sans changer la position
+        let event = fleet.observe_message(1, lost_after + 1, &heartbeat(2, 8, 10.0));
+        assert_eq!(event, Some(FleetEvent::Recovered(1)));
+        assert_eq!(fleet.get(1).unwrap().position, Position { x: 4, y: 0 });
+        assert!(fleet.recoveries().is_empty());
+        assert!(fleet.check(lost_after + 2).is_empty());
+
+        assert!(fleet.unregister(1).is_some());
+        assert_eq!(
+            fleet.observe_message(1, lost_after + 3, &RobotMessage::RequestResourcesState),
+            None
+        );
+        assert!(fleet.get(1).is_none());
+    }
+
+    #[test]
+    fn test_search_widens_around_the_last_known_position() {
+        let mut fleet = FleetTable::new();
+        fleet.observe_message(1, 0, &heartbeat(5, 2, 0.0));
+        fleet.check(fleet.config.lost_after_ticks);
+
+        let task = |fleet: &FleetTable| fleet.recoveries()[0].clone();
+        assert_eq!(
+            task(&fleet).search_waypoint(50, 50),
+            Some(Position { x: 2, y: 0 })
+        );
+
+        // Les points de fouille restent sur la carte
+        let mut waypoints = Vec::new();
+        while let Some(waypoint) = task(&fleet).search_waypoint(50, 50) {
+            waypoints.push(waypoint);
+            fleet.advance_search(1);
+        }
+        assert_eq!(
+            waypoints.len(),
+            1 + SEARCH_RINGS as usize * SEARCH_DIRECTIONS.len()
+        );
+        assert!(waypoints.contains(&Position {
+            x: 2 + 2 * RESCUE_SEARCH_RADIUS,
+            y: 2 * RESCUE_SEARCH_RADIUS
+        }));
+        assert!(waypoints
+            .iter()
+            .all(|waypoint| waypoint.x < 50 && waypoint.y < 50));
+
+        fleet.sight_lost_robot(1, Position { x: 7, y: 3 });
+        assert_eq!(
+            task(&fleet).search_waypoint(50, 50),
+            Some(Position { x: 7, y: 3 })
+        );
+    }
+}
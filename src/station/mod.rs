@@ -1,13 +1,29 @@
 #[allow(dead_code)]
 pub mod communication;
+pub mod conflict;
+pub mod crdt;
+pub mod docking;
+pub mod fleet;
 pub mod production;
+pub mod research;
 pub mod resources;
 pub mod sync;
+pub mod workshop;
 
 use crate::map::{Map, Tile};
+use crate::map::pathfinding::UnknownTilePolicy;
 use crate::robot::resources::ResourceType;
+use crate::robot::cargo::CargoPolicy;
+use crate::robot::energy::EnergyModel;
 use crate::robot::{HardwareModule, Position, Resources, Robot};
 use serde::{Deserialize, Serialize};
+use docking::{DockingConfig, DockingManager};
+use fleet::FleetTable;
+use production::{BuildError, ProductionQueue};
+use resources::{ResourcesHistory, STOCK_SAMPLE_INTERVAL_TICKS};
+use research::ResearchLab;
+use sync::MapKnowledge;
+use workshop::WorkshopCosts;
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct DiscoveredResources {
@@ -25,6 +41,24 @@ pub struct Station {
     pub max_robots: usize,
     pub production_costs: ProductionCosts,
     pub discovered_resources: DiscoveredResources,
+    pub module_storage: Vec<HardwareModule>,
+    pub workshop_costs: WorkshopCosts,
+    pub research: ResearchLab,
+    pub scanner_range_bonus: u32,
+    pub production_queue: ProductionQueue,
+    pub energy_model: EnergyModel,
+    pub docking: DockingManager,
+    pub cargo_policy: CargoPolicy,
+    pub knowledge: MapKnowledge,
+    pub unknown_tile_policy: UnknownTilePolicy,
+    pub fleet: FleetTable,
+    /// Ressources collectées, dépensées et stockées au fil des ticks. Propre à
+    /// la station : il n'est pas transmis aux robots distants.
+    #[serde(skip)]
+    pub history: ResourcesHistory,
+    /// Tick de simulation courant, utilisé pour dater l'historique
+    pub clock: u64,
+    next_robot_id: u32,
 }
 
 fn find_nearby_empty_position(map: &Map, center: Position) -> Position {
@@ -79,6 +113,18 @@ pub struct ProductionCosts {
     pub scientist: (u32, u32),
 }
 
+impl ProductionCosts {
+    /// Coût d'un robot, exprimé en (energy, minerals)
+    pub fn for_robot(&self, robot_type: RobotType) -> (u32, u32) {
+        match robot_type {
+            RobotType::Explorer => self.explorer,
+            RobotType::EnergyCollector => self.energy_collector,
+            RobotType::Miner => self.miner,
+            RobotType::Scientist => self.scientist,
+        }
+    }
+}
+
 // / Implémentation de la station
 impl Station {
     pub fn new(global_map: &mut Map) -> Self {
@@ -109,68 +155,174 @@ impl Station {
                 scientist: (250, 150),
             },
             discovered_resources: DiscoveredResources::default(),
+            module_storage: Vec::new(),
+            workshop_costs: WorkshopCosts::default(),
+            research: ResearchLab::new(),
+            scanner_range_bonus: 0,
+            production_queue: ProductionQueue::new(),
+            energy_model: EnergyModel::default(),
+            docking: DockingManager::new(pos, global_map, DockingConfig::default()),
+            cargo_policy: CargoPolicy::default(),
+            knowledge: MapKnowledge::new(),
+            unknown_tile_policy: UnknownTilePolicy::default(),
+            fleet: FleetTable::new(),
+            history: ResourcesHistory::new(),
+            clock: 0,
+            next_robot_id: 1,
         }
     }
 
     pub fn update(&mut self, _global_map: &Map) {
         self.sync_with_returned_robots();
+        self.update_research();
+        if self.clock.is_multiple_of(STOCK_SAMPLE_INTERVAL_TICKS) {
+            self.history.record_stock(self.clock, &self.resources);
+        }
     }
 
     fn sync_with_returned_robots(&mut self) {
     }
 
-    pub fn collect_robot_resources(&mut self, robot: &mut Robot) {
-        self.resources.energy += robot.inventory.energy;
-        self.resources.minerals += robot.inventory.minerals;
-        self.resources.scientific_data += robot.inventory.scientific_data;
+    /// Reconstruit les baies d'amarrage avec une nouvelle configuration
+    pub fn configure_docking(&mut self, map: &Map, config: DockingConfig) {
+        self.docking = DockingManager::new(self.position, map, config);
+    }
 
-        robot.inventory.energy = 0;
-        robot.inventory.minerals = 0;
-        robot.inventory.scientific_data = 0;
+    /// Fusionne les cases modifiées par un robot amarré dans la carte de la station,
+    /// puis lui renvoie uniquement les changements depuis sa dernière synchronisation.
+    /// Retourne les positions nouvellement connues.
+    pub fn sync_robot_knowledge(&mut self, robot: &mut Robot) -> Vec<Position> {
+        let last_seen_version = robot.knowledge.version;
+        let robot_tiles = robot.knowledge.take_local_changes();
+        let updated = self.knowledge.merge_robot_knowledge(robot_tiles, robot.id);
+        robot
+            .knowledge
+            .apply_sync(self.knowledge.delta_since(last_seen_version));
+        updated
     }
 
-    pub fn can_create_robot(&self, robot_type: RobotType) -> bool {
-        match robot_type {
-            RobotType::Explorer => {
-                let (energy_cost, mineral_cost) = self.production_costs.explorer;
-                self.resources.energy >= energy_cost && self.resources.minerals >= mineral_cost
-            }
-            RobotType::EnergyCollector => {
-                let (energy_cost, mineral_cost) = self.production_costs.energy_collector;
-                self.resources.energy >= energy_cost && self.resources.minerals >= mineral_cost
-            }
-            RobotType::Miner => {
-                let (energy_cost, mineral_cost) = self.production_costs.miner;
-                self.resources.energy >= energy_cost && self.resources.minerals >= mineral_cost
-            }
-            RobotType::Scientist => {
-                let (energy_cost, mineral_cost) = self.production_costs.scientist;
-                self.resources.energy >= energy_cost && self.resources.minerals >= mineral_cost
-            }
+    /// Recharge un robot amarré au rythme du modèle d'énergie.
+    /// Retourne vrai quand le robot peut repartir.
+    pub fn recharge_robot(&mut self, robot: &mut Robot) -> bool {
+        let energy_needed = (robot.battery_capacity - robot.energy).max(0.0);
+        let available_energy = self.resources.energy as f32;
+        let energy_given = energy_needed
+            .min(self.energy_model.recharge_rate)
+            .min(available_energy)
+            .floor();
+
+        robot.energy += self.draw_energy(energy_given as u32) as f32;
+
+        let fully_charged = robot.energy >= robot.battery_capacity - 1.0;
+        let station_depleted = energy_given <= 0.0 && energy_needed > 0.0;
+        fully_charged
+            || (station_depleted && robot.energy >= self.energy_model.min_departure_energy)
+    }
+
+    /// Décharge l'inventaire d'un robot amarré au rythme des baies.
+    /// Retourne vrai quand l'inventaire est vide.
+    pub fn collect_robot_resources(&mut self, robot: &mut Robot) -> bool {
+        let mut budget = self.docking.config.unload_rate;
+        let mut unloaded = Resources {
+            energy: 0,
+            minerals: 0,
+            scientific_data: 0,
+        };
+
+        for (from, to) in [
+            (&mut robot.inventory.minerals, &mut unloaded.minerals),
+            (&mut robot.inventory.energy, &mut unloaded.energy),
+            (
+                &mut robot.inventory.scientific_data,
+                &mut unloaded.scientific_data,
+            ),
+        ] {
+            let amount = (*from).min(budget);
+            *from -= amount;
+            *to = amount;
+            budget -= amount;
         }
+        self.receive_cargo(&unloaded);
+
+        robot.inventory.is_empty()
     }
 
-    /// Crée un nouveau robot si les ressources sont suffisantes
-    pub fn create_robot(&mut self, robot_type: RobotType) -> Option<Robot> {
-        if self.robots.len() >= self.max_robots {
-            return None;
+    /// Crédite le stock d'un chargement déchargé à quai
+    pub fn receive_cargo(&mut self, cargo: &Resources) {
+        self.resources.energy += cargo.energy;
+        self.resources.minerals += cargo.minerals;
+        self.resources.scientific_data += cargo.scientific_data;
+
+        if cargo.minerals > 0 {
+            self.history.add_minerals_collected(self.clock, cargo.minerals);
+        }
+        if cargo.energy > 0 {
+            self.history.add_energy_collected(self.clock, cargo.energy);
         }
+        if cargo.scientific_data > 0 {
+            self.history
+                .add_scientific_data_collected(self.clock, cargo.scientific_data);
+        }
+    }
 
+    /// Prélève de l'énergie sur le stock, dans la limite disponible.
+    /// Retourne la quantité effectivement prélevée.
+    pub fn draw_energy(&mut self, amount: u32) -> u32 {
+        let drawn = amount.min(self.resources.energy);
+        self.resources.energy -= drawn;
+        if drawn > 0 {
+            self.history.add_resources_spent(
+                self.clock,
+                Resources {
+                    energy: drawn,
+                    minerals: 0,
+                    scientific_data: 0,
+                },
+            );
+        }
+        drawn
+    }
+
+    pub fn can_create_robot(&self, robot_type: RobotType) -> bool {
+        let (energy_cost, mineral_cost) = self.production_costs.for_robot(robot_type);
+        self.resources.energy >= energy_cost && self.resources.minerals >= mineral_cost
+    }
+
+    /// Vérifie qu'un robot peut être commandé, en comptant ceux déjà en production
+    pub fn check_build(&self, robot_type: RobotType) -> Result<(), BuildError> {
+        if self.robots.len() + self.production_queue.pending() >= self.max_robots {
+            return Err(BuildError::MaxRobotsReached(self.max_robots));
+        }
         if !self.can_create_robot(robot_type) {
-            return None;
+            let (energy, minerals) = self.production_costs.for_robot(robot_type);
+            return Err(BuildError::InsufficientResources { energy, minerals });
         }
+        Ok(())
+    }
 
-        let modules = self.get_modules_for_robot_type(robot_type);
+    /// Crée un nouveau robot si les ressources sont suffisantes
+    pub fn create_robot(&mut self, robot_type: RobotType) -> Option<Robot> {
+        self.try_create_robot(robot_type).ok()
+    }
 
+    /// Crée immédiatement un robot, ou indique pourquoi c'est impossible
+    pub fn try_create_robot(&mut self, robot_type: RobotType) -> Result<Robot, BuildError> {
+        self.check_build(robot_type)?;
         self.consume_resources_for_robot(robot_type);
+        Ok(self.assemble_robot(robot_type))
+    }
 
-        let energy_cost = match robot_type {
-            RobotType::Explorer => self.production_costs.explorer.0,
-            RobotType::EnergyCollector => self.production_costs.energy_collector.0,
-            RobotType::Miner => self.production_costs.miner.0,
-            RobotType::Scientist => self.production_costs.scientist.0,
-        };
+    /// Paie un robot et le place dans la file de production
+    pub fn queue_robot(&mut self, robot_type: RobotType) -> Result<(), BuildError> {
+        self.check_build(robot_type)?;
+        self.consume_resources_for_robot(robot_type);
+        self.production_queue.enqueue(robot_type);
+        Ok(())
+    }
 
+    /// Assemble un robot déjà payé, à côté de la station
+    pub fn assemble_robot(&mut self, robot_type: RobotType) -> Robot {
+        let modules = self.get_modules_for_robot_type(robot_type);
         let mut robot = Robot::new(
             Position {
                 x: self.position.x.saturating_add(1),
@@ -179,10 +331,15 @@ impl Station {
             modules,
         );
 
-        robot.energy = energy_cost as f32;
+        robot.id = self.next_robot_id;
+        self.next_robot_id += 1;
+        robot.knowledge.apply_sync(self.knowledge.delta_since(0));
+        robot.battery_capacity = self.energy_model.battery_capacity(robot_type);
+        robot.energy = robot.battery_capacity;
 
+        self.fleet.register(&robot);
         self.robots.push(robot.clone());
-        Some(robot)
+        robot
     }
 
 
@@ -191,22 +348,24 @@ impl Station {
         match robot_type {
             RobotType::Explorer => vec![HardwareModule::TerrainScanner {
                 efficiency: 0.9,
-                range: 20,
+                range: 20 + self.scanner_range_bonus,
             }],
             RobotType::EnergyCollector => vec![
                 HardwareModule::EnergyHarvester {
                     collection_rate: 2.0,
                 },
+                HardwareModule::CargoHold { capacity: 100 },
                 HardwareModule::TerrainScanner {
                     efficiency: 0.6,
-                    range: 10,
+                    range: 10 + self.scanner_range_bonus,
                 },
             ],
             RobotType::Miner => vec![
                 HardwareModule::DeepDrill { mining_speed: 2.0 },
+                HardwareModule::CargoHold { capacity: 100 },
                 HardwareModule::TerrainScanner {
                     efficiency: 0.6,
-                    range: 10,
+                    range: 10 + self.scanner_range_bonus,
                 },
             ],
             RobotType::Scientist => vec![
@@ -215,7 +374,7 @@ impl Station {
                 },
                 HardwareModule::TerrainScanner {
                     efficiency: 0.7,
-                    range: 15,
+                    range: 15 + self.scanner_range_bonus,
                 },
             ],
         }
@@ -223,28 +382,17 @@ impl Station {
 
     /// Consomme les ressources nécessaires pour créer un robot
     fn consume_resources_for_robot(&mut self, robot_type: RobotType) {
-        match robot_type {
-            RobotType::Explorer => {
-                let (energy_cost, mineral_cost) = self.production_costs.explorer;
-                self.resources.energy -= energy_cost;
-                self.resources.minerals -= mineral_cost;
-            }
-            RobotType::EnergyCollector => {
-                let (energy_cost, mineral_cost) = self.production_costs.energy_collector;
-                self.resources.energy -= energy_cost;
-                self.resources.minerals -= mineral_cost;
-            }
-            RobotType::Miner => {
-                let (energy_cost, mineral_cost) = self.production_costs.miner;
-                self.resources.energy -= energy_cost;
-                self.resources.minerals -= mineral_cost;
-            }
-            RobotType::Scientist => {
-                let (energy_cost, mineral_cost) = self.production_costs.scientist;
-                self.resources.energy -= energy_cost;
-                self.resources.minerals -= mineral_cost;
-            }
-        }
+        let (energy_cost, mineral_cost) = self.production_costs.for_robot(robot_type);
+        self.resources.energy -= energy_cost;
+        self.resources.minerals -= mineral_cost;
+        self.history.add_resources_used_for_production(
+            self.clock,
+            Resources {
+                energy: energy_cost,
+                minerals: mineral_cost,
+                scientific_data: 0,
+            },
+        );
     }
 
     // Méthode pour signaler une ressource découverte
@@ -299,3 +447,26 @@ pub enum RobotType {
     Miner,           
     Scientist,       
 }
+
+impl RobotType {
+    pub const ALL: [RobotType; 4] = [
+        RobotType::Explorer,
+        RobotType::EnergyCollector,
+        RobotType::Miner,
+        RobotType::Scientist,
+    ];
+
+    /// Nom utilisé en ligne de commande
+    pub fn name(&self) -> &'static str {
+        match self {
+            RobotType::Explorer => "explorer",
+            RobotType::EnergyCollector => "energy-collector",
+            RobotType::Miner => "miner",
+            RobotType::Scientist => "scientist",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<RobotType> {
+        Self::ALL.into_iter().find(|robot_type| robot_type.name() == name)
+    }
+}
@@ -2,6 +2,26 @@
 use crate::robot::{HardwareModule, Position, Robot};
 use crate::station::RobotType;
 use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Raison pour laquelle la station refuse de construire un robot
+#[derive(Debug, Clone, PartialEq)]
+pub enum BuildError {
+    /// La flotte, robots en production compris, a atteint sa taille maximale
+    MaxRobotsReached(usize),
+    InsufficientResources { energy: u32, minerals: u32 },
+}
+
+impl fmt::Display for BuildError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BuildError::MaxRobotsReached(max) => write!(f, "maximum of {max} robots reached"),
+            BuildError::InsufficientResources { energy, minerals } => {
+                write!(f, "needs {energy} energy and {minerals} minerals")
+            }
+        }
+    }
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ProductionQueue {
@@ -19,6 +39,12 @@ pub struct ProductionTime {
     pub scientist: u32,
 }
 
+impl Default for ProductionQueue {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ProductionQueue {
     pub fn new() -> Self {
         Self {
@@ -59,7 +85,21 @@ impl ProductionQueue {
         None
     }
 
-    fn get_production_time(&self, robot_type: RobotType) -> u32 {
+    /// Réduit tous les temps de production d'un pourcentage donné
+    pub fn reduce_production_time(&mut self, percent: u32) {
+        let factor = 100 - percent.min(100);
+        let times = &mut self.production_time;
+        for time in [
+            &mut times.explorer,
+            &mut times.energy_collector,
+            &mut times.miner,
+            &mut times.scientist,
+        ] {
+            *time = *time * factor / 100;
+        }
+    }
+
+    pub fn get_production_time(&self, robot_type: RobotType) -> u32 {
         match robot_type {
             RobotType::Explorer => self.production_time.explorer,
             RobotType::EnergyCollector => self.production_time.energy_collector,
@@ -72,6 +112,20 @@ impl ProductionQueue {
         self.queue.len()
     }
 
+    /// Robots en attente, dans l'ordre où ils seront produits
+    pub fn queued(&self) -> &[RobotType] {
+        &self.queue
+    }
+
+    pub fn current_production(&self) -> Option<RobotType> {
+        self.current_production
+    }
+
+    /// Robots commandés et pas encore livrés, celui en production compris
+    pub fn pending(&self) -> usize {
+        self.queue.len() + usize::from(self.current_production.is_some())
+    }
+
     pub fn production_progress(&self) -> Option<f32> {
         if let (Some(robot_type), Some(time_left)) =
             (self.current_production, self.current_production_time_left)
@@ -84,3 +138,59 @@ impl ProductionQueue {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::Map;
+    use crate::station::Station;
+
+    #[test]
+    fn test_queued_builds_are_paid_upfront_and_count_towards_the_limit() {
+        let mut map = Map::new(50, 50, 42);
+        let mut station = Station::new(&mut map);
+        station.max_robots = 2;
+        station.resources.energy = 1000;
+        station.resources.minerals = 1000;
+
+        let (energy, minerals) = station.production_costs.for_robot(RobotType::Miner);
+        station.queue_robot(RobotType::Miner).unwrap();
+        assert_eq!(station.resources.energy, 1000 - energy);
+        assert_eq!(station.resources.minerals, 1000 - minerals);
+
+        station.queue_robot(RobotType::Explorer).unwrap();
+        assert_eq!(station.production_queue.pending(), 2);
+        assert_eq!(
+            station.check_build(RobotType::Explorer),
+            Err(BuildError::MaxRobotsReached(2))
+        );
+
+        // Le robot en production compte encore tant qu'il n'est pas livré
+        assert_eq!(station.production_queue.update(), None);
+        assert_eq!(
+            station.production_queue.current_production(),
+            Some(RobotType::Miner)
+        );
+        assert_eq!(station.production_queue.queued(), &[RobotType::Explorer]);
+        assert_eq!(station.production_queue.pending(), 2);
+    }
+
+    #[test]
+    fn test_build_refusal_reports_missing_resources() {
+        let mut map = Map::new(50, 50, 42);
+        let mut station = Station::new(&mut map);
+        station.resources.energy = 0;
+
+        let (energy, minerals) = station.production_costs.for_robot(RobotType::Scientist);
+        let error = station.try_create_robot(RobotType::Scientist).unwrap_err();
+        assert_eq!(
+            error,
+            BuildError::InsufficientResources { energy, minerals }
+        );
+        assert_eq!(
+            error.to_string(),
+            format!("needs {energy} energy and {minerals} minerals")
+        );
+        assert!(station.robots.is_empty());
+    }
+}
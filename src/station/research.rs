@@ -0,0 +1,303 @@
+use crate::robot::Resources;
+use crate::station::Station;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum ResearchTopic {
+    ImprovedOptics,
+    LongRangeOptics,
+    LeanManufacturing,
+    ExpandedHangar,
+    AutomatedAssembly,
+    SignalRelays,
+}
+
+impl ResearchTopic {
+    pub const ALL: [ResearchTopic; 6] = [
+        ResearchTopic::ImprovedOptics,
+        ResearchTopic::LongRangeOptics,
+        ResearchTopic::LeanManufacturing,
+        ResearchTopic::ExpandedHangar,
+        ResearchTopic::AutomatedAssembly,
+        ResearchTopic::SignalRelays,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            ResearchTopic::ImprovedOptics => "Improved Optics",
+            ResearchTopic::LongRangeOptics => "Long Range Optics",
+            ResearchTopic::LeanManufacturing => "Lean Manufacturing",
+            ResearchTopic::ExpandedHangar => "Expanded Hangar",
+            ResearchTopic::AutomatedAssembly => "Automated Assembly",
+            ResearchTopic::SignalRelays => "Signal Relays",
+        }
+    }
+
+    pub fn description(&self) -> &'static str {
+        match self {
+            ResearchTopic::ImprovedOptics => "+5 range on new terrain scanners",
+            ResearchTopic::LongRangeOptics => "+10 range on new terrain scanners",
+            ResearchTopic::LeanManufacturing => "-20% robot production costs",
+            ResearchTopic::ExpandedHangar => "+5 maximum robots",
+            ResearchTopic::AutomatedAssembly => "-30% robot production time",
+            ResearchTopic::SignalRelays => "Unlocks the signal relay module",
+        }
+    }
+
+    /// Coût en données scientifiques
+    pub fn cost(&self) -> u32 {
+        match self {
+            ResearchTopic::ImprovedOptics => 200,
+            ResearchTopic::LongRangeOptics => 600,
+            ResearchTopic::LeanManufacturing => 300,
+            ResearchTopic::ExpandedHangar => 400,
+            ResearchTopic::AutomatedAssembly => 500,
+            ResearchTopic::SignalRelays => 450,
+        }
+    }
+
+    /// Durée de la recherche en ticks de simulation
+    pub fn duration(&self) -> u32 {
+        match self {
+            ResearchTopic::ImprovedOptics => 100,
+            ResearchTopic::LongRangeOptics => 250,
+            ResearchTopic::LeanManufacturing => 150,
+            ResearchTopic::ExpandedHangar => 200,
+            ResearchTopic::AutomatedAssembly => 250,
+            ResearchTopic::SignalRelays => 200,
+        }
+    }
+
+    pub fn prerequisites(&self) -> &'static [ResearchTopic] {
+        match self {
+            ResearchTopic::ImprovedOptics
+            | ResearchTopic::LeanManufacturing
+            | ResearchTopic::ExpandedHangar => &[],
+            ResearchTopic::LongRangeOptics | ResearchTopic::SignalRelays => {
+                &[ResearchTopic::ImprovedOptics]
+            }
+            ResearchTopic::AutomatedAssembly => &[ResearchTopic::LeanManufacturing],
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ResearchError {
+    AlreadyCompleted,
+    MissingPrerequisite(ResearchTopic),
+    LabBusy,
+    InsufficientScientificData { required: u32 },
+}
+
+impl fmt::Display for ResearchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ResearchError::AlreadyCompleted => write!(f, "already researched"),
+            ResearchError::MissingPrerequisite(topic) => write!(f, "requires {}", topic.name()),
+            ResearchError::LabBusy => write!(f, "another research is in progress"),
+            ResearchError::InsufficientScientificData { required } => {
+                write!(f, "needs {required} scientific data")
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResearchLab {
+    completed: Vec<ResearchTopic>,
+    current: Option<ResearchTopic>,
+    time_left: u32,
+}
+
+impl ResearchLab {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn is_completed(&self, topic: ResearchTopic) -> bool {
+        self.completed.contains(&topic)
+    }
+
+    pub fn current(&self) -> Option<ResearchTopic> {
+        self.current
+    }
+
+    pub fn check_can_start(&self, topic: ResearchTopic) -> Result<(), ResearchError> {
+        if self.is_completed(topic) {
+            return Err(ResearchError::AlreadyCompleted);
+        }
+        if self.current.is_some() {
+            return Err(ResearchError::LabBusy);
+        }
+        if let Some(missing) = topic
+            .prerequisites()
+            .iter()
+            .find(|prerequisite| !self.is_completed(**prerequisite))
+        {
+            return Err(ResearchError::MissingPrerequisite(*missing));
+        }
+        Ok(())
+    }
+
+    /// Fait avancer la recherche en cours et retourne le sujet terminé s'il y en a un
+    pub fn update(&mut self) -> Option<ResearchTopic> {
+        let topic = self.current?;
+
+        self.time_left = self.time_left.saturating_sub(1);
+        if self.time_left == 0 {
+            self.current = None;
+            self.completed.push(topic);
+            return Some(topic);
+        }
+
+        None
+    }
+
+    pub fn progress(&self) -> Option<f32> {
+        self.current.map(|topic| {
+            let total_time = topic.duration() as f32;
+            (total_time - self.time_left as f32) / total_time
+        })
+    }
+}
+
+// / Recherche de la station, financée par les données scientifiques
+impl Station {
+    pub fn start_research(&mut self, topic: ResearchTopic) -> Result<(), ResearchError> {
+        self.research.check_can_start(topic)?;
+
+        if self.resources.scientific_data < topic.cost() {
+            return Err(ResearchError::InsufficientScientificData {
+                required: topic.cost(),
+            });
+        }
+
+        self.resources.scientific_data -= topic.cost();
+        self.history.add_resources_spent(
+            self.clock,
+            Resources {
+                energy: 0,
+                minerals: 0,
+                scientific_data: topic.cost(),
+            },
+        );
+        self.research.current = Some(topic);
+        self.research.time_left = topic.duration();
+        Ok(())
+    }
+
+    /// Fait avancer la recherche et applique ses effets une fois terminée
+    pub fn update_research(&mut self) -> Option<ResearchTopic> {
+        let topic = self.research.update()?;
+        self.apply_research(topic);
+        Some(topic)
+    }
+
+    fn apply_research(&mut self, topic: ResearchTopic) {
+        match topic {
+            ResearchTopic::ImprovedOptics => self.scanner_range_bonus += 5,
+            ResearchTopic::LongRangeOptics => self.scanner_range_bonus += 10,
+            ResearchTopic::LeanManufacturing => {
+                let costs = &mut self.production_costs;
+                for cost in [
+                    &mut costs.explorer,
+                    &mut costs.energy_collector,
+                    &mut costs.miner,
+                    &mut costs.scientist,
+                ] {
+                    cost.0 = cost.0 * 4 / 5;
+                    cost.1 = cost.1 * 4 / 5;
+                }
+            }
+            ResearchTopic::ExpandedHangar => self.max_robots += 5,
+            ResearchTopic::AutomatedAssembly => self.production_queue.reduce_production_time(30),
+            ResearchTopic::SignalRelays => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::Map;
+    use crate::robot::HardwareModule;
+    use crate::station::RobotType;
+
+    fn complete_research(station: &mut Station, topic: ResearchTopic) {
+        station.resources.scientific_data += topic.cost();
+        station.start_research(topic).unwrap();
+        for _ in 0..topic.duration() {
+            station.update_research();
+        }
+        assert!(station.research.is_completed(topic));
+    }
+
+    #[test]
+    fn test_research_costs_scientific_data_and_time() {
+        let mut map = Map::new(50, 50, 42);
+        let mut station = Station::new(&mut map);
+        let topic = ResearchTopic::ExpandedHangar;
+
+        assert_eq!(
+            station.start_research(topic),
+            Err(ResearchError::InsufficientScientificData {
+                required: topic.cost()
+            })
+        );
+
+        station.resources.scientific_data = topic.cost();
+        station.start_research(topic).unwrap();
+        assert_eq!(station.resources.scientific_data, 0);
+        assert_eq!(station.history.total_spent().scientific_data, topic.cost());
+        assert_eq!(station.research.progress(), Some(0.0));
+
+        for _ in 0..topic.duration() - 1 {
+            assert_eq!(station.update_research(), None);
+        }
+        assert_eq!(station.max_robots, 10);
+        assert_eq!(station.update_research(), Some(topic));
+        assert_eq!(station.max_robots, 15);
+        assert_eq!(station.research.progress(), None);
+    }
+
+    #[test]
+    fn test_research_requires_prerequisites() {
+        let mut map = Map::new(50, 50, 42);
+        let mut station = Station::new(&mut map);
+        station.resources.scientific_data = 10000;
+
+        assert_eq!(
+            station.start_research(ResearchTopic::LongRangeOptics),
+            Err(ResearchError::MissingPrerequisite(
+                ResearchTopic::ImprovedOptics
+            ))
+        );
+
+        complete_research(&mut station, ResearchTopic::ImprovedOptics);
+        assert!(station.start_research(ResearchTopic::LongRangeOptics).is_ok());
+        assert_eq!(
+            station.start_research(ResearchTopic::ExpandedHangar),
+            Err(ResearchError::LabBusy)
+        );
+    }
+
+    #[test]
+    fn test_research_effects() {
+        let mut map = Map::new(50, 50, 42);
+        let mut station = Station::new(&mut map);
+
+        complete_research(&mut station, ResearchTopic::ImprovedOptics);
+        let modules = station.get_modules_for_robot_type(RobotType::Explorer);
+        assert_eq!(
+            modules[0],
+            HardwareModule::TerrainScanner {
+                efficiency: 0.9,
+                range: 25,
+            }
+        );
+
+        complete_research(&mut station, ResearchTopic::LeanManufacturing);
+        assert_eq!(station.production_costs.explorer, (16, 80));
+    }
+}
@@ -1,75 +1,249 @@
 #[allow(dead_code)]
 use crate::robot::Resources;
 use serde::{Deserialize, Serialize};
+use std::collections::VecDeque;
+
+/// Ticks entre deux relevés du stock de la station
+pub const STOCK_SAMPLE_INTERVAL_TICKS: u64 = 10;
+/// Nombre de relevés du stock conservés
+pub const STOCK_HISTORY_CAPACITY: usize = 2000;
+/// Largeur des fenêtres dans lesquelles collectes et dépenses sont cumulées, en ticks
+pub const FLOW_WINDOW_TICKS: u64 = 10;
+/// Nombre de fenêtres de collectes et de dépenses conservées
+pub const FLOW_HISTORY_CAPACITY: usize = 1000;
+
+/// Mouvements de ressources cumulés par fenêtre de `FLOW_WINDOW_TICKS`, les
+/// plus anciennes fenêtres oubliées, et leur total depuis le début
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FlowSeries {
+    /// Premier tick de chaque fenêtre et quantités cumulées
+    windows: VecDeque<(u64, Resources)>,
+    total: Resources,
+}
+
+impl FlowSeries {
+    fn add(&mut self, timestamp: u64, amount: &Resources) {
+        let start = timestamp - timestamp % FLOW_WINDOW_TICKS;
+        match self.windows.back_mut() {
+            // Un horodatage en retard est compté dans la dernière fenêtre
+            Some((window, resources)) if *window >= start => add_to(resources, amount),
+            _ => {
+                if self.windows.len() == FLOW_HISTORY_CAPACITY {
+                    self.windows.pop_front();
+                }
+                self.windows.push_back((start, amount.clone()));
+            }
+        }
+        add_to(&mut self.total, amount);
+    }
+
+    pub fn total(&self) -> &Resources {
+        &self.total
+    }
+
+    pub fn len(&self) -> usize {
+        self.windows.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.windows.is_empty()
+    }
+
+    /// Quantités des `count` dernières fenêtres, celle de `now` comprise, de
+    /// la plus ancienne à la plus récente. Seules ces fenêtres sont parcourues.
+    pub fn per_window(&self, count: usize, now: u64) -> Vec<Resources> {
+        let mut buckets = vec![Resources::default(); count];
+        let current = now / FLOW_WINDOW_TICKS;
+        for (start, resources) in self.windows.iter().rev() {
+            let Some(age) = current.checked_sub(start / FLOW_WINDOW_TICKS) else {
+                continue;
+            };
+            if age >= count as u64 {
+                break;
+            }
+            add_to(&mut buckets[count - 1 - age as usize], resources);
+        }
+        buckets
+    }
+}
+
+fn add_to(total: &mut Resources, amount: &Resources) {
+    total.energy += amount.energy;
+    total.minerals += amount.minerals;
+    total.scientific_data += amount.scientific_data;
+}
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ResourcesHistory {
-    pub energy_collected: Vec<(u64, u32)>,
-    pub minerals_collected: Vec<(u64, u32)>,
-    pub scientific_data_collected: Vec<(u64, u32)>,
-    pub resources_used_for_production: Vec<(u64, Resources)>,
+    pub collected: FlowSeries,
+    pub used_for_production: FlowSeries,
+    /// Autres dépenses de la station : recherche, atelier et recharge des robots
+    pub spent: FlowSeries,
+    /// Stock de la station relevé à intervalle régulier, les plus anciens oubliés
+    pub stock: VecDeque<(u64, Resources)>,
+}
+
+impl Default for ResourcesHistory {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl ResourcesHistory {
     pub fn new() -> Self {
         Self {
-            energy_collected: Vec::new(),
-            minerals_collected: Vec::new(),
-            scientific_data_collected: Vec::new(),
-            resources_used_for_production: Vec::new(),
+            collected: FlowSeries::default(),
+            used_for_production: FlowSeries::default(),
+            spent: FlowSeries::default(),
+            stock: VecDeque::new(),
         }
     }
 
     pub fn add_energy_collected(&mut self, timestamp: u64, amount: u32) {
-        self.energy_collected.push((timestamp, amount));
+        let resources = Resources {
+            energy: amount,
+            ..Resources::default()
+        };
+        self.collected.add(timestamp, &resources);
     }
 
     pub fn add_minerals_collected(&mut self, timestamp: u64, amount: u32) {
-        self.minerals_collected.push((timestamp, amount));
+        let resources = Resources {
+            minerals: amount,
+            ..Resources::default()
+        };
+        self.collected.add(timestamp, &resources);
     }
 
     pub fn add_scientific_data_collected(&mut self, timestamp: u64, amount: u32) {
-        self.scientific_data_collected.push((timestamp, amount));
+        let resources = Resources {
+            scientific_data: amount,
+            ..Resources::default()
+        };
+        self.collected.add(timestamp, &resources);
     }
 
     pub fn add_resources_used_for_production(&mut self, timestamp: u64, resources: Resources) {
-        self.resources_used_for_production
-            .push((timestamp, resources));
+        self.used_for_production.add(timestamp, &resources);
     }
 
-    pub fn total_collected(&self) -> Resources {
-        let total_energy = self.energy_collected.iter().map(|(_, amount)| amount).sum();
-        let total_minerals = self
-            .minerals_collected
-            .iter()
-            .map(|(_, amount)| amount)
-            .sum();
-        let total_scientific_data = self
-            .scientific_data_collected
-            .iter()
-            .map(|(_, amount)| amount)
-            .sum();
-
-        Resources {
-            energy: total_energy,
-            minerals: total_minerals,
-            scientific_data: total_scientific_data,
+    pub fn add_resources_spent(&mut self, timestamp: u64, resources: Resources) {
+        self.spent.add(timestamp, &resources);
+    }
+
+    pub fn record_stock(&mut self, timestamp: u64, resources: &Resources) {
+        if self.stock.len() == STOCK_HISTORY_CAPACITY {
+            self.stock.pop_front();
         }
+        self.stock.push_back((timestamp, resources.clone()));
     }
 
-    pub fn total_used_for_production(&self) -> Resources {
-        let mut total = Resources {
-            energy: 0,
-            minerals: 0,
-            scientific_data: 0,
-        };
+    /// Quantités collectées par fenêtre, pour les `count` dernières fenêtres
+    /// jusqu'à `now`, de la plus ancienne à la plus récente
+    pub fn collected_per_window(&self, count: usize, now: u64) -> Vec<Resources> {
+        self.collected.per_window(count, now)
+    }
 
-        for (_, resources) in &self.resources_used_for_production {
-            total.energy += resources.energy;
-            total.minerals += resources.minerals;
-            total.scientific_data += resources.scientific_data;
+    /// Ressources dépensées par fenêtre, production comprise, comme `collected_per_window`
+    pub fn spent_per_window(&self, count: usize, now: u64) -> Vec<Resources> {
+        let mut buckets = self.used_for_production.per_window(count, now);
+        for (bucket, spent) in buckets.iter_mut().zip(self.spent.per_window(count, now)) {
+            add_to(bucket, &spent);
         }
+        buckets
+    }
 
+    pub fn total_collected(&self) -> Resources {
+        self.collected.total().clone()
+    }
+
+    pub fn total_used_for_production(&self) -> Resources {
+        self.used_for_production.total().clone()
+    }
+
+    /// Total des dépenses, production comprise
+    pub fn total_spent(&self) -> Resources {
+        let mut total = self.total_used_for_production();
+        add_to(&mut total, self.spent.total());
         total
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_income_and_spending_are_grouped_by_window() {
+        let mut history = ResourcesHistory::new();
+        history.add_energy_collected(5, 10);
+        history.add_scientific_data_collected(80, 7);
+        history.add_minerals_collected(95, 20);
+        history.add_minerals_collected(99, 5);
+        history.add_resources_used_for_production(
+            98,
+            Resources {
+                energy: 20,
+                minerals: 100,
+                scientific_data: 0,
+            },
+        );
+        history.add_resources_spent(
+            90,
+            Resources {
+                energy: 0,
+                minerals: 0,
+                scientific_data: 30,
+            },
+        );
+
+        let collected = history.collected_per_window(10, 99);
+        assert_eq!(collected[0].energy, 10);
+        assert_eq!(collected[9].minerals, 25);
+        assert_eq!(collected[8].scientific_data, 7);
+        assert_eq!(collected[7], Resources::default());
+        // La collecte du tick 5 est antérieure aux fenêtres demandées
+        assert_eq!(history.collected_per_window(9, 99)[0].energy, 0);
+
+        let spent = history.spent_per_window(2, 99);
+        assert_eq!(spent[1].minerals, 100);
+        assert_eq!(spent[1].scientific_data, 30);
+        assert_eq!(history.total_collected().minerals, 25);
+        assert_eq!(history.total_used_for_production().scientific_data, 0);
+        assert_eq!(history.total_spent().scientific_data, 30);
+    }
+
+    #[test]
+    fn test_flows_are_bounded_but_totals_are_kept() {
+        let mut history = ResourcesHistory::new();
+        let windows = FLOW_HISTORY_CAPACITY as u64 + 5;
+        for tick in 0..windows * FLOW_WINDOW_TICKS {
+            history.add_minerals_collected(tick, 1);
+        }
+        assert_eq!(history.collected.len(), FLOW_HISTORY_CAPACITY);
+        assert_eq!(
+            u64::from(history.total_collected().minerals),
+            windows * FLOW_WINDOW_TICKS
+        );
+
+        let now = windows * FLOW_WINDOW_TICKS - 1;
+        let collected = history.collected_per_window(FLOW_HISTORY_CAPACITY + 2, now);
+        assert_eq!(collected[0].minerals, 0);
+        assert_eq!(collected[2].minerals, FLOW_WINDOW_TICKS as u32);
+    }
+
+    #[test]
+    fn test_stock_history_is_bounded() {
+        let mut history = ResourcesHistory::new();
+        let resources = Resources {
+            energy: 1,
+            minerals: 2,
+            scientific_data: 3,
+        };
+        for tick in 0..STOCK_HISTORY_CAPACITY as u64 + 5 {
+            history.record_stock(tick, &resources);
+        }
+        assert_eq!(history.stock.len(), STOCK_HISTORY_CAPACITY);
+        assert_eq!(history.stock.front().unwrap().0, 5);
+    }
+}
@@ -1,14 +1,73 @@
 #[allow(dead_code)]
 use crate::map::{Map, Tile};
 use crate::robot::Position;
+use crate::station::conflict::{
+    compare_clocks, merge_observations, Causality, ConflictPolicy, Observation, Observer,
+    TileConflict, MAX_CONFLICT_LOG,
+};
 use serde::{Deserialize, Serialize};
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// Nombre maximal de mises à jour conservées dans l'historique. Au-delà, les
+/// plus anciennes sont compactées et une synchronisation trop ancienne
+/// nécessite un instantané complet.
+pub const MAX_HISTORY_LEN: usize = 256;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MapKnowledge {
     pub version: u64,
     pub explored_tiles: HashMap<(u32, u32), ExploredTile>,
     pub updates_history: Vec<MapUpdate>,
+    /// Version la plus ancienne à partir de laquelle l'historique est complet
+    #[serde(default)]
+    pub history_start: u64,
+    /// Cases modifiées localement depuis la dernière synchronisation
+    #[serde(default)]
+    pub local_changes: HashSet<(u32, u32)>,
+    /// Politique appliquée aux observations concurrentes et contradictoires
+    #[serde(default)]
+    pub conflict_policy: ConflictPolicy,
+    /// Derniers désaccords détectés entre robots
+    #[serde(default)]
+    pub conflicts: Vec<TileConflict>,
+}
+
+/// Réponse de synchronisation envoyée par la station : seulement les
+/// changements depuis la version connue du robot, ou la carte complète si
+/// l'historique nécessaire a été compacté.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum KnowledgeSync {
+    Delta {
+        from_version: u64,
+        to_version: u64,
+        updates: Vec<MapUpdate>,
+        tiles: Vec<((u32, u32), ExploredTile)>,
+    },
+    Snapshot {
+        version: u64,
+        tiles: Vec<((u32, u32), ExploredTile)>,
+    },
+}
+
+impl KnowledgeSync {
+    pub fn version(&self) -> u64 {
+        match self {
+            KnowledgeSync::Delta { to_version, .. } => *to_version,
+            KnowledgeSync::Snapshot { version, .. } => *version,
+        }
+    }
+
+    /// Nombre de cases transportées par le message
+    pub fn tile_count(&self) -> usize {
+        match self {
+            KnowledgeSync::Delta { tiles, .. } => tiles.len(),
+            KnowledgeSync::Snapshot { tiles, .. } => tiles.len(),
+        }
+    }
+
+    pub fn is_snapshot(&self) -> bool {
+        matches!(self, KnowledgeSync::Snapshot { .. })
+    }
 }
 
 
@@ -17,6 +76,10 @@ pub struct ExploredTile {
     pub tile: Tile,
     pub version: u64,
     pub explorer_id: Option<u32>,
+    /// Dernière observation de chaque robot, dont les compteurs forment
+    /// l'horloge vectorielle de la case
+    #[serde(default)]
+    pub observations: BTreeMap<u32, Observation>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -27,13 +90,201 @@ pub struct MapUpdate {
     pub robot_id: u32,
 }
 
+impl Default for MapKnowledge {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl MapKnowledge {
     pub fn new() -> Self {
         Self {
             version: 0,
             explored_tiles: HashMap::new(),
             updates_history: Vec::new(),
+            history_start: 0,
+            local_changes: HashSet::new(),
+            conflict_policy: ConflictPolicy::default(),
+            conflicts: Vec::new(),
+        }
+    }
+
+    /// Enregistre l'observation d'une case. La version de la case n'augmente que
+    /// si son contenu a changé ; l'observation d'un robot est tout de même
+    /// enregistrée quand elle diffère de sa précédente, pour le consensus.
+    /// Retourne vrai si le contenu de la case a changé.
+    pub fn observe_tile(&mut self, position: Position, tile: &Tile, observer: Observer) -> bool {
+        let key = (position.x, position.y);
+        let known = self.explored_tiles.entry(key).or_insert_with(|| ExploredTile {
+            tile: tile.clone(),
+            version: 0,
+            explorer_id: None,
+            observations: BTreeMap::new(),
+        });
+
+        let changed = known.version == 0 || known.tile != *tile;
+        let new_observation = known
+            .observations
+            .get(&observer.robot_id)
+            .is_none_or(|observation| observation.tile != *tile);
+        if !changed && !new_observation {
+            return false;
+        }
+
+        let count = known
+            .observations
+            .get(&observer.robot_id)
+            .map_or(0, |observation| observation.count)
+            + 1;
+        known.observations.insert(
+            observer.robot_id,
+            Observation {
+                tile: tile.clone(),
+                tick: observer.tick,
+                count,
+                sensor_efficiency: observer.sensor_efficiency,
+            },
+        );
+
+        if changed {
+            known.tile = tile.clone();
+            known.version += 1;
+            known.explorer_id = Some(observer.robot_id);
+        }
+
+        self.local_changes.insert(key);
+        changed
+    }
+
+    /// Fusionne des cases reçues d'un autre robot. Elles sont marquées comme
+    /// modifiées localement pour être relayées à la station au prochain amarrage.
+    pub fn merge_peer_tiles(
+        &mut self,
+        tiles: impl IntoIterator<Item = ((u32, u32), ExploredTile)>,
+    ) -> Vec<Position> {
+        let mut updated_positions = Vec::new();
+        for (key, tile) in tiles {
+            if self.merge_tile(key, tile) {
+                self.local_changes.insert(key);
+                updated_positions.push(Position { x: key.0, y: key.1 });
+            }
         }
+        updated_positions
+    }
+
+    /// Retire et retourne les cases modifiées localement, à envoyer à la station
+    pub fn take_local_changes(&mut self) -> HashMap<(u32, u32), ExploredTile> {
+        let keys = std::mem::take(&mut self.local_changes);
+        keys.into_iter()
+            .filter_map(|key| self.explored_tiles.get(&key).map(|tile| (key, tile.clone())))
+            .collect()
+    }
+
+    /// Construit la réponse de synchronisation pour un robot qui connaît la
+    /// version `since` : un delta si l'historique le permet, sinon un instantané.
+    pub fn delta_since(&self, since: u64) -> KnowledgeSync {
+        if since > self.version || since < self.history_start {
+            return KnowledgeSync::Snapshot {
+                version: self.version,
+                tiles: self
+                    .explored_tiles
+                    .iter()
+                    .map(|(key, tile)| (*key, tile.clone()))
+                    .collect(),
+            };
+        }
+
+        let updates: Vec<MapUpdate> = self
+            .updates_history
+            .iter()
+            .filter(|update| update.version > since)
+            .cloned()
+            .collect();
+
+        let mut seen = HashSet::new();
+        let tiles = updates
+            .iter()
+            .flat_map(|update| update.positions.iter())
+            .filter(|position| seen.insert((position.x, position.y)))
+            .filter_map(|position| {
+                let key = (position.x, position.y);
+                self.explored_tiles.get(&key).map(|tile| (key, tile.clone()))
+            })
+            .collect();
+
+        KnowledgeSync::Delta {
+            from_version: since,
+            to_version: self.version,
+            updates,
+            tiles,
+        }
+    }
+
+    /// Applique une réponse de synchronisation de la station. Les cases modifiées
+    /// localement et pas encore envoyées sont conservées. Une réponse périmée ou
+    /// qui suppose des changements manquants (perte, désordre) est ignorée.
+    /// Retourne vrai si la réponse a été appliquée.
+    pub fn apply_sync(&mut self, sync: KnowledgeSync) -> bool {
+        match sync {
+            KnowledgeSync::Delta {
+                from_version,
+                to_version,
+                updates,
+                tiles,
+            } => {
+                if to_version <= self.version || from_version > self.version {
+                    return false;
+                }
+                for (key, tile) in tiles {
+                    if !self.local_changes.contains(&key) {
+                        self.explored_tiles.insert(key, tile);
+                    }
+                }
+                self.updates_history
+                    .extend(updates.into_iter().filter(|update| update.version > self.version));
+                self.version = to_version;
+            }
+            KnowledgeSync::Snapshot { version, tiles } => {
+                if version < self.version {
+                    return false;
+                }
+                let mut tiles: HashMap<_, _> = tiles.into_iter().collect();
+                for key in &self.local_changes {
+                    if let Some(tile) = self.explored_tiles.remove(key) {
+                        tiles.insert(*key, tile);
+                    }
+                }
+                self.explored_tiles = tiles;
+                self.updates_history.clear();
+                self.history_start = version;
+                self.version = version;
+            }
+        }
+        self.compact_history(MAX_HISTORY_LEN);
+        true
+    }
+
+    /// Ne garde que les `max_len` dernières mises à jour de l'historique
+    pub fn compact_history(&mut self, max_len: usize) {
+        if self.updates_history.len() <= max_len {
+            return;
+        }
+
+        let excess = self.updates_history.len() - max_len;
+        self.updates_history.drain(..excess);
+        if let Some(oldest) = self.updates_history.first() {
+            self.history_start = oldest.version - 1;
+        } else {
+            self.history_start = self.version;
+        }
+    }
+
+    pub fn get_tile(&self, x: u32, y: u32) -> Option<&ExploredTile> {
+        self.explored_tiles.get(&(x, y))
+    }
+
+    pub fn explored_count(&self) -> usize {
+        self.explored_tiles.len()
     }
 
     pub fn merge_robot_knowledge(
@@ -42,22 +293,16 @@ impl MapKnowledge {
         robot_id: u32,
     ) -> Vec<Position> {
         let mut updated_positions = Vec::new();
-        let timestamp = std::time::SystemTime::now()
-            .duration_since(std::time::UNIX_EPOCH)
-            .unwrap()
-            .as_secs();
+        let timestamp = robot_tiles
+            .values()
+            .flat_map(|tile| tile.observations.values())
+            .map(|observation| observation.tick)
+            .max()
+            .unwrap_or(0);
 
         for ((x, y), robot_tile) in robot_tiles {
-            let position = Position { x, y };
-
-            if let Some(station_tile) = self.explored_tiles.get_mut(&(x, y)) {
-                if robot_tile.version > station_tile.version {
-                    *station_tile = robot_tile;
-                    updated_positions.push(position);
-                }
-            } else {
-                self.explored_tiles.insert((x, y), robot_tile);
-                updated_positions.push(position);
+            if self.merge_tile((x, y), robot_tile) {
+                updated_positions.push(Position { x, y });
             }
         }
 
@@ -70,13 +315,71 @@ impl MapKnowledge {
                 timestamp,
                 robot_id,
             });
+            self.compact_history(MAX_HISTORY_LEN);
         }
 
         updated_positions
     }
 
+    /// Fusionne une case distante selon la causalité de leurs observations.
+    /// Les observations concurrentes sont départagées par la politique de
+    /// conflit. Retourne vrai si la case locale a changé.
+    fn merge_tile(&mut self, key: (u32, u32), remote: ExploredTile) -> bool {
+        let Some(local) = self.explored_tiles.get_mut(&key) else {
+            self.explored_tiles.insert(key, remote);
+            return true;
+        };
+
+        match compare_clocks(&local.observations, &remote.observations) {
+            Causality::After => false,
+            Causality::Equal if remote.version <= local.version => false,
+            Causality::Equal | Causality::Before => {
+                *local = remote;
+                true
+            }
+            Causality::Concurrent => {
+                let observations = merge_observations(&local.observations, &remote.observations);
+                let Some((robot_id, chosen)) = self.conflict_policy.resolve(&observations) else {
+                    return false;
+                };
+                let resolved = chosen.tile.clone();
+
+                if local.tile != remote.tile {
+                    let tick = observations
+                        .values()
+                        .map(|observation| observation.tick)
+                        .max()
+                        .unwrap_or(0);
+                    self.conflicts.push(TileConflict {
+                        position: Position { x: key.0, y: key.1 },
+                        tick,
+                        local_robot: local.explorer_id,
+                        local_tile: local.tile.clone(),
+                        remote_robot: remote.explorer_id,
+                        remote_tile: remote.tile.clone(),
+                        resolved: resolved.clone(),
+                        policy: self.conflict_policy,
+                    });
+                    if self.conflicts.len() > MAX_CONFLICT_LOG {
+                        self.conflicts.remove(0);
+                    }
+                }
+
+                if local.tile != resolved {
+                    local.tile = resolved;
+                    local.explorer_id = Some(robot_id);
+                }
+                local.version = local.version.max(remote.version) + 1;
+                local.observations = observations;
+                true
+            }
+        }
+    }
+
+    /// Construit une carte où seules les cases explorées sont renseignées,
+    /// les autres restant `Tile::Unknown`
     pub fn generate_partial_map(&self, width: u32, height: u32) -> Map {
-        let mut map = Map::new(width, height, 0); 
+        let mut map = Map::unknown(width, height);
 
         for ((x, y), explored_tile) in &self.explored_tiles {
             if *x < width && *y < height {
@@ -89,10 +392,13 @@ impl MapKnowledge {
         map
     }
 
+    /// Vrai si une case a été observée différemment par des sources concurrentes
     pub fn has_conflicts(&self, other: &MapKnowledge) -> bool {
         for ((x, y), tile) in &self.explored_tiles {
             if let Some(other_tile) = other.explored_tiles.get(&(*x, *y)) {
-                if tile.version == other_tile.version && tile.tile != other_tile.tile {
+                let concurrent = compare_clocks(&tile.observations, &other_tile.observations)
+                    == Causality::Concurrent;
+                if concurrent && tile.tile != other_tile.tile {
                     return true;
                 }
             }
@@ -101,16 +407,217 @@ impl MapKnowledge {
     }
 
     pub fn resolve_conflicts(&mut self, other: &MapKnowledge) {
-        for ((x, y), other_tile) in &other.explored_tiles {
-            if let Some(tile) = self.explored_tiles.get_mut(&(*x, *y)) {
-                if other_tile.version > tile.version {
-                    *tile = other_tile.clone();
-                }
-            } else {
-                self.explored_tiles.insert((*x, *y), other_tile.clone());
+        let mut updated_positions = Vec::new();
+
+        for (key, other_tile) in &other.explored_tiles {
+            if self.merge_tile(*key, other_tile.clone()) {
+                updated_positions.push(Position { x: key.0, y: key.1 });
             }
         }
 
         self.version = self.version.max(other.version) + 1;
+
+        // Garder l'historique cohérent pour les synchronisations par delta
+        self.updates_history.push(MapUpdate {
+            version: self.version,
+            positions: updated_positions,
+            timestamp: 0,
+            robot_id: 0,
+        });
+        self.compact_history(MAX_HISTORY_LEN);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_observe_tile_versions() {
+        let mut knowledge = MapKnowledge::new();
+        let position = Position { x: 3, y: 4 };
+
+        assert!(knowledge.observe_tile(position, &Tile::Empty, observer(1, 0)));
+        assert!(!knowledge.observe_tile(position, &Tile::Empty, observer(2, 0)));
+        assert_eq!(knowledge.get_tile(3, 4).unwrap().version, 1);
+
+        assert!(knowledge.observe_tile(position, &Tile::Obstacle, observer(2, 0)));
+        let tile = knowledge.get_tile(3, 4).unwrap();
+        assert_eq!(tile.version, 2);
+        assert_eq!(tile.explorer_id, Some(2));
+    }
+
+    #[test]
+    fn test_merge_keeps_newer_observations() {
+        let mut station = MapKnowledge::new();
+        station.observe_tile(Position { x: 1, y: 1 }, &Tile::Empty, observer(1, 0));
+
+        let mut robot = station.clone();
+        robot.observe_tile(Position { x: 1, y: 1 }, &Tile::Obstacle, observer(2, 0));
+        robot.observe_tile(Position { x: 2, y: 2 }, &Tile::Empty, observer(2, 0));
+
+        let updated = station.merge_robot_knowledge(robot.explored_tiles, 2);
+
+        assert_eq!(updated.len(), 2);
+        assert_eq!(station.get_tile(1, 1).unwrap().tile, Tile::Obstacle);
+        assert_eq!(station.version, 1);
+        assert_eq!(station.updates_history.len(), 1);
+    }
+
+    #[test]
+    fn test_delta_contains_only_changes_since_version() {
+        let mut station = MapKnowledge::new();
+        let mut first = HashMap::new();
+        first.insert((1, 1), tile(Tile::Empty, 1));
+        station.merge_robot_knowledge(first, 1);
+        let mut second = HashMap::new();
+        second.insert((2, 2), tile(Tile::Obstacle, 1));
+        station.merge_robot_knowledge(second, 2);
+
+        let sync = station.delta_since(1);
+        assert!(!sync.is_snapshot());
+        assert_eq!(sync.version(), 2);
+        assert_eq!(sync.tile_count(), 1);
+
+        assert_eq!(station.delta_since(2).tile_count(), 0);
+    }
+
+    #[test]
+    fn test_compacted_history_falls_back_to_snapshot() {
+        let mut station = MapKnowledge::new();
+        for x in 0..(MAX_HISTORY_LEN as u32 + 10) {
+            let mut tiles = HashMap::new();
+            tiles.insert((x, 0), tile(Tile::Empty, 1));
+            station.merge_robot_knowledge(tiles, 1);
+        }
+        assert_eq!(station.updates_history.len(), MAX_HISTORY_LEN);
+
+        let sync = station.delta_since(5);
+        assert!(sync.is_snapshot());
+        assert_eq!(sync.tile_count(), station.explored_count());
+
+        let recent = station.delta_since(station.version - 3);
+        assert!(!recent.is_snapshot());
+        assert_eq!(recent.tile_count(), 3);
+    }
+
+    #[test]
+    fn test_apply_sync_keeps_unsent_local_changes() {
+        let mut station = MapKnowledge::new();
+        let mut tiles = HashMap::new();
+        tiles.insert((1, 1), tile(Tile::Empty, 1));
+        tiles.insert((2, 2), tile(Tile::Empty, 1));
+        station.merge_robot_knowledge(tiles, 1);
+
+        let mut robot = MapKnowledge::new();
+        robot.observe_tile(Position { x: 1, y: 1 }, &Tile::Obstacle, observer(2, 0));
+        robot.apply_sync(station.delta_since(0));
+
+        assert_eq!(robot.version, station.version);
+        assert_eq!(robot.get_tile(1, 1).unwrap().tile, Tile::Obstacle);
+        assert_eq!(robot.get_tile(2, 2).unwrap().tile, Tile::Empty);
+
+        let changes = robot.take_local_changes();
+        assert_eq!(changes.len(), 1);
+        assert!(robot.take_local_changes().is_empty());
+    }
+
+    #[test]
+    fn test_partial_map_marks_unexplored_tiles_unknown() {
+        let mut knowledge = MapKnowledge::new();
+        knowledge.observe_tile(Position { x: 1, y: 1 }, &Tile::Obstacle, observer(1, 0));
+
+        let map = knowledge.generate_partial_map(5, 5);
+
+        assert!(map.is_obstacle(1, 1));
+        assert!(map.is_unknown(0, 0));
+        assert_eq!(
+            (0..5)
+                .flat_map(|y| (0..5).map(move |x| (x, y)))
+                .filter(|(x, y)| map.is_unknown(*x, *y))
+                .count(),
+            24
+        );
+    }
+
+    #[test]
+    fn test_concurrent_observations_are_resolved_and_logged() {
+        let mut station = MapKnowledge::new();
+        station.observe_tile(Position { x: 1, y: 1 }, &Tile::Empty, observer(1, 1));
+
+        let mut first = station.clone();
+        let mut second = station.clone();
+        first.observe_tile(Position { x: 1, y: 1 }, &Tile::Obstacle, observer(2, 10));
+        second.observe_tile(Position { x: 1, y: 1 }, &Tile::Empty, observer(3, 5));
+        assert!(first.has_conflicts(&second));
+
+        station.merge_robot_knowledge(first.take_local_changes(), 2);
+        assert!(station.conflicts.is_empty());
+        station.merge_robot_knowledge(second.take_local_changes(), 3);
+
+        // La dernière observation (tick 10) l'emporte malgré l'ordre d'arrivée
+        assert_eq!(station.get_tile(1, 1).unwrap().tile, Tile::Obstacle);
+        assert_eq!(station.get_tile(1, 1).unwrap().observations.len(), 3);
+        assert_eq!(station.conflicts.len(), 1);
+        assert_eq!(station.conflicts[0].local_tile, Tile::Obstacle);
+        assert_eq!(station.conflicts[0].remote_tile, Tile::Empty);
+        assert_eq!(station.updates_history[1].timestamp, 5);
+    }
+
+    #[test]
+    fn test_consensus_policy_outvotes_single_robot() {
+        let mut station = MapKnowledge::new();
+        station.conflict_policy = ConflictPolicy::Consensus { quorum: 2 };
+        station.observe_tile(Position { x: 1, y: 1 }, &Tile::Empty, observer(1, 1));
+
+        let mut dissent = station.clone();
+        let mut agree = station.clone();
+        dissent.observe_tile(Position { x: 1, y: 1 }, &Tile::Obstacle, observer(2, 10));
+        agree.observe_tile(Position { x: 1, y: 1 }, &Tile::Empty, observer(3, 5));
+
+        station.merge_robot_knowledge(dissent.take_local_changes(), 2);
+        station.merge_robot_knowledge(agree.take_local_changes(), 3);
+
+        assert_eq!(station.get_tile(1, 1).unwrap().tile, Tile::Empty);
+        assert_eq!(station.conflicts.len(), 1);
+    }
+
+    #[test]
+    fn test_stale_or_gapped_syncs_are_ignored() {
+        let mut station = MapKnowledge::new();
+        for x in 0..3 {
+            let mut tiles = HashMap::new();
+            tiles.insert((x, 0), tile(Tile::Empty, 1));
+            station.merge_robot_knowledge(tiles, 1);
+        }
+
+        let mut robot = MapKnowledge::new();
+        let gapped = station.delta_since(1);
+        let old = station.delta_since(0);
+        assert!(!robot.apply_sync(gapped.clone()));
+        assert_eq!(robot.version, 0);
+
+        assert!(robot.apply_sync(station.delta_since(0)));
+        assert_eq!(robot.version, 3);
+        assert!(!robot.apply_sync(old));
+        assert!(!robot.apply_sync(gapped));
+        assert_eq!(robot.explored_count(), 3);
+    }
+
+    fn tile(tile: Tile, version: u64) -> ExploredTile {
+        ExploredTile {
+            tile,
+            version,
+            explorer_id: None,
+            observations: BTreeMap::new(),
+        }
+    }
+
+    fn observer(robot_id: u32, tick: u64) -> Observer {
+        Observer {
+            robot_id,
+            tick,
+            sensor_efficiency: 0.5,
+        }
     }
 }
@@ -0,0 +1,358 @@
+use crate::robot::{HardwareModule, Resources, Robot};
+use crate::station::research::ResearchTopic;
+use crate::station::{RobotType, Station};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+pub const MAX_MODULES_PER_ROBOT: usize = 4;
+
+/// Coûts de l'atelier, exprimés en (minerals, scientific_data)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorkshopCosts {
+    pub install: (u32, u32),
+    pub upgrade: (u32, u32),
+    pub fabrication: (u32, u32),
+}
+
+impl Default for WorkshopCosts {
+    fn default() -> Self {
+        Self {
+            install: (20, 0),
+            upgrade: (60, 40),
+            fabrication: (80, 20),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum WorkshopError {
+    NotDocked,
+    NoSuchModule,
+    NoFreeSlot,
+    ModuleLocked(ResearchTopic),
+    InsufficientResources { minerals: u32, scientific_data: u32 },
+}
+
+impl fmt::Display for WorkshopError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WorkshopError::NotDocked => write!(f, "robot is not docked at the station"),
+            WorkshopError::NoSuchModule => write!(f, "no module in that slot"),
+            WorkshopError::NoFreeSlot => write!(f, "robot has no free module slot"),
+            WorkshopError::ModuleLocked(topic) => write!(f, "requires {} research", topic.name()),
+            WorkshopError::InsufficientResources {
+                minerals,
+                scientific_data,
+            } => write!(
+                f,
+                "needs {minerals} minerals and {scientific_data} scientific data"
+            ),
+        }
+    }
+}
+
+// / Atelier de la station : installation, retrait et amélioration des modules
+impl Station {
+    /// Installe un module du stockage de la station sur un robot amarré
+    pub fn install_stored_module(
+        &mut self,
+        robot: &mut Robot,
+        storage_index: usize,
+    ) -> Result<(), WorkshopError> {
+        self.ensure_docked(robot)?;
+
+        if storage_index >= self.module_storage.len() {
+            return Err(WorkshopError::NoSuchModule);
+        }
+        if robot.modules.len() >= MAX_MODULES_PER_ROBOT {
+            return Err(WorkshopError::NoFreeSlot);
+        }
+
+        self.spend_workshop_resources(self.workshop_costs.install)?;
+
+        let module = self.module_storage.remove(storage_index);
+        robot.modules.push(module);
+        Ok(())
+    }
+
+    /// Fabrique un module neuf et l'installe directement sur un robot amarré
+    pub fn fabricate_module(
+        &mut self,
+        robot: &mut Robot,
+        module: HardwareModule,
+    ) -> Result<(), WorkshopError> {
+        self.ensure_docked(robot)?;
+
+        if let Some(topic) = self.required_research(&module) {
+            return Err(WorkshopError::ModuleLocked(topic));
+        }
+        if robot.modules.len() >= MAX_MODULES_PER_ROBOT {
+            return Err(WorkshopError::NoFreeSlot);
+        }
+
+        self.spend_workshop_resources(self.workshop_costs.fabrication)?;
+
+        robot.modules.push(module);
+        Ok(())
+    }
+
+    /// Retourne la recherche manquante pour fabriquer ce type de module
+    pub fn required_research(&self, module: &HardwareModule) -> Option<ResearchTopic> {
+        match module {
+            HardwareModule::SignalRelay { .. }
+                if !self.research.is_completed(ResearchTopic::SignalRelays) =>
+            {
+                Some(ResearchTopic::SignalRelays)
+            }
+            _ => None,
+        }
+    }
+
+    /// Retire un module d'un robot amarré et le range dans le stockage de la station
+    pub fn remove_module(
+        &mut self,
+        robot: &mut Robot,
+        module_index: usize,
+    ) -> Result<(), WorkshopError> {
+        self.ensure_docked(robot)?;
+
+        if module_index >= robot.modules.len() {
+            return Err(WorkshopError::NoSuchModule);
+        }
+
+        let module = robot.modules.remove(module_index);
+        self.module_storage.push(module);
+        Ok(())
+    }
+
+    /// Améliore un module installé sur un robot amarré
+    pub fn upgrade_module(
+        &mut self,
+        robot: &mut Robot,
+        module_index: usize,
+    ) -> Result<(), WorkshopError> {
+        self.ensure_docked(robot)?;
+
+        let Some(module) = robot.modules.get(module_index) else {
+            return Err(WorkshopError::NoSuchModule);
+        };
+        let upgraded = module.upgraded();
+
+        self.spend_workshop_resources(self.workshop_costs.upgrade)?;
+
+        robot.modules[module_index] = upgraded;
+        Ok(())
+    }
+
+    /// Change le rôle d'un robot amarré en remplaçant tous ses modules.
+    /// Les anciens modules partent au stockage, et les modules du même type
+    /// déjà stockés sont réutilisés avant d'en fabriquer de nouveaux.
+    pub fn refit_robot(
+        &mut self,
+        robot: &mut Robot,
+        robot_type: RobotType,
+    ) -> Result<(), WorkshopError> {
+        self.ensure_docked(robot)?;
+
+        let loadout = self.get_modules_for_robot_type(robot_type);
+
+        let mut available: Vec<HardwareModule> = self.module_storage.clone();
+        available.extend(robot.modules.iter().cloned());
+
+        let mut cost = (0, 0);
+        for module in &loadout {
+            let (minerals, scientific_data) =
+                match available.iter().position(|m| m.is_same_kind(module)) {
+                    Some(index) => {
+                        available.remove(index);
+                        self.workshop_costs.install
+                    }
+                    None => self.workshop_costs.fabrication,
+                };
+            cost.0 += minerals;
+            cost.1 += scientific_data;
+        }
+
+        self.spend_workshop_resources(cost)?;
+
+        self.module_storage.append(&mut robot.modules);
+        for module in loadout {
+            match self
+                .module_storage
+                .iter()
+                .position(|m| m.is_same_kind(&module))
+            {
+                Some(index) => robot.modules.push(self.module_storage.remove(index)),
+                None => robot.modules.push(module),
+            }
+        }
+
+        Ok(())
+    }
+
+    fn ensure_docked(&self, robot: &Robot) -> Result<(), WorkshopError> {
+        if robot.is_at_station(self) {
+            Ok(())
+        } else {
+            Err(WorkshopError::NotDocked)
+        }
+    }
+
+    fn spend_workshop_resources(&mut self, cost: (u32, u32)) -> Result<(), WorkshopError> {
+        let (minerals, scientific_data) = cost;
+        if self.resources.minerals < minerals || self.resources.scientific_data < scientific_data {
+            return Err(WorkshopError::InsufficientResources {
+                minerals,
+                scientific_data,
+            });
+        }
+
+        self.resources.minerals -= minerals;
+        self.resources.scientific_data -= scientific_data;
+        self.history.add_resources_spent(
+            self.clock,
+            Resources {
+                energy: 0,
+                minerals,
+                scientific_data,
+            },
+        );
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::map::Map;
+    use crate::robot::Position;
+
+    fn docked_robot(station: &mut Station, modules: Vec<HardwareModule>) -> Robot {
+        let bay = station.docking.request_bay(1).unwrap();
+        let mut robot = Robot::new(bay, modules);
+        robot.id = 1;
+        robot
+    }
+
+    #[test]
+    fn test_operations_require_docking() {
+        let mut map = Map::new(50, 50, 42);
+        let mut station = Station::new(&mut map);
+        let away = Position {
+            x: station.position.x + 5,
+            y: station.position.y,
+        };
+        let mut robot = Robot::new(away, vec![HardwareModule::DeepDrill { mining_speed: 2.0 }]);
+
+        assert_eq!(
+            station.remove_module(&mut robot, 0),
+            Err(WorkshopError::NotDocked)
+        );
+        assert_eq!(robot.modules.len(), 1);
+    }
+
+    #[test]
+    fn test_remove_and_install_module() {
+        let mut map = Map::new(50, 50, 42);
+        let mut station = Station::new(&mut map);
+        let mut robot = docked_robot(
+            &mut station,
+            vec![HardwareModule::DeepDrill { mining_speed: 2.0 }],
+        );
+
+        station.remove_module(&mut robot, 0).unwrap();
+        assert!(robot.modules.is_empty());
+        assert_eq!(station.module_storage.len(), 1);
+
+        let minerals_before = station.resources.minerals;
+        station.install_stored_module(&mut robot, 0).unwrap();
+        assert_eq!(robot.modules.len(), 1);
+        assert!(station.module_storage.is_empty());
+        assert_eq!(
+            station.resources.minerals,
+            minerals_before - station.workshop_costs.install.0
+        );
+    }
+
+    #[test]
+    fn test_upgrade_module_costs_scientific_data() {
+        let mut map = Map::new(50, 50, 42);
+        let mut station = Station::new(&mut map);
+        let mut robot = docked_robot(
+            &mut station,
+            vec![HardwareModule::TerrainScanner {
+                efficiency: 0.9,
+                range: 20,
+            }],
+        );
+
+        station.resources.scientific_data = 0;
+        assert!(matches!(
+            station.upgrade_module(&mut robot, 0),
+            Err(WorkshopError::InsufficientResources { .. })
+        ));
+
+        station.resources.scientific_data = 100;
+        station.upgrade_module(&mut robot, 0).unwrap();
+        assert_eq!(
+            robot.modules[0],
+            HardwareModule::TerrainScanner {
+                efficiency: 0.95,
+                range: 25,
+            }
+        );
+        assert_eq!(
+            station.resources.scientific_data,
+            100 - station.workshop_costs.upgrade.1
+        );
+    }
+
+    #[test]
+    fn test_fabricate_locked_module() {
+        let mut map = Map::new(50, 50, 42);
+        let mut station = Station::new(&mut map);
+        let mut robot = docked_robot(&mut station, vec![]);
+        let relay = HardwareModule::SignalRelay { range: 30 };
+
+        assert_eq!(
+            station.fabricate_module(&mut robot, relay.clone()),
+            Err(WorkshopError::ModuleLocked(ResearchTopic::SignalRelays))
+        );
+
+        station.resources.scientific_data = ResearchTopic::ImprovedOptics.cost()
+            + ResearchTopic::SignalRelays.cost()
+            + station.workshop_costs.fabrication.1;
+        for topic in [ResearchTopic::ImprovedOptics, ResearchTopic::SignalRelays] {
+            station.start_research(topic).unwrap();
+            while station.update_research().is_none() {}
+        }
+
+        station.fabricate_module(&mut robot, relay.clone()).unwrap();
+        assert_eq!(robot.modules, vec![relay]);
+    }
+
+    #[test]
+    fn test_refit_changes_role_and_reuses_modules() {
+        let mut map = Map::new(50, 50, 42);
+        let mut station = Station::new(&mut map);
+        station.resources.scientific_data = 1000;
+        let modules = station.get_modules_for_robot_type(RobotType::Miner);
+        let mut robot = docked_robot(&mut station, modules);
+        assert_eq!(robot.role(), RobotType::Miner);
+
+        station
+            .refit_robot(&mut robot, RobotType::Scientist)
+            .unwrap();
+
+        assert_eq!(robot.role(), RobotType::Scientist);
+        assert_eq!(robot.modules.len(), 2);
+        // Le scanner a été réutilisé, la foreuse et la soute restent en stockage
+        assert_eq!(
+            station.module_storage,
+            vec![
+                HardwareModule::DeepDrill { mining_speed: 2.0 },
+                HardwareModule::CargoHold { capacity: 100 },
+            ]
+        );
+    }
+}
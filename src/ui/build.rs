@@ -0,0 +1,150 @@
+use crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Gauge, Paragraph, Widget, Wrap},
+};
+
+use crate::simulation::{
+    command::{CommandError, UserCommand},
+    Simulation,
+};
+use crate::station::{RobotType, Station};
+
+/// État du menu de construction des robots
+#[derive(Debug, Default)]
+pub struct BuildPanel {
+    selected_type: usize,
+    message: Option<String>,
+}
+
+impl BuildPanel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn handle_key(&mut self, key: KeyCode, sim: &mut Simulation) {
+        let robot_type = RobotType::ALL[self.selected_type];
+        match key {
+            KeyCode::Up => self.selected_type = self.selected_type.saturating_sub(1),
+            KeyCode::Down => {
+                self.selected_type = (self.selected_type + 1).min(RobotType::ALL.len() - 1)
+            }
+            KeyCode::Enter => {
+                self.message = Some(match sim.apply(UserCommand::QueueRobot(robot_type)) {
+                    Ok(()) => format!("Queued {}", robot_type.name()),
+                    Err(error) => format!("Refused: {error}"),
+                });
+            }
+            _ => {}
+        }
+    }
+
+    pub fn report_refusal(&mut self, error: &CommandError) {
+        self.message = Some(format!("Refused: {error}"));
+    }
+}
+
+pub struct BuildWidget<'a> {
+    panel: &'a BuildPanel,
+    station: &'a Station,
+}
+
+impl<'a> BuildWidget<'a> {
+    pub fn new(panel: &'a BuildPanel, station: &'a Station) -> Self {
+        Self { panel, station }
+    }
+}
+
+impl Widget for BuildWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = Block::default().title("Build").borders(Borders::ALL);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(3), Constraint::Length(1)].as_ref())
+            .split(inner);
+
+        let queue = &self.station.production_queue;
+        let mut lines = vec![
+            Line::from(Span::styled(
+                "↑↓ select  Enter queue build",
+                Style::default().fg(Color::DarkGray),
+            )),
+            Line::from(format!(
+                "Robots: {} built, {} ordered, max {}",
+                self.station.robots.len(),
+                queue.pending(),
+                self.station.max_robots
+            )),
+            Line::from(""),
+        ];
+
+        for (index, robot_type) in RobotType::ALL.iter().enumerate() {
+            let (energy, minerals) = self.station.production_costs.for_robot(*robot_type);
+            let (status, color) = match self.station.check_build(*robot_type) {
+                Ok(()) => ("affordable".to_string(), Color::Green),
+                Err(error) => (error.to_string(), Color::Red),
+            };
+
+            let mut style = Style::default();
+            if index == self.panel.selected_type {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+
+            lines.push(Line::from(Span::styled(
+                format!(
+                    "{} - {energy} energy, {minerals} minerals, {} ticks",
+                    robot_type.name(),
+                    queue.get_production_time(*robot_type)
+                ),
+                style,
+            )));
+            lines.push(Line::from(Span::styled(
+                format!("  {status}"),
+                Style::default().fg(color),
+            )));
+        }
+
+        lines.push(Line::from(""));
+        let queued: Vec<&str> = queue
+            .queued()
+            .iter()
+            .map(|robot_type| robot_type.name())
+            .collect();
+        lines.push(Line::from(if queued.is_empty() {
+            "Queue empty".to_string()
+        } else {
+            format!("Queue: {}", queued.join(", "))
+        }));
+
+        if let Some(message) = &self.panel.message {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                message.clone(),
+                Style::default().fg(Color::Magenta),
+            )));
+        }
+
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: true })
+            .render(chunks[0], buf);
+
+        let (label, ratio) = match (queue.current_production(), queue.production_progress()) {
+            (Some(robot_type), Some(progress)) => (
+                format!("{} {:.0}%", robot_type.name(), progress * 100.0),
+                progress as f64,
+            ),
+            _ => ("Idle".to_string(), 0.0),
+        };
+        Gauge::default()
+            .gauge_style(Style::default().fg(Color::Cyan).bg(Color::Black))
+            .label(label)
+            .ratio(ratio.clamp(0.0, 1.0))
+            .render(chunks[1], buf);
+    }
+}
@@ -0,0 +1,99 @@
+use crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Widget},
+};
+
+use crate::simulation::events::{EventLog, SimEvent};
+
+/// Nombre de lignes parcourues par PageUp et PageDown
+const PAGE_LINES: usize = 10;
+
+/// État du journal d'événements : décalage depuis l'événement le plus récent
+#[derive(Debug, Default)]
+pub struct EventsPanel {
+    scroll: usize,
+}
+
+impl EventsPanel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn handle_key(&mut self, key: KeyCode, log: &EventLog) {
+        let oldest = log.recent().len().saturating_sub(1);
+        self.scroll = match key {
+            KeyCode::Up => self.scroll + 1,
+            KeyCode::Down => self.scroll.saturating_sub(1),
+            KeyCode::PageUp => self.scroll + PAGE_LINES,
+            KeyCode::PageDown => self.scroll.saturating_sub(PAGE_LINES),
+            KeyCode::Home => oldest,
+            KeyCode::End => 0,
+            _ => self.scroll,
+        }
+        .min(oldest);
+    }
+}
+
+pub struct EventsWidget<'a> {
+    panel: &'a EventsPanel,
+    log: &'a EventLog,
+}
+
+impl<'a> EventsWidget<'a> {
+    pub fn new(panel: &'a EventsPanel, log: &'a EventLog) -> Self {
+        Self { panel, log }
+    }
+}
+
+fn event_color(event: &SimEvent) -> Color {
+    match event {
+        SimEvent::RobotSpawned { .. } | SimEvent::ProductionCompleted { .. } => Color::Cyan,
+        SimEvent::StateChanged { .. } => Color::Gray,
+        SimEvent::ResourceDiscovered { .. } => Color::Green,
+        SimEvent::ResourceConsumed { .. } => Color::Blue,
+        SimEvent::ResourceDepleted { .. } => Color::Magenta,
+        SimEvent::RobotDocked { .. } => Color::Yellow,
+        SimEvent::MessageDropped { .. } => Color::Red,
+    }
+}
+
+impl Widget for EventsWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let title = if self.panel.scroll > 0 {
+            format!("Events (-{})", self.panel.scroll)
+        } else {
+            "Events".to_string()
+        };
+        let block = Block::default().title(title).borders(Borders::ALL);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let mut lines = vec![Line::from(Span::styled(
+            "↑↓ PgUp PgDn scroll  End follow",
+            Style::default().fg(Color::DarkGray),
+        ))];
+
+        // Les événements les plus récents en bas, comme un terminal
+        let visible = (inner.height as usize).saturating_sub(lines.len());
+        let shown: Vec<Line> = self
+            .log
+            .recent()
+            .rev()
+            .skip(self.panel.scroll)
+            .take(visible)
+            .map(|record| {
+                Line::from(Span::styled(
+                    record.to_string(),
+                    Style::default().fg(event_color(&record.event)),
+                ))
+            })
+            .collect();
+        lines.extend(shown.into_iter().rev());
+
+        Paragraph::new(lines).render(inner, buf);
+    }
+}
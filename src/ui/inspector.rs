@@ -0,0 +1,304 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Widget, Wrap},
+};
+
+use crate::{
+    robot::{Position, Robot, RobotOrder, State},
+    simulation::command::CommandError,
+    station::Station,
+};
+
+/// Largeur de la jauge d'énergie, en caractères
+const ENERGY_BAR_WIDTH: usize = 20;
+/// Nombre d'étapes du chemin prévu affichées en clair
+const PATH_PREVIEW: usize = 5;
+
+/// Robot sélectionné, désigné par son identifiant pour survivre aux
+/// créations et disparitions de robots, et résultat du dernier ordre donné
+#[derive(Debug, Default)]
+pub struct InspectorPanel {
+    selected: Option<u32>,
+    message: Option<String>,
+}
+
+impl InspectorPanel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn selected(&self) -> Option<u32> {
+        self.selected
+    }
+
+    pub fn select(&mut self, robot_id: u32) {
+        self.selected = Some(robot_id);
+        self.message = None;
+    }
+
+    pub fn report(&mut self, result: Result<(), CommandError>) {
+        self.message = Some(match result {
+            Ok(()) => "Order given".to_string(),
+            Err(error) => format!("Refused: {error}"),
+        });
+    }
+
+    pub fn selected_robot<'a>(&self, robots: &'a [Robot]) -> Option<&'a Robot> {
+        let selected = self.selected?;
+        robots.iter().find(|robot| robot.id == selected)
+    }
+
+    /// Passe au robot suivant, ou au précédent, en bouclant sur la flotte
+    pub fn cycle(&mut self, robots: &[Robot], forward: bool) {
+        let current = self
+            .selected
+            .and_then(|selected| robots.iter().position(|robot| robot.id == selected));
+        let next = match (current, forward) {
+            _ if robots.is_empty() => None,
+            (None, _) => Some(0),
+            (Some(index), true) => Some((index + 1) % robots.len()),
+            (Some(index), false) => Some((index + robots.len() - 1) % robots.len()),
+        };
+        self.selected = next.map(|index| robots[index].id);
+        self.message = None;
+    }
+}
+
+pub struct InspectorWidget<'a> {
+    panel: &'a InspectorPanel,
+    station: &'a Station,
+    robots: &'a [Robot],
+}
+
+impl<'a> InspectorWidget<'a> {
+    pub fn new(panel: &'a InspectorPanel, station: &'a Station, robots: &'a [Robot]) -> Self {
+        Self {
+            panel,
+            station,
+            robots,
+        }
+    }
+}
+
+fn describe_position(position: &Position) -> String {
+    format!("({}, {})", position.x, position.y)
+}
+
+fn describe_path(path: &[Position]) -> String {
+    let mut steps: Vec<String> = path
+        .iter()
+        .take(PATH_PREVIEW)
+        .map(describe_position)
+        .collect();
+    if path.len() > PATH_PREVIEW {
+        steps.push(format!("… {} more", path.len() - PATH_PREVIEW));
+    }
+    steps.join(" → ")
+}
+
+fn describe_state(state: &State) -> Vec<String> {
+    match state {
+        State::Idle => vec!["idle".to_string()],
+        State::Exploring { target, path } => vec![
+            format!("exploring towards {}", describe_position(target)),
+            format!("path ({} steps): {}", path.len(), describe_path(path)),
+        ],
+        State::Collecting {
+            resource_type,
+            target,
+        } => vec![format!(
+            "collecting {resource_type:?} at {}",
+            describe_position(target)
+        )],
+        State::Analyzing { sample } => vec![format!(
+            "analyzing {} ({:.2})",
+            sample.data_type, sample.value
+        )],
+        State::Returning {
+            base_position,
+            path,
+        } => vec![
+            format!("returning to {}", describe_position(base_position)),
+            format!("path ({} steps): {}", path.len(), describe_path(path)),
+        ],
+    }
+}
+
+fn energy_bar(energy: f32, capacity: f32) -> (String, Color) {
+    let ratio = if capacity > 0.0 {
+        (energy / capacity).clamp(0.0, 1.0)
+    } else {
+        0.0
+    };
+    let filled = (ratio * ENERGY_BAR_WIDTH as f32).round() as usize;
+    let color = if ratio > 0.5 {
+        Color::Green
+    } else if ratio > 0.2 {
+        Color::Yellow
+    } else {
+        Color::Red
+    };
+    (
+        format!(
+            "{}{}",
+            "█".repeat(filled),
+            "░".repeat(ENERGY_BAR_WIDTH - filled)
+        ),
+        color,
+    )
+}
+
+impl Widget for InspectorWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let mut lines = vec![Line::from(Span::styled(
+            "Tab/Shift+Tab or click select robot",
+            Style::default().fg(Color::DarkGray),
+        ))];
+
+        let Some(robot) = self.panel.selected_robot(self.robots) else {
+            lines.push(Line::from(""));
+            lines.push(Line::from("No robot selected"));
+            Paragraph::new(lines)
+                .block(Block::default().title("Inspector").borders(Borders::ALL))
+                .render(area, buf);
+            return;
+        };
+
+        let (bar, bar_color) = energy_bar(robot.energy, robot.battery_capacity);
+        let inventory = &robot.inventory;
+        lines.extend([
+            Line::from(""),
+            Line::from(vec![
+                Span::styled(
+                    format!("Robot #{}", robot.id),
+                    Style::default().fg(Color::Magenta),
+                ),
+                Span::raw(format!(" - {}", robot.role().name())),
+            ]),
+            Line::from(format!(
+                "Position {} - {} tiles from station",
+                describe_position(&robot.position),
+                robot.position.distance_to(&self.station.position)
+            )),
+            Line::from(vec![
+                Span::raw("Energy "),
+                Span::styled(bar, Style::default().fg(bar_color)),
+                Span::raw(format!(
+                    " {:.0}/{:.0}",
+                    robot.energy, robot.battery_capacity
+                )),
+            ]),
+            Line::from(
+                match robot.estimated_return_cost(&self.station.energy_model) {
+                    Some(cost) => format!("Return to station needs ~{cost:.0} energy"),
+                    None => "Return cost not estimated yet".to_string(),
+                },
+            ),
+            Line::from(""),
+            Line::from(Span::styled("State", Style::default().fg(Color::Cyan))),
+        ]);
+        lines.extend(
+            describe_state(&robot.state)
+                .into_iter()
+                .map(|line| Line::from(format!("  {line}"))),
+        );
+        if let Some(order) = robot.order {
+            let (action, target) = match order {
+                RobotOrder::GoTo(target) => ("go to", target),
+                RobotOrder::Harvest(target) => ("harvest at", target),
+            };
+            lines.push(Line::from(Span::styled(
+                format!("  manual order: {action} {}", describe_position(&target)),
+                Style::default().fg(Color::Yellow),
+            )));
+        }
+
+        lines.extend([
+            Line::from(""),
+            Line::from(Span::styled(
+                format!(
+                    "Inventory ({}/{})",
+                    robot.cargo_load(),
+                    robot.cargo_capacity()
+                ),
+                Style::default().fg(Color::Cyan),
+            )),
+            Line::from(format!(
+                "  Energy {} | Minerals {} | Scientific {}",
+                inventory.energy, inventory.minerals, inventory.scientific_data
+            )),
+            Line::from(""),
+            Line::from(Span::styled(
+                format!("Modules ({})", robot.modules.len()),
+                Style::default().fg(Color::Cyan),
+            )),
+        ]);
+        lines.extend(
+            robot
+                .modules
+                .iter()
+                .map(|module| Line::from(format!("  {}", module.describe()))),
+        );
+
+        if let Some(message) = &self.panel.message {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                message.clone(),
+                Style::default().fg(Color::Magenta),
+            )));
+        }
+
+        Paragraph::new(lines)
+            .block(Block::default().title("Inspector").borders(Borders::ALL))
+            .wrap(Wrap { trim: true })
+            .render(area, buf);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn robots(ids: &[u32]) -> Vec<Robot> {
+        ids.iter()
+            .map(|&id| {
+                let mut robot = Robot::new(Position { x: 0, y: 0 }, vec![]);
+                robot.id = id;
+                robot
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_cycle_wraps_around_the_fleet() {
+        let robots = robots(&[4, 7, 9]);
+        let mut panel = InspectorPanel::new();
+
+        panel.cycle(&robots, true);
+        assert_eq!(panel.selected(), Some(4));
+        panel.cycle(&robots, false);
+        assert_eq!(panel.selected(), Some(9));
+        panel.cycle(&robots, true);
+        assert_eq!(panel.selected(), Some(4));
+        panel.cycle(&robots, true);
+        assert_eq!(panel.selected(), Some(7));
+
+        // Un robot disparu est remplacé par le premier de la flotte
+        panel.select(12);
+        panel.cycle(&robots, true);
+        assert_eq!(panel.selected(), Some(4));
+    }
+
+    #[test]
+    fn test_cycle_without_robots_clears_the_selection() {
+        let mut panel = InspectorPanel::new();
+        panel.select(3);
+        panel.cycle(&[], true);
+        assert_eq!(panel.selected(), None);
+        panel.cycle(&[], false);
+        assert_eq!(panel.selected(), None);
+    }
+}
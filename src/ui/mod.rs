@@ -0,0 +1,20 @@
+pub mod build;
+pub mod events;
+pub mod inspector;
+pub mod orders;
+pub mod research;
+pub mod stats;
+pub mod tile;
+pub mod workshop;
+
+/// Panneau latéral affiché à droite de la carte
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SidePanel {
+    Workshop,
+    Research,
+    Events,
+    Inspector,
+    Tile,
+    Build,
+    Statistics,
+}
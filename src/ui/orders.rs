@@ -0,0 +1,128 @@
+use crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::Line,
+    widgets::{Block, Borders, Clear, Paragraph, Widget},
+};
+
+use crate::{robot::Position, simulation::command::UserCommand};
+
+/// Menu contextuel ouvert par un clic droit sur la carte, proposant les
+/// ordres manuels possibles pour le robot sélectionné
+#[derive(Debug, Clone)]
+pub struct OrderMenu {
+    robot_id: u32,
+    target: Position,
+    can_harvest: bool,
+    /// Coin supérieur gauche du menu à l'écran
+    column: u16,
+    row: u16,
+    selected: usize,
+}
+
+impl OrderMenu {
+    pub fn new(robot_id: u32, target: Position, can_harvest: bool, column: u16, row: u16) -> Self {
+        Self {
+            robot_id,
+            target,
+            can_harvest,
+            column,
+            row,
+            selected: 0,
+        }
+    }
+
+    fn options(&self) -> Vec<(&'static str, UserCommand)> {
+        let (robot_id, target) = (self.robot_id, self.target);
+        let mut options = vec![("Go here", UserCommand::GoTo { robot_id, target })];
+        if self.can_harvest {
+            options.push(("Harvest this", UserCommand::Harvest { robot_id, target }));
+        }
+        options
+    }
+
+    /// Zone occupée par le menu, bornée par l'écran
+    pub fn area(&self, screen: Rect) -> Rect {
+        let width = 18;
+        let height = self.options().len() as u16 + 2;
+        Rect {
+            x: self.column.min(screen.right().saturating_sub(width)),
+            y: self.row.min(screen.bottom().saturating_sub(height)),
+            width: width.min(screen.width),
+            height: height.min(screen.height),
+        }
+    }
+
+    /// Ordre choisi par un clic, `None` si le clic tombe hors des options
+    pub fn command_at(&self, screen: Rect, column: u16, row: u16) -> Option<UserCommand> {
+        let area = self.area(screen);
+        if column <= area.x || column + 1 >= area.right() || row <= area.y {
+            return None;
+        }
+        let index = (row - area.y - 1) as usize;
+        self.options()
+            .into_iter()
+            .nth(index)
+            .map(|(_, command)| command)
+    }
+
+    /// Navigation au clavier ; renvoie l'ordre validé par Entrée
+    pub fn handle_key(&mut self, key: KeyCode) -> Option<UserCommand> {
+        let count = self.options().len();
+        match key {
+            KeyCode::Up => self.selected = self.selected.saturating_sub(1),
+            KeyCode::Down => self.selected = (self.selected + 1).min(count - 1),
+            KeyCode::Enter => {
+                return self
+                    .options()
+                    .into_iter()
+                    .nth(self.selected)
+                    .map(|(_, command)| command)
+            }
+            _ => {}
+        }
+        None
+    }
+}
+
+pub struct OrderMenuWidget<'a> {
+    menu: &'a OrderMenu,
+}
+
+impl<'a> OrderMenuWidget<'a> {
+    pub fn new(menu: &'a OrderMenu) -> Self {
+        Self { menu }
+    }
+}
+
+impl Widget for OrderMenuWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let area = self.menu.area(area);
+        let lines: Vec<Line> = self
+            .menu
+            .options()
+            .into_iter()
+            .enumerate()
+            .map(|(index, (label, _))| {
+                let style = if index == self.menu.selected {
+                    Style::default().add_modifier(Modifier::REVERSED)
+                } else {
+                    Style::default()
+                };
+                Line::styled(label, style)
+            })
+            .collect();
+
+        Clear.render(area, buf);
+        Paragraph::new(lines)
+            .block(
+                Block::default()
+                    .title(format!("#{}", self.menu.robot_id))
+                    .borders(Borders::ALL)
+                    .border_style(Style::default().fg(Color::Yellow)),
+            )
+            .render(area, buf);
+    }
+}
@@ -0,0 +1,136 @@
+use crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Gauge, Paragraph, Widget, Wrap},
+};
+
+use crate::simulation::{command::UserCommand, Simulation};
+use crate::station::{
+    research::{ResearchError, ResearchTopic},
+    Station,
+};
+
+/// État de l'interface de recherche
+#[derive(Debug, Default)]
+pub struct ResearchPanel {
+    selected_topic: usize,
+    message: Option<String>,
+}
+
+impl ResearchPanel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn handle_key(&mut self, key: KeyCode, sim: &mut Simulation) {
+        match key {
+            KeyCode::Up => self.selected_topic = self.selected_topic.saturating_sub(1),
+            KeyCode::Down => {
+                self.selected_topic = (self.selected_topic + 1).min(ResearchTopic::ALL.len() - 1)
+            }
+            KeyCode::Enter => {
+                let topic = ResearchTopic::ALL[self.selected_topic];
+                self.message = Some(match sim.apply(UserCommand::StartResearch(topic)) {
+                    Ok(()) => format!("Started {}", topic.name()),
+                    Err(error) => format!("Refused: {error}"),
+                });
+            }
+            _ => {}
+        }
+    }
+}
+
+pub struct ResearchWidget<'a> {
+    panel: &'a ResearchPanel,
+    station: &'a Station,
+}
+
+impl<'a> ResearchWidget<'a> {
+    pub fn new(panel: &'a ResearchPanel, station: &'a Station) -> Self {
+        Self { panel, station }
+    }
+}
+
+impl Widget for ResearchWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = Block::default().title("Research").borders(Borders::ALL);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints([Constraint::Min(3), Constraint::Length(1)].as_ref())
+            .split(inner);
+
+        let lab = &self.station.research;
+        let mut lines = vec![
+            Line::from(Span::styled(
+                "↑↓ select  Enter start research",
+                Style::default().fg(Color::DarkGray),
+            )),
+            Line::from(""),
+        ];
+
+        for (index, topic) in ResearchTopic::ALL.iter().enumerate() {
+            let (status, color) = if lab.is_completed(*topic) {
+                ("done", Color::Green)
+            } else if lab.current() == Some(*topic) {
+                ("running", Color::Yellow)
+            } else if matches!(
+                lab.check_can_start(*topic),
+                Ok(()) | Err(ResearchError::LabBusy)
+            ) {
+                ("available", Color::White)
+            } else {
+                ("locked", Color::DarkGray)
+            };
+
+            let mut style = Style::default().fg(color);
+            if index == self.panel.selected_topic {
+                style = style.add_modifier(Modifier::REVERSED);
+            }
+
+            lines.push(Line::from(Span::styled(
+                format!(
+                    "{} [{status}] - {} sci, {} ticks",
+                    topic.name(),
+                    topic.cost(),
+                    topic.duration()
+                ),
+                style,
+            )));
+            lines.push(Line::from(Span::styled(
+                format!("  {}", topic.description()),
+                Style::default().fg(Color::DarkGray),
+            )));
+        }
+
+        if let Some(message) = &self.panel.message {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                message.clone(),
+                Style::default().fg(Color::Magenta),
+            )));
+        }
+
+        Paragraph::new(lines)
+            .wrap(Wrap { trim: true })
+            .render(chunks[0], buf);
+
+        let (label, ratio) = match (lab.current(), lab.progress()) {
+            (Some(topic), Some(progress)) => (
+                format!("{} {:.0}%", topic.name(), progress * 100.0),
+                progress as f64,
+            ),
+            _ => ("Idle".to_string(), 0.0),
+        };
+        Gauge::default()
+            .gauge_style(Style::default().fg(Color::Green).bg(Color::Black))
+            .label(label)
+            .ratio(ratio.clamp(0.0, 1.0))
+            .render(chunks[1], buf);
+    }
+}
@@ -0,0 +1,191 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::{Constraint, Direction, Layout, Rect},
+    style::{Color, Style},
+    symbols,
+    text::{Line, Span},
+    widgets::{Axis, Block, Borders, Chart, Dataset, GraphType, Paragraph, Sparkline, Widget},
+};
+
+use crate::robot::Resources;
+use crate::station::resources::FLOW_WINDOW_TICKS;
+use crate::station::Station;
+
+/// Statistiques de la station : évolution du stock, revenus et dépenses
+pub struct StatsWidget<'a> {
+    station: &'a Station,
+    tick: u64,
+}
+
+impl<'a> StatsWidget<'a> {
+    pub fn new(station: &'a Station, tick: u64) -> Self {
+        Self { station, tick }
+    }
+}
+
+fn stock_series(stock: &[(u64, Resources)], field: fn(&Resources) -> u32) -> Vec<(f64, f64)> {
+    stock
+        .iter()
+        .map(|(tick, resources)| (*tick as f64, f64::from(field(resources))))
+        .collect()
+}
+
+fn rate_sparkline<'a>(title: String, data: &'a [u64], color: Color) -> Sparkline<'a> {
+    Sparkline::default()
+        .block(Block::default().title(title))
+        .data(data)
+        .style(Style::default().fg(color))
+}
+
+impl Widget for StatsWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let block = Block::default().title("Statistics").borders(Borders::ALL);
+        let inner = block.inner(area);
+        block.render(area, buf);
+
+        let chunks = Layout::default()
+            .direction(Direction::Vertical)
+            .constraints(
+                [
+                    Constraint::Min(8),
+                    Constraint::Length(3),
+                    Constraint::Length(3),
+                    Constraint::Length(3),
+                    Constraint::Length(3),
+                    Constraint::Length(2),
+                ]
+                .as_ref(),
+            )
+            .split(inner);
+
+        let history = &self.station.history;
+
+        // Stock de la station au fil des relevés
+        let stock: Vec<(u64, Resources)> = history.stock.iter().cloned().collect();
+        let energy = stock_series(&stock, |resources| resources.energy);
+        let minerals = stock_series(&stock, |resources| resources.minerals);
+        let science = stock_series(&stock, |resources| resources.scientific_data);
+        let first_tick = stock.first().map_or(0, |(tick, _)| *tick);
+        let last_tick = self.tick.max(first_tick + 1);
+        let max_stock = stock
+            .iter()
+            .map(|(_, resources)| {
+                resources
+                    .energy
+                    .max(resources.minerals)
+                    .max(resources.scientific_data)
+            })
+            .max()
+            .unwrap_or(0)
+            .max(1);
+
+        let datasets = vec![
+            Dataset::default()
+                .name("energy")
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(Color::Yellow))
+                .data(&energy),
+            Dataset::default()
+                .name("minerals")
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(Color::Magenta))
+                .data(&minerals),
+            Dataset::default()
+                .name("science")
+                .marker(symbols::Marker::Braille)
+                .graph_type(GraphType::Line)
+                .style(Style::default().fg(Color::Cyan))
+                .data(&science),
+        ];
+        let axis_style = Style::default().fg(Color::DarkGray);
+        Chart::new(datasets)
+            .block(Block::default().title("Station stock"))
+            .x_axis(
+                Axis::default()
+                    .style(axis_style)
+                    .bounds([first_tick as f64, last_tick as f64])
+                    .labels(vec![
+                        Span::raw(first_tick.to_string()),
+                        Span::raw(last_tick.to_string()),
+                    ]),
+            )
+            .y_axis(
+                Axis::default()
+                    .style(axis_style)
+                    .bounds([0.0, f64::from(max_stock)])
+                    .labels(vec![Span::raw("0"), Span::raw(max_stock.to_string())]),
+            )
+            .render(chunks[0], buf);
+
+        // Une barre par fenêtre, autant que la largeur disponible
+        let windows = inner.width as usize;
+        let collected = history.collected_per_window(windows, self.tick);
+        let spent = history.spent_per_window(windows, self.tick);
+        let series = |buckets: &[Resources], field: fn(&Resources) -> u32| -> Vec<u64> {
+            buckets
+                .iter()
+                .map(|resources| u64::from(field(resources)))
+                .collect()
+        };
+        let energy_income = series(&collected, |resources| resources.energy);
+        let mineral_income = series(&collected, |resources| resources.minerals);
+        let science_income = series(&collected, |resources| resources.scientific_data);
+        let spending = series(&spent, |resources| {
+            resources.energy + resources.minerals + resources.scientific_data
+        });
+        let latest = |data: &[u64]| data.last().copied().unwrap_or(0);
+
+        rate_sparkline(
+            format!(
+                "Energy income / {FLOW_WINDOW_TICKS} ticks: {}",
+                latest(&energy_income)
+            ),
+            &energy_income,
+            Color::Yellow,
+        )
+        .render(chunks[1], buf);
+        rate_sparkline(
+            format!(
+                "Mineral income / {FLOW_WINDOW_TICKS} ticks: {}",
+                latest(&mineral_income)
+            ),
+            &mineral_income,
+            Color::Magenta,
+        )
+        .render(chunks[2], buf);
+        rate_sparkline(
+            format!(
+                "Science income / {FLOW_WINDOW_TICKS} ticks: {}",
+                latest(&science_income)
+            ),
+            &science_income,
+            Color::Cyan,
+        )
+        .render(chunks[3], buf);
+        rate_sparkline(
+            format!(
+                "Spending (all resources) / {FLOW_WINDOW_TICKS} ticks: {}",
+                latest(&spending)
+            ),
+            &spending,
+            Color::Red,
+        )
+        .render(chunks[4], buf);
+
+        let total_collected = history.total_collected();
+        let total_spent = history.total_spent();
+        Paragraph::new(vec![
+            Line::from(format!(
+                "Collected: {} energy, {} minerals, {} science",
+                total_collected.energy, total_collected.minerals, total_collected.scientific_data
+            )),
+            Line::from(format!(
+                "Spent: {} energy, {} minerals, {} science",
+                total_spent.energy, total_spent.minerals, total_spent.scientific_data
+            )),
+        ])
+        .render(chunks[5], buf);
+    }
+}
@@ -0,0 +1,98 @@
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Widget, Wrap},
+};
+
+use crate::{
+    map::{Map, Tile},
+    robot::Position,
+    station::sync::MapKnowledge,
+};
+
+/// Contenu d'une case : le terrain réel et ce qu'en sait la station
+pub struct TileInfoWidget<'a> {
+    position: Position,
+    map: &'a Map,
+    knowledge: &'a MapKnowledge,
+}
+
+impl<'a> TileInfoWidget<'a> {
+    pub fn new(position: Position, map: &'a Map, knowledge: &'a MapKnowledge) -> Self {
+        Self {
+            position,
+            map,
+            knowledge,
+        }
+    }
+}
+
+fn describe_tile(tile: &Tile) -> String {
+    let base = |is_base: bool| if is_base { " (base)" } else { "" };
+    match tile {
+        Tile::Empty => "empty".to_string(),
+        Tile::Obstacle => "obstacle".to_string(),
+        Tile::Energy(energy) => format!("energy {}{}", energy.amount, base(energy.is_base)),
+        Tile::Mineral(mineral) => format!("minerals {}{}", mineral.amount, base(mineral.is_base)),
+        Tile::ScientificPoint(point) => {
+            format!("scientific data {}{}", point.value, base(point.is_base))
+        }
+        Tile::Station => "station".to_string(),
+        Tile::Unknown => "unknown".to_string(),
+    }
+}
+
+impl Widget for TileInfoWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let heading = Style::default().fg(Color::Cyan);
+        let Position { x, y } = self.position;
+        let mut lines = vec![
+            Line::from(Span::styled(
+                "Right-click with a robot selected to give an order",
+                Style::default().fg(Color::DarkGray),
+            )),
+            Line::from(""),
+            Line::from(format!("Tile ({x}, {y})")),
+            Line::from(vec![
+                Span::styled("Actual: ", heading),
+                Span::raw(
+                    self.map
+                        .get_tile(x, y)
+                        .map_or_else(|| "outside the map".to_string(), describe_tile),
+                ),
+            ]),
+            Line::from(""),
+            Line::from(Span::styled("Station knowledge", heading)),
+        ];
+
+        match self.knowledge.get_tile(x, y) {
+            None => lines.push(Line::from("  never explored")),
+            Some(known) => {
+                lines.push(Line::from(format!("  {}", describe_tile(&known.tile))));
+                lines.push(Line::from(format!("  version {}", known.version)));
+                lines.push(Line::from(format!(
+                    "  discovered by {}",
+                    known
+                        .explorer_id
+                        .map_or_else(|| "unknown".to_string(), |id| format!("robot #{id}"))
+                )));
+                for (robot_id, observation) in &known.observations {
+                    lines.push(Line::from(format!(
+                        "  #{robot_id} saw {} at tick {} ({} times, eff {:.2})",
+                        describe_tile(&observation.tile),
+                        observation.tick,
+                        observation.count,
+                        observation.sensor_efficiency
+                    )));
+                }
+            }
+        }
+
+        Paragraph::new(lines)
+            .block(Block::default().title("Tile").borders(Borders::ALL))
+            .wrap(Wrap { trim: true })
+            .render(area, buf);
+    }
+}
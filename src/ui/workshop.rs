@@ -0,0 +1,232 @@
+use crossterm::event::KeyCode;
+use ratatui::{
+    buffer::Buffer,
+    layout::Rect,
+    style::{Color, Modifier, Style},
+    text::{Line, Span},
+    widgets::{Block, Borders, Paragraph, Widget, Wrap},
+};
+
+use crate::{
+    robot::{HardwareModule, Robot},
+    simulation::{command::UserCommand, Simulation},
+    station::{RobotType, Station},
+};
+
+/// État de l'interface de l'atelier (sélections et dernier résultat)
+#[derive(Debug, Default)]
+pub struct WorkshopPanel {
+    selected_robot: usize,
+    selected_module: usize,
+    selected_storage: usize,
+    message: Option<String>,
+}
+
+impl WorkshopPanel {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn handle_key(&mut self, key: KeyCode, sim: &mut Simulation) {
+        if sim.robots.is_empty() {
+            self.message = Some("No robots to service".to_string());
+            return;
+        }
+        self.selected_robot = self.selected_robot.min(sim.robots.len() - 1);
+        let robot = &sim.robots[self.selected_robot];
+        let robot_id = robot.id;
+
+        match key {
+            KeyCode::Up => {
+                self.selected_robot = self.selected_robot.saturating_sub(1);
+                self.selected_module = 0;
+            }
+            KeyCode::Down => {
+                self.selected_robot = (self.selected_robot + 1).min(sim.robots.len() - 1);
+                self.selected_module = 0;
+            }
+            KeyCode::Left => self.selected_module = self.selected_module.saturating_sub(1),
+            KeyCode::Right => self.selected_module += 1,
+            KeyCode::Char('[') => self.selected_storage = self.selected_storage.saturating_sub(1),
+            KeyCode::Char(']') => self.selected_storage += 1,
+            KeyCode::Enter => {
+                let success = if robot.service_requested {
+                    "Robot released"
+                } else {
+                    "Robot recalled for service"
+                };
+                let result = sim.apply(UserCommand::ToggleService { robot_id });
+                self.report(result, success);
+            }
+            KeyCode::Char('u') => {
+                let result = sim.apply(UserCommand::UpgradeModule {
+                    robot_id,
+                    module: self.selected_module,
+                });
+                self.report(result, "Module upgraded");
+            }
+            KeyCode::Char('x') => {
+                let result = sim.apply(UserCommand::RemoveModule {
+                    robot_id,
+                    module: self.selected_module,
+                });
+                self.report(result, "Module moved to storage");
+            }
+            KeyCode::Char('i') => {
+                let result = sim.apply(UserCommand::InstallModule {
+                    robot_id,
+                    storage: self.selected_storage,
+                });
+                self.report(result, "Module installed");
+            }
+            KeyCode::Char('f') => {
+                let result = sim.apply(UserCommand::FabricateModule {
+                    robot_id,
+                    module: HardwareModule::SignalRelay { range: 30 },
+                });
+                self.report(result, "Signal relay fitted");
+            }
+            KeyCode::Char(c @ '1'..='4') => {
+                let robot_type = match c {
+                    '1' => RobotType::Explorer,
+                    '2' => RobotType::EnergyCollector,
+                    '3' => RobotType::Miner,
+                    _ => RobotType::Scientist,
+                };
+                let result = sim.apply(UserCommand::Refit {
+                    robot_id,
+                    robot_type,
+                });
+                self.report(result, "Robot refitted");
+            }
+            _ => {}
+        }
+
+        let robot = &sim.robots[self.selected_robot];
+        self.selected_module = self
+            .selected_module
+            .min(robot.modules.len().saturating_sub(1));
+        self.selected_storage = self
+            .selected_storage
+            .min(sim.station.module_storage.len().saturating_sub(1));
+    }
+
+    fn report<E: std::fmt::Display>(&mut self, result: Result<(), E>, success: &str) {
+        self.message = Some(match result {
+            Ok(()) => success.to_string(),
+            Err(error) => format!("Refused: {error}"),
+        });
+    }
+}
+
+pub struct WorkshopWidget<'a> {
+    panel: &'a WorkshopPanel,
+    station: &'a Station,
+    robots: &'a [Robot],
+}
+
+impl<'a> WorkshopWidget<'a> {
+    pub fn new(panel: &'a WorkshopPanel, station: &'a Station, robots: &'a [Robot]) -> Self {
+        Self {
+            panel,
+            station,
+            robots,
+        }
+    }
+}
+
+impl Widget for WorkshopWidget<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        let selected = Style::default().add_modifier(Modifier::REVERSED);
+        let costs = &self.station.workshop_costs;
+        let mut lines = vec![
+            Line::from(Span::styled(
+                "↑↓ robot  ←→ module  [] storage",
+                Style::default().fg(Color::DarkGray),
+            )),
+            Line::from(Span::styled(
+                "Enter recall/release  u upgrade  x remove  i install  f relay  1-4 refit",
+                Style::default().fg(Color::DarkGray),
+            )),
+            Line::from(format!(
+                "Costs (min/sci) - install {:?} upgrade {:?} new {:?}",
+                costs.install, costs.upgrade, costs.fabrication
+            )),
+            Line::from(""),
+        ];
+
+        for (index, robot) in self.robots.iter().enumerate() {
+            let status = if robot.is_at_station(self.station) {
+                "docked"
+            } else if robot.service_requested {
+                "en route"
+            } else {
+                "away"
+            };
+            let style = if index == self.panel.selected_robot {
+                selected
+            } else {
+                Style::default()
+            };
+            lines.push(Line::from(Span::styled(
+                format!(
+                    "Robot #{} {:?} - {status} - cargo {}/{} - {} - {}",
+                    robot.id,
+                    robot.role(),
+                    robot.cargo_load(),
+                    robot.cargo_capacity(),
+                    robot.link.describe(),
+                    self.station
+                        .fleet
+                        .liveness(robot.id)
+                        .map_or("untracked", |liveness| liveness.name())
+                ),
+                style,
+            )));
+
+            if index == self.panel.selected_robot {
+                for (slot, module) in robot.modules.iter().enumerate() {
+                    let style = if slot == self.panel.selected_module {
+                        Style::default().fg(Color::Yellow)
+                    } else {
+                        Style::default()
+                    };
+                    lines.push(Line::from(Span::styled(
+                        format!("  [{slot}] {}", module.describe()),
+                        style,
+                    )));
+                }
+            }
+        }
+
+        lines.push(Line::from(""));
+        lines.push(Line::from(format!(
+            "Storage ({} modules)",
+            self.station.module_storage.len()
+        )));
+        for (index, module) in self.station.module_storage.iter().enumerate() {
+            let style = if index == self.panel.selected_storage {
+                Style::default().fg(Color::Cyan)
+            } else {
+                Style::default()
+            };
+            lines.push(Line::from(Span::styled(
+                format!("  ({index}) {}", module.describe()),
+                style,
+            )));
+        }
+
+        if let Some(message) = &self.panel.message {
+            lines.push(Line::from(""));
+            lines.push(Line::from(Span::styled(
+                message.clone(),
+                Style::default().fg(Color::Magenta),
+            )));
+        }
+
+        Paragraph::new(lines)
+            .block(Block::default().title("Workshop").borders(Borders::ALL))
+            .wrap(Wrap { trim: true })
+            .render(area, buf);
+    }
+}